@@ -0,0 +1,17 @@
+#!/bin/sh
+# Downloads the full registries embedded at build time into src-tauri/resources.
+# Without them the build falls back to the bundled common-port and vendor subsets.
+set -eu
+
+resources="$(cd "$(dirname "$0")/../src-tauri/resources" && pwd)"
+
+echo "Fetching the IANA service name and port number registry"
+curl -fsSL -o "$resources/service-names-port-numbers.csv.tmp" \
+    https://www.iana.org/assignments/service-names-port-numbers/service-names-port-numbers.csv
+mv "$resources/service-names-port-numbers.csv.tmp" "$resources/service-names-port-numbers.csv"
+
+# Only the `(hex)` lines of the IEEE MA-L listing; the addresses that follow
+# each of them aren't needed and would triple the size
+echo "Fetching the IEEE MA-L (OUI) registry"
+curl -fsSL https://standards-oui.ieee.org/oui/oui.txt | tr -d '\r' | grep '(hex)' > "$resources/ieee-oui.txt.tmp"
+mv "$resources/ieee-oui.txt.tmp" "$resources/ieee-oui.txt"
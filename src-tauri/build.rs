@@ -1,3 +1,20 @@
+use std::path::Path;
+
+/// Registries fetched by `scripts/update-registries.sh`; when present they are
+/// embedded instead of the hand-picked subsets.
+const REGISTRIES: &[(&str, &str)] = &[
+    ("resources/service-names-port-numbers.csv", "netwatch_iana_registry"),
+    ("resources/ieee-oui.txt", "netwatch_ieee_registry"),
+];
+
 fn main() {
+    println!("cargo::rerun-if-changed=resources");
+    for (path, cfg) in REGISTRIES {
+        println!("cargo::rustc-check-cfg=cfg({})", cfg);
+        if Path::new(path).is_file() {
+            println!("cargo::rustc-cfg={}", cfg);
+        }
+    }
+
     tauri_build::build()
 }
@@ -0,0 +1,7 @@
+use crate::models::Alert;
+use crate::services::ConnectionCollector;
+
+#[tauri::command]
+pub fn get_alerts(collector: tauri::State<'_, ConnectionCollector>) -> Vec<Alert> {
+    collector.alerts().list()
+}
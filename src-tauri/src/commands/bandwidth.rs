@@ -0,0 +1,7 @@
+use crate::models::{TalkerWindow, TopTalkers};
+use crate::services::ConnectionCollector;
+
+#[tauri::command]
+pub fn get_top_talkers(window: TalkerWindow, collector: tauri::State<'_, ConnectionCollector>) -> TopTalkers {
+    collector.top_talkers(window)
+}
@@ -0,0 +1,28 @@
+use crate::models::BaselineStatus;
+use crate::services::ConnectionCollector;
+use std::path::PathBuf;
+
+#[tauri::command]
+pub fn get_baseline_status(collector: tauri::State<'_, ConnectionCollector>) -> BaselineStatus {
+    collector.baseline_status()
+}
+
+#[tauri::command]
+pub fn start_baseline_learning(collector: tauri::State<'_, ConnectionCollector>) -> Result<BaselineStatus, String> {
+    collector.start_baseline_learning()
+}
+
+#[tauri::command]
+pub fn export_baseline(collector: tauri::State<'_, ConnectionCollector>) -> Result<String, String> {
+    collector
+        .export_baseline()
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+#[tauri::command]
+pub fn import_baseline(
+    path: String,
+    collector: tauri::State<'_, ConnectionCollector>,
+) -> Result<BaselineStatus, String> {
+    collector.import_baseline(&PathBuf::from(path))
+}
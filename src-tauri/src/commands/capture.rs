@@ -0,0 +1,51 @@
+use crate::models::{CaptureDiagnostics, Connection};
+use crate::services::capture::import_capture_file;
+use crate::services::rules::load_tls_rules;
+use crate::services::ConnectionCollector;
+use std::path::PathBuf;
+use tauri::Manager;
+
+#[tauri::command]
+pub async fn import_capture(path: String, app_handle: tauri::AppHandle) -> Result<Vec<Connection>, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .unwrap_or_else(|_| PathBuf::from("./"));
+    let tls_rules = load_tls_rules(&app_data_dir);
+
+    // Parsing a large capture is CPU and disk bound; keep it off the async workers
+    tauri::async_runtime::spawn_blocking(move || import_capture_file(&PathBuf::from(path), &tls_rules))
+        .await
+        .map_err(|e| format!("Capture import task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn start_live_capture(interface: String, app_handle: tauri::AppHandle) -> Result<CaptureDiagnostics, String> {
+    // Replacing a running capture joins its thread; keep that off the async workers
+    tauri::async_runtime::spawn_blocking(move || {
+        let collector = app_handle.state::<ConnectionCollector>();
+        let live_capture = collector.live_capture();
+        live_capture.start(&interface)?;
+        Ok(live_capture.diagnostics())
+    })
+    .await
+    .map_err(|e| format!("Live capture task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn stop_live_capture(app_handle: tauri::AppHandle) -> Result<CaptureDiagnostics, String> {
+    // Joining the capture thread can wait out a socket read timeout
+    tauri::async_runtime::spawn_blocking(move || {
+        let collector = app_handle.state::<ConnectionCollector>();
+        let live_capture = collector.live_capture();
+        live_capture.stop();
+        live_capture.diagnostics()
+    })
+    .await
+    .map_err(|e| format!("Live capture task failed: {}", e))
+}
+
+#[tauri::command]
+pub fn get_capture_diagnostics(collector: tauri::State<'_, ConnectionCollector>) -> CaptureDiagnostics {
+    collector.live_capture().diagnostics()
+}
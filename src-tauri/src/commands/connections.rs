@@ -2,7 +2,6 @@ use crate::models::Connection;
 use crate::services::ConnectionCollector;
 
 #[tauri::command]
-pub async fn get_connections() -> Result<Vec<Connection>, String> {
-    let collector = ConnectionCollector::new();
+pub async fn get_connections(collector: tauri::State<'_, ConnectionCollector>) -> Result<Vec<Connection>, String> {
     collector.get_connections()
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 use crate::models::Connection;
+use crate::services::exposure::build_exposure_report;
 use serde_json;
 use std::fs::File;
 use std::io::Write;
@@ -27,7 +28,10 @@ pub async fn export_connections(
         .map_err(|e| format!("Time error: {}", e))?
         .as_secs();
 
-    let filename = format!("netwatch_connections_{}.{}", timestamp, format.to_lowercase());
+    let filename = match format.to_lowercase().as_str() {
+        "exposure" => format!("netwatch_exposure_{}.json", timestamp),
+        other => format!("netwatch_connections_{}.{}", timestamp, other),
+    };
     let filepath = app_data_dir.join(&filename);
 
     match format.to_lowercase().as_str() {
@@ -46,22 +50,20 @@ pub async fn export_connections(
                 .map_err(|e| format!("File write error: {}", e))?;
         },
         "csv" => {
-            let mut csv_data = String::from("Process,PID,Protocol,Local Address,Local Port,Remote Address,Remote Port,State,Risk,Captured At\n");
-            
-            for conn in connections {
-                csv_data.push_str(&format!(
-                    "{},{},{},{},{},{},{},{},{},{}\n",
-                    escape_csv_field(&conn.process_name),
-                    conn.pid,
-                    conn.protocol,
-                    escape_csv_field(&conn.local_addr),
-                    conn.local_port,
-                    escape_csv_field(&conn.remote_addr),
-                    conn.remote_port,
-                    conn.state,
-                    conn.risk.as_str(),
-                    conn.captured_at
-                ));
+            let header: Vec<String> = csv_columns(&Connection::default())
+                .into_iter()
+                .map(|(name, _)| escape_csv_field(name))
+                .collect();
+            let mut csv_data = header.join(",");
+            csv_data.push('\n');
+
+            for conn in &connections {
+                let row: Vec<String> = csv_columns(conn)
+                    .into_iter()
+                    .map(|(_, value)| escape_csv_field(&value))
+                    .collect();
+                csv_data.push_str(&row.join(","));
+                csv_data.push('\n');
             }
             
             File::create(&filepath)
@@ -69,14 +71,85 @@ pub async fn export_connections(
                 .write_all(csv_data.as_bytes())
                 .map_err(|e| format!("File write error: {}", e))?;
         },
+        "exposure" => {
+            let report = build_exposure_report(&connections);
+
+            let json_data = serde_json::to_string_pretty(&report)
+                .map_err(|e| format!("JSON serialization error: {}", e))?;
+
+            File::create(&filepath)
+                .map_err(|e| format!("File creation error: {}", e))?
+                .write_all(json_data.as_bytes())
+                .map_err(|e| format!("File write error: {}", e))?;
+        },
         _ => return Err(format!("Unsupported export format: {}", format))
     }
 
     Ok(filepath.to_string_lossy().to_string())
 }
 
+/// The CSV export's columns for `conn`, header name first; every value is
+/// escaped when the row is written.
+fn csv_columns(conn: &Connection) -> Vec<(&'static str, String)> {
+    let flow = conn.flow.as_ref();
+    let tls = conn.tls.as_ref();
+    let tcp = conn.tcp_metrics.as_ref();
+    let route = conn.route.as_ref();
+    let neighbor = conn.neighbor.as_ref();
+    let container = conn.container.as_ref();
+    let unix = conn.unix.as_ref();
+    let raw = conn.raw.as_ref();
+    let text = |value: Option<&str>| value.unwrap_or("").to_string();
+
+    vec![
+        ("Process", conn.process_name.clone()),
+        ("PID", conn.pid.to_string()),
+        ("Namespace", text(conn.namespace.as_ref().map(|ns| ns.name.as_str()))),
+        ("Container ID", text(container.map(|c| c.id.as_str()))),
+        ("Container Name", text(container.and_then(|c| c.name.as_deref()))),
+        ("Container Image", text(container.and_then(|c| c.image.as_deref()))),
+        ("Systemd Unit", text(conn.systemd_unit.as_ref().map(|unit| unit.name.as_str()))),
+        ("Protocol", conn.protocol.clone()),
+        ("Local Address", conn.local_addr.clone()),
+        ("Local Port", conn.local_port.to_string()),
+        ("Local Service", text(conn.local_service.as_deref())),
+        ("Interface", text(conn.interface.as_deref())),
+        ("Remote Address", conn.remote_addr.clone()),
+        ("Remote Host", text(conn.hostname.as_deref())),
+        ("Resolved Name", text(conn.resolved_name.as_deref())),
+        ("Remote Port", conn.remote_port.to_string()),
+        ("Remote Service", text(conn.remote_service.as_deref())),
+        ("Remote Class", conn.remote_class.as_str().to_string()),
+        ("Neighbor MAC", text(neighbor.and_then(|n| n.mac_address.as_deref()))),
+        ("Neighbor Vendor", text(neighbor.and_then(|n| n.vendor.as_deref()))),
+        ("Egress Interface", text(route.map(|r| r.interface.as_str()))),
+        ("Gateway", text(route.and_then(|r| r.gateway.as_deref()))),
+        ("Direction", conn.direction.as_str().to_string()),
+        ("State", conn.state.clone()),
+        ("Risk", conn.risk.as_str().to_string()),
+        ("Captured At", conn.captured_at.to_string()),
+        ("Source", conn.source.as_str().to_string()),
+        ("Packets Sent", flow.map(|f| f.packets_sent.to_string()).unwrap_or_default()),
+        ("Packets Received", flow.map(|f| f.packets_received.to_string()).unwrap_or_default()),
+        ("Bytes Sent", flow.map(|f| f.bytes_sent.to_string()).unwrap_or_default()),
+        ("Bytes Received", flow.map(|f| f.bytes_received.to_string()).unwrap_or_default()),
+        ("TLS Server Name", text(tls.and_then(|t| t.sni.as_deref()))),
+        ("JA3", text(tls.map(|t| t.ja3_hash.as_str()))),
+        ("JA4", text(tls.map(|t| t.ja4.as_str()))),
+        ("RTT (us)", tcp.map(|t| t.rtt_us.to_string()).unwrap_or_default()),
+        ("Retransmits", tcp.map(|t| t.retransmits.to_string()).unwrap_or_default()),
+        ("Send Rate (B/s)", tcp.and_then(|t| t.send_rate).map(|r| format!("{:.0}", r)).unwrap_or_default()),
+        ("Receive Rate (B/s)", tcp.and_then(|t| t.receive_rate).map(|r| format!("{:.0}", r)).unwrap_or_default()),
+        ("Unix Socket Type", text(unix.map(|u| u.socket_type.as_str()))),
+        ("Unix Peer PID", unix.and_then(|u| u.peer_pid).map(|pid| pid.to_string()).unwrap_or_default()),
+        ("Unix Peer Process", text(unix.and_then(|u| u.peer_process.as_deref()))),
+        ("Raw Protocol", text(raw.map(|r| r.protocol.as_str()))),
+        ("Raw Interface", text(raw.and_then(|r| r.interface.as_deref()))),
+    ]
+}
+
 fn escape_csv_field(field: &str) -> String {
-    if field.contains(',') || field.contains('"') || field.contains('\n') {
+    if field.contains([',', '"', '\n', '\r']) {
         format!("\"{}\"", field.replace('"', "\"\""))
     } else {
         field.to_string()
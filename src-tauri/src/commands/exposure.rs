@@ -0,0 +1,10 @@
+use crate::models::ExposureReport;
+use crate::services::exposure::build_exposure_report;
+use crate::services::ConnectionCollector;
+
+#[tauri::command]
+pub async fn get_exposure_report(collector: tauri::State<'_, ConnectionCollector>) -> Result<ExposureReport, String> {
+    // A second poll would shorten the rate windows and re-run the alert watchers
+    let connections = collector.latest_connections()?;
+    Ok(build_exposure_report(&connections))
+}
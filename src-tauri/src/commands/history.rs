@@ -0,0 +1,20 @@
+use crate::models::{ConnectionInterval, HistoryFilter, HistorySnapshot};
+use crate::services::ConnectionCollector;
+
+#[tauri::command]
+pub async fn query_history(
+    filter: HistoryFilter,
+    from: u64,
+    to: u64,
+    collector: tauri::State<'_, ConnectionCollector>,
+) -> Result<Vec<ConnectionInterval>, String> {
+    collector.query_history(&filter, from, to)
+}
+
+#[tauri::command]
+pub async fn get_snapshot_at(
+    timestamp: u64,
+    collector: tauri::State<'_, ConnectionCollector>,
+) -> Result<HistorySnapshot, String> {
+    collector.snapshot_at(timestamp)
+}
@@ -0,0 +1,7 @@
+use crate::models::NetworkInterface;
+use crate::services::ConnectionCollector;
+
+#[tauri::command]
+pub async fn get_interfaces(collector: tauri::State<'_, ConnectionCollector>) -> Result<Vec<NetworkInterface>, String> {
+    collector.interfaces()
+}
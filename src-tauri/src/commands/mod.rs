@@ -1,2 +1,12 @@
+pub mod alerts;
+pub mod bandwidth;
+pub mod baseline;
+pub mod capture;
 pub mod connections;
-pub mod export;
\ No newline at end of file
+pub mod export;
+pub mod exposure;
+pub mod history;
+pub mod interfaces;
+pub mod neighbors;
+pub mod processes;
+pub mod settings;
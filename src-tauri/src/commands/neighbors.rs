@@ -0,0 +1,7 @@
+use crate::models::Neighbor;
+use crate::services::ConnectionCollector;
+
+#[tauri::command]
+pub async fn get_neighbors(collector: tauri::State<'_, ConnectionCollector>) -> Result<Vec<Neighbor>, String> {
+    collector.neighbors()
+}
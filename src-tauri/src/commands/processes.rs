@@ -0,0 +1,10 @@
+use crate::models::ProcessDetails;
+use crate::services::ConnectionCollector;
+
+#[tauri::command]
+pub async fn get_process_details(
+    pid: u32,
+    collector: tauri::State<'_, ConnectionCollector>,
+) -> Result<ProcessDetails, String> {
+    collector.process_details(pid)
+}
@@ -0,0 +1,19 @@
+use crate::models::Settings;
+use crate::services::settings::SettingsStore;
+use crate::services::ConnectionCollector;
+
+#[tauri::command]
+pub fn get_settings(store: tauri::State<'_, SettingsStore>) -> Settings {
+    store.get()
+}
+
+#[tauri::command]
+pub fn update_settings(
+    settings: Settings,
+    store: tauri::State<'_, SettingsStore>,
+    collector: tauri::State<'_, ConnectionCollector>,
+) -> Result<(), String> {
+    store.update(settings.clone())?;
+    collector.apply_settings(&settings);
+    Ok(())
+}
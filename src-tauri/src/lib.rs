@@ -5,11 +5,28 @@ mod services;
 mod commands;
 mod utils;
 
+use commands::alerts::get_alerts;
+use commands::bandwidth::get_top_talkers;
+use commands::baseline::{export_baseline, get_baseline_status, import_baseline, start_baseline_learning};
+use commands::capture::{get_capture_diagnostics, import_capture, start_live_capture, stop_live_capture};
 use commands::connections::get_connections;
 use commands::export::export_connections;
+use commands::exposure::get_exposure_report;
+use commands::history::{get_snapshot_at, query_history};
+use commands::interfaces::get_interfaces;
+use commands::neighbors::get_neighbors;
+use commands::processes::get_process_details;
+use commands::settings::{get_settings, update_settings};
+use services::settings::SettingsStore;
+use services::ConnectionCollector;
+use std::path::PathBuf;
+use tauri::{Emitter, Manager};
 use utils::logger::{log_debug, log_info, log_warn, log_error};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+/// Event carrying each new [`models::Alert`] to the frontend.
+const ALERT_EVENT: &str = "alert-raised";
+
 #[tauri::command]
 fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
@@ -23,7 +40,26 @@ pub fn run() {
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![greet, get_connections, export_connections, log_debug, log_info, log_warn, log_error])
+        .setup(|app| {
+            let app_data_dir = app
+                .path()
+                .app_data_dir()
+                .unwrap_or_else(|_| PathBuf::from("./"));
+
+            let settings = SettingsStore::load(app_data_dir.clone());
+            let collector = ConnectionCollector::new(&settings.get(), app_data_dir);
+            let app_handle = app.handle().clone();
+            collector.alerts().set_listener(move |alert| {
+                if let Err(e) = app_handle.emit(ALERT_EVENT, alert) {
+                    tracing::warn!("Failed to emit alert event: {}", e);
+                }
+            });
+
+            app.manage(collector);
+            app.manage(settings);
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![greet, get_connections, export_connections, get_exposure_report, get_settings, update_settings, import_capture, start_live_capture, stop_live_capture, get_capture_diagnostics, get_top_talkers, get_interfaces, get_neighbors, get_process_details, get_alerts, query_history, get_snapshot_at, get_baseline_status, start_baseline_learning, export_baseline, import_baseline, log_debug, log_info, log_warn, log_error])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
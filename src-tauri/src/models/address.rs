@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Where a remote address lives, from the point of view of this host.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum AddressClass {
+    Loopback,
+    Private, // RFC1918 (IPv4) or ULA fc00::/7 (IPv6)
+    Cgnat,   // 100.64.0.0/10 carrier-grade NAT
+    LinkLocal,
+    Multicast,
+    Broadcast,
+    Reserved, // documentation, benchmarking and other non-routable ranges
+    #[default]
+    Unspecified, // 0.0.0.0, ::, or no remote address at all
+    Public,
+}
+
+impl AddressClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AddressClass::Loopback => "loopback",
+            AddressClass::Private => "private",
+            AddressClass::Cgnat => "cgnat",
+            AddressClass::LinkLocal => "linkLocal",
+            AddressClass::Multicast => "multicast",
+            AddressClass::Broadcast => "broadcast",
+            AddressClass::Reserved => "reserved",
+            AddressClass::Unspecified => "unspecified",
+            AddressClass::Public => "public",
+        }
+    }
+
+    /// Traffic that never leaves the local network segment (or the host itself).
+    pub fn is_local_network(&self) -> bool {
+        matches!(
+            self,
+            AddressClass::Loopback
+                | AddressClass::Private
+                | AddressClass::LinkLocal
+                | AddressClass::Multicast
+                | AddressClass::Broadcast
+        )
+    }
+}
+
+/// Parses an address as reported by the collectors: tolerates `[v6]` brackets,
+/// `%zone` suffixes and unwraps IPv4-mapped IPv6 addresses.
+pub fn parse_ip_address(address: &str) -> Option<IpAddr> {
+    let trimmed = address.trim().trim_start_matches('[').trim_end_matches(']');
+    let without_zone = trimmed.split('%').next().unwrap_or(trimmed);
+
+    match without_zone.parse::<IpAddr>().ok()? {
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => Some(IpAddr::V4(v4)),
+            None => Some(IpAddr::V6(v6)),
+        },
+        ip => Some(ip),
+    }
+}
+
+/// Classifies an address string as reported by the collectors; anything that
+/// doesn't parse (empty, `*`, ...) is treated as unspecified.
+pub fn classify_address(address: &str) -> AddressClass {
+    parse_ip_address(address).map_or(AddressClass::Unspecified, classify_ip)
+}
+
+pub fn classify_ip(ip: IpAddr) -> AddressClass {
+    match ip {
+        IpAddr::V4(v4) => classify_ipv4(v4),
+        IpAddr::V6(v6) => classify_ipv6(v6),
+    }
+}
+
+fn classify_ipv4(ip: Ipv4Addr) -> AddressClass {
+    let [a, b, c, _] = ip.octets();
+
+    if ip.is_unspecified() {
+        AddressClass::Unspecified
+    } else if ip.is_loopback() {
+        AddressClass::Loopback
+    } else if ip.is_broadcast() {
+        AddressClass::Broadcast
+    } else if ip.is_private() {
+        AddressClass::Private
+    } else if a == 100 && (b & 0xc0) == 64 {
+        AddressClass::Cgnat
+    } else if ip.is_link_local() {
+        AddressClass::LinkLocal
+    } else if ip.is_multicast() {
+        AddressClass::Multicast
+    } else if a == 0 // "this network", IETF assignments, TEST-NETs, benchmarking, class E
+        || (a == 192 && b == 0 && c == 0)
+        || ip.is_documentation()
+        || (a == 198 && (b & 0xfe) == 18)
+        || a >= 240
+    {
+        AddressClass::Reserved
+    } else {
+        AddressClass::Public
+    }
+}
+
+fn classify_ipv6(ip: Ipv6Addr) -> AddressClass {
+    if let Some(v4) = ip.to_ipv4_mapped() {
+        return classify_ipv4(v4);
+    }
+
+    let segments = ip.segments();
+
+    // NAT64 well-known prefix carries an IPv4 destination in the low 32 bits
+    if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+        let octets = ip.octets();
+        return classify_ipv4(Ipv4Addr::new(octets[12], octets[13], octets[14], octets[15]));
+    }
+
+    if ip.is_unspecified() {
+        AddressClass::Unspecified
+    } else if ip.is_loopback() {
+        AddressClass::Loopback
+    } else if (segments[0] & 0xfe00) == 0xfc00 {
+        AddressClass::Private
+    } else if (segments[0] & 0xffc0) == 0xfe80 {
+        AddressClass::LinkLocal
+    } else if (segments[0] & 0xff00) == 0xff00 {
+        AddressClass::Multicast
+    } else if is_reserved_ipv6(segments) {
+        AddressClass::Reserved
+    } else {
+        AddressClass::Public
+    }
+}
+
+fn is_reserved_ipv6(segments: [u16; 8]) -> bool {
+    // Documentation 2001:db8::/32 and 3fff::/20
+    (segments[0] == 0x2001 && segments[1] == 0x0db8)
+        || (segments[0] == 0x3fff && (segments[1] & 0xf000) == 0)
+        // Discard-only 100::/64
+        || segments[..4] == [0x0100, 0, 0, 0]
+        // Anything outside global unicast 2000::/3
+        || (segments[0] & 0xe000) != 0x2000
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class(address: &str) -> AddressClass {
+        classify_ip(address.parse().unwrap())
+    }
+
+    #[test]
+    fn classifies_ipv4_ranges() {
+        assert_eq!(class("10.1.2.3"), AddressClass::Private);
+        assert_eq!(class("172.16.0.1"), AddressClass::Private);
+        assert_eq!(class("172.31.255.255"), AddressClass::Private);
+        assert_eq!(class("172.32.0.1"), AddressClass::Public);
+        assert_eq!(class("192.168.1.1"), AddressClass::Private);
+        assert_eq!(class("100.64.0.1"), AddressClass::Cgnat);
+        assert_eq!(class("100.127.255.254"), AddressClass::Cgnat);
+        assert_eq!(class("100.128.0.1"), AddressClass::Public);
+        assert_eq!(class("169.254.10.20"), AddressClass::LinkLocal);
+        assert_eq!(class("127.0.0.53"), AddressClass::Loopback);
+        assert_eq!(class("0.0.0.0"), AddressClass::Unspecified);
+        assert_eq!(class("255.255.255.255"), AddressClass::Broadcast);
+        assert_eq!(class("8.8.8.8"), AddressClass::Public);
+    }
+
+    #[test]
+    fn classifies_ipv4_multicast_and_documentation() {
+        assert_eq!(class("224.0.0.251"), AddressClass::Multicast);
+        assert_eq!(class("239.255.255.250"), AddressClass::Multicast);
+        assert_eq!(class("192.0.2.1"), AddressClass::Reserved);
+        assert_eq!(class("198.51.100.7"), AddressClass::Reserved);
+        assert_eq!(class("203.0.113.9"), AddressClass::Reserved);
+        assert_eq!(class("198.18.0.1"), AddressClass::Reserved);
+        assert_eq!(class("240.0.0.1"), AddressClass::Reserved);
+    }
+
+    #[test]
+    fn classifies_ipv6_ranges() {
+        assert_eq!(class("fc00::1"), AddressClass::Private);
+        assert_eq!(class("fd12:3456:789a::1"), AddressClass::Private);
+        assert_eq!(class("fe80::1"), AddressClass::LinkLocal);
+        assert_eq!(class("febf::1"), AddressClass::LinkLocal);
+        assert_eq!(class("fec0::1"), AddressClass::Reserved);
+        assert_eq!(class("ff02::fb"), AddressClass::Multicast);
+        assert_eq!(class("2001:db8::1"), AddressClass::Reserved);
+        assert_eq!(class("3fff::1"), AddressClass::Reserved);
+        assert_eq!(class("::1"), AddressClass::Loopback);
+        assert_eq!(class("::"), AddressClass::Unspecified);
+        assert_eq!(class("2606:4700:4700::1111"), AddressClass::Public);
+    }
+
+    #[test]
+    fn classifies_embedded_ipv4_as_the_inner_address() {
+        assert_eq!(class("::ffff:192.168.1.10"), AddressClass::Private);
+        assert_eq!(class("::ffff:100.64.1.1"), AddressClass::Cgnat);
+        assert_eq!(class("::ffff:127.0.0.1"), AddressClass::Loopback);
+        assert_eq!(class("::ffff:8.8.8.8"), AddressClass::Public);
+        assert_eq!(class("64:ff9b::10.0.0.1"), AddressClass::Private);
+        assert_eq!(class("64:ff9b::1.1.1.1"), AddressClass::Public);
+    }
+
+    #[test]
+    fn classify_address_tolerates_collector_formats() {
+        assert_eq!(classify_address("[fe80::1%eth0]"), AddressClass::LinkLocal);
+        assert_eq!(classify_address("::ffff:10.0.0.1"), AddressClass::Private);
+        assert_eq!(classify_address(""), AddressClass::Unspecified);
+        assert_eq!(classify_address("*"), AddressClass::Unspecified);
+    }
+}
@@ -0,0 +1,43 @@
+use super::connection::RiskLevel;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AlertKind {
+    GatewayMacChanged,    // the default gateway now answers from a different MAC
+    SharedMac,            // one MAC answers for several IPv4 addresses
+    MacFlapping,          // an IP keeps switching between MACs
+    Beaconing,            // a process connects out to the same endpoint at a steady interval
+    NovelConnection,      // traffic the learned baseline has never seen
+    NewListener,          // a process started listening on a port
+    ListenerOwnerChanged, // a listening port is now held by a different process
+}
+
+/// Something noteworthy that happened between polls, as opposed to a risk
+/// reason, which describes a single connection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Alert {
+    pub id: String,
+    pub kind: AlertKind,
+    pub severity: RiskLevel,
+    pub raised_at: u64, // Unix timestamp in milliseconds
+    pub message: String,
+    pub details: Vec<String>, // the observations behind the alert
+}
+
+impl Alert {
+    pub fn new(kind: AlertKind, severity: RiskLevel, message: String, details: Vec<String>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind,
+            severity,
+            raised_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            message,
+            details,
+        }
+    }
+}
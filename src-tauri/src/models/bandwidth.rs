@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Sliding window for throughput accounting.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TalkerWindow {
+    #[serde(rename = "1m")]
+    OneMinute,
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[serde(rename = "1h")]
+    OneHour,
+}
+
+impl TalkerWindow {
+    pub fn duration(&self) -> Duration {
+        match self {
+            TalkerWindow::OneMinute => Duration::from_secs(60),
+            TalkerWindow::FiveMinutes => Duration::from_secs(5 * 60),
+            TalkerWindow::OneHour => Duration::from_secs(60 * 60),
+        }
+    }
+
+    /// The longest window; history older than this is discarded.
+    pub fn longest() -> Self {
+        TalkerWindow::OneHour
+    }
+}
+
+/// Bytes moved over a window. Rates divide by the part of the window
+/// actually observed, so they are meaningful right after startup too.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Throughput {
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub send_rate: f64, // bytes per second
+    pub receive_rate: f64,
+}
+
+impl Throughput {
+    pub fn total_bytes(&self) -> u64 {
+        self.bytes_sent + self.bytes_received
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessTalker {
+    pub pid: u32,
+    pub process_name: String,
+    pub throughput: Throughput,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostTalker {
+    pub remote_addr: String,
+    pub hostname: Option<String>, // resolved or PTR name, when known
+    pub throughput: Throughput,
+}
+
+/// Traffic of every process running under one systemd unit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnitTalker {
+    pub unit: String,
+    pub throughput: Throughput,
+}
+
+/// Processes, systemd units and remote hosts ranked by bytes moved, busiest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopTalkers {
+    pub window: TalkerWindow,
+    pub generated_at: u64, // Unix timestamp in milliseconds
+    pub observed_secs: f64, // how much of the window has been tracked
+    pub processes: Vec<ProcessTalker>,
+    pub units: Vec<UnitTalker>, // Linux only
+    pub remote_hosts: Vec<HostTalker>,
+}
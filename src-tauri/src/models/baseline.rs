@@ -0,0 +1,104 @@
+use super::connection::ConnectionDirection;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// One kind of traffic a process normally has: who it talks to, on which
+/// port, and in which direction. The port is the service side's: remote
+/// for outbound connections, local for inbound ones and listeners.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct BaselineTuple {
+    pub process_name: String,
+    pub port: u16,
+    pub peer: String, // "AS12345", a CIDR block, or "*" for listeners
+    pub direction: ConnectionDirection,
+}
+
+/// The tuples recorded during learning, persisted as `baseline.json` and
+/// exchanged between machines with export and import.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Baseline {
+    pub learning_started_at: u64, // Unix timestamp in milliseconds
+    pub learning_ends_at: u64,
+    /// Missing from baselines saved before it was recorded.
+    #[serde(default)]
+    pub peer_keying: Option<PeerKeying>,
+    pub tuples: BTreeSet<BaselineTuple>,
+}
+
+/// How the peers in a baseline's tuples were keyed. Enforcement keys peers
+/// the same way, whatever the settings are by then.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerKeying {
+    pub asn: bool, // public peers by autonomous system
+    pub ipv4_prefix_len: u8,
+    pub ipv6_prefix_len: u8,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BaselineState {
+    None,      // learning was never started
+    Learning,  // tuples are being recorded
+    Enforcing, // tuples missing from the baseline are flagged
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaselineStatus {
+    pub state: BaselineState,
+    pub learning_started_at: Option<u64>,
+    pub learning_ends_at: Option<u64>,
+    pub tuples: usize,
+}
+
+impl Baseline {
+    pub fn is_learning(&self, now: u64) -> bool {
+        now < self.learning_ends_at
+    }
+
+    pub fn status(&self, now: u64) -> BaselineStatus {
+        BaselineStatus {
+            state: if self.is_learning(now) {
+                BaselineState::Learning
+            } else {
+                BaselineState::Enforcing
+            },
+            learning_started_at: Some(self.learning_started_at),
+            learning_ends_at: Some(self.learning_ends_at),
+            tuples: self.tuples.len(),
+        }
+    }
+}
+
+impl PeerKeying {
+    pub fn describe(&self) -> String {
+        let blocks = format!("/{} and /{} blocks", self.ipv4_prefix_len, self.ipv6_prefix_len);
+        if self.asn {
+            format!("autonomous systems, else {}", blocks)
+        } else {
+            blocks
+        }
+    }
+}
+
+impl BaselineTuple {
+    /// The finding for a tuple the baseline has never seen.
+    pub fn describe_novel(&self) -> String {
+        match self.direction {
+            ConnectionDirection::Listening => {
+                format!("First time {} has listened on {}", self.process_name, self.port)
+            }
+            ConnectionDirection::Inbound => format!(
+                "First time {} has accepted a connection from {} on {}",
+                self.process_name, self.peer, self.port
+            ),
+            ConnectionDirection::Outbound | ConnectionDirection::Unknown => format!(
+                "First time {} has talked to {} on {}",
+                self.process_name, self.peer, self.port
+            ),
+        }
+    }
+}
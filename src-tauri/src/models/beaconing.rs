@@ -0,0 +1,80 @@
+use super::connection::{Connection, RiskLevel};
+use std::collections::BTreeSet;
+
+/// An outbound connect: a socket appearing in the connection history, or a
+/// conversation live capture saw open.
+#[derive(Debug, Clone)]
+pub struct ConnectEvent {
+    pub process_name: String,
+    pub protocol: String,
+    pub remote_addr: String,
+    pub remote_port: u16,
+    pub hostname: Option<String>,
+    pub at: u64, // Unix timestamp in milliseconds
+}
+
+/// A process that keeps connecting to the same endpoint at a steady
+/// interval, the way command-and-control implants check in.
+#[derive(Debug, Clone)]
+pub struct BeaconFinding {
+    pub process_name: String,
+    pub protocol: String,
+    pub remote_host: String, // host name when one was seen, otherwise the address
+    pub remote_addrs: BTreeSet<String>,
+    pub remote_port: u16,
+    pub connections: usize,
+    pub period_secs: f64, // mean time between connects
+    pub jitter_secs: f64, // standard deviation of that time
+    pub first_seen: u64,
+    pub last_seen: u64,
+}
+
+impl BeaconFinding {
+    /// Identifies the beacon across analyses.
+    pub fn key(&self) -> String {
+        format!("{}|{}|{}|{}", self.process_name, self.protocol, self.remote_host, self.remote_port)
+    }
+
+    /// Coefficient of variation of the connect intervals.
+    pub fn variation(&self) -> f64 {
+        self.jitter_secs / self.period_secs
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "Beaconing to {}:{} every {} (±{} jitter over {} connections)",
+            self.remote_host,
+            self.remote_port,
+            format_secs(self.period_secs),
+            format_secs(self.jitter_secs),
+            self.connections
+        )
+    }
+
+    pub fn matches(&self, connection: &Connection) -> bool {
+        connection.process_name == self.process_name
+            && connection.protocol == self.protocol
+            && connection.remote_port == self.remote_port
+            && (self.remote_addrs.contains(&connection.remote_addr)
+                || connection.hostname.as_deref() == Some(&self.remote_host)
+                || connection.resolved_name.as_deref() == Some(&self.remote_host))
+    }
+
+    /// Flags a live connection to the beacon's endpoint.
+    pub fn apply(&self, connection: &mut Connection) {
+        connection.risk_reasons.retain(|reason| reason != "Standard connection");
+        connection.risk_reasons.push(self.describe());
+        connection.risk = connection.risk.clone().max(RiskLevel::Medium);
+    }
+}
+
+/// A duration as seconds, minutes or hours, whichever reads best.
+pub fn format_secs(secs: f64) -> String {
+    if secs < 90.0 {
+        format!("{:.0}s", secs)
+    } else if secs < 90.0 * 60.0 {
+        format!("{:.1}m", secs / 60.0)
+    } else {
+        format!("{:.1}h", secs / 3600.0)
+    }
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+/// State of the live packet capture, for the UI to explain why flow data
+/// is missing (feature not built, no privileges, interface gone).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureDiagnostics {
+    pub available: bool, // built with the `capture` feature on a supported OS
+    pub has_privileges: bool,
+    pub running: bool,
+    pub interface: Option<String>,
+    pub started_at: Option<u64>, // Unix timestamp in milliseconds
+    pub packets_captured: u64,
+    pub packets_dropped: u64, // dropped by the kernel because we fell behind
+    pub flows_tracked: usize,
+    pub dns_addresses: usize,
+    pub last_error: Option<String>,
+}
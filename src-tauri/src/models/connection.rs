@@ -1,25 +1,222 @@
+use super::address::AddressClass;
+use super::neighbor::Neighbor;
+use super::exposure::{connection_exposure, exposure_risk};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Connection {
     pub id: String,
     pub process_name: String,
     pub pid: u32,
-    pub protocol: String, // "TCP" or "UDP"
+    #[serde(default)]
+    pub namespace: Option<NetworkNamespace>, // Linux network namespace the socket lives in
+    #[serde(default)]
+    pub container: Option<ContainerInfo>, // container the owning process runs in
+    #[serde(default)]
+    pub systemd_unit: Option<SystemdUnit>, // unit the owning process runs under, Linux only
+    pub protocol: String, // "TCP", "UDP", "UNIX", "RAW", "ICMP" (ping sockets) or "PACKET"
     pub local_addr: String,
     pub local_port: u16,
+    #[serde(default)]
+    pub local_service: Option<String>,
+    #[serde(default)]
+    pub interface: Option<String>, // interface that owns the local address
+    #[serde(default)]
+    pub route: Option<EgressRoute>, // where traffic to the remote leaves the host, Linux only
     pub remote_addr: String,
     pub remote_port: u16,
+    #[serde(default)]
+    pub remote_service: Option<String>,
+    #[serde(default)]
+    pub remote_class: AddressClass,
+    #[serde(default)]
+    pub neighbor: Option<Neighbor>, // neighbor table entry when the remote is on a local link
+    #[serde(default)]
+    pub hostname: Option<String>, // reverse DNS (PTR) name of the remote address
+    #[serde(default)]
+    pub resolved_name: Option<String>, // name the client looked up, from observed DNS answers
+    #[serde(default)]
+    pub direction: ConnectionDirection,
     pub state: String,
     pub risk: RiskLevel,
     pub risk_reasons: Vec<String>,
     pub captured_at: u64, // Unix timestamp in milliseconds
+    #[serde(default)]
+    pub source: ConnectionSource,
+    #[serde(default)]
+    pub flow: Option<FlowStats>, // packet-level counters, only when packets were captured
+    #[serde(default)]
+    pub tls: Option<TlsInfo>, // from the ClientHello, only when packets were captured
+    #[serde(default)]
+    pub tcp_metrics: Option<TcpMetrics>, // kernel TCP statistics, Linux only
+    #[serde(default)]
+    pub unix: Option<UnixSocketInfo>, // only for protocol "UNIX"
+    #[serde(default)]
+    pub raw: Option<RawSocketInfo>, // only for protocols "RAW", "ICMP" and "PACKET"
+}
+
+impl Connection {
+    /// Live sockets in the host's own network namespace: the only ones whose
+    /// local address, routes and neighbors are this machine's.
+    pub fn is_host_socket(&self) -> bool {
+        self.source == ConnectionSource::Live && self.namespace.as_ref().is_none_or(|ns| ns.is_host)
+    }
+
+    /// Unix domain sockets have paths instead of IP addresses and ports.
+    pub fn is_unix_socket(&self) -> bool {
+        self.protocol == "UNIX"
+    }
+}
+
+/// Where a connection record came from.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionSource {
+    #[default]
+    Live,    // the host's socket table
+    Capture, // reconstructed from a pcap/pcapng file; no process information
+}
+
+impl ConnectionSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConnectionSource::Live => "live",
+            ConnectionSource::Capture => "capture",
+        }
+    }
+}
+
+/// Traffic counters for a flow rebuilt from packets. "Sent" is local to remote.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlowStats {
+    pub first_seen: u64, // Unix timestamp in milliseconds
+    pub last_seen: u64,  // Unix timestamp in milliseconds
+    pub packets_sent: u64,
+    pub packets_received: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+}
+
+/// Kernel statistics for a TCP socket (Linux `tcp_info`). Rates are only
+/// known from the second poll that sees the socket.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TcpMetrics {
+    pub bytes_acked: u64, // sent and acknowledged by the peer
+    pub bytes_received: u64,
+    pub rtt_us: u32, // smoothed round-trip time
+    pub rtt_var_us: u32,
+    pub retransmits: u32, // segments retransmitted over the socket's lifetime
+    pub congestion_window: u32, // in segments
+    pub send_rate: Option<f64>, // bytes per second since the previous poll
+    pub receive_rate: Option<f64>,
+}
+
+/// Details of a unix domain socket. Its path is the connection's local
+/// address and the peer's path its remote address; abstract names start with
+/// '@' and unnamed sockets have an empty address.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnixSocketInfo {
+    pub socket_type: String, // "stream", "dgram" or "seqpacket"
+    pub inode: u64,
+    pub peer_inode: Option<u64>, // connected sockets, when the kernel reports the peer
+    pub peer_pid: Option<u32>,
+    pub peer_process: Option<String>,
+}
+
+/// Raw IP, ICMP ping and packet sockets have a protocol where others have
+/// ports. Their ports are left at zero.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawSocketInfo {
+    pub protocol: String, // IP protocol ("ICMP", "255") or, for packet sockets, EtherType ("all", "ARP", "0x88cc")
+    pub interface: Option<String>, // packet sockets bound to a single interface
+}
+
+/// A Linux network namespace. Addresses, routes and neighbors in any other
+/// than the host's are not the host's own.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkNamespace {
+    pub id: u64,      // inode of the namespace, as in `net:[4026531840]`
+    pub name: String, // "host", the `ip netns` name, or the first process inside
+    pub is_host: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerRuntime {
+    Docker,
+    Containerd,
+    Podman,
+    Crio,
+    #[default]
+    Unknown, // a container ID in a cgroup path that names no runtime
+}
+
+impl ContainerRuntime {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Containerd => "containerd",
+            ContainerRuntime::Podman => "podman",
+            ContainerRuntime::Crio => "crio",
+            ContainerRuntime::Unknown => "unknown",
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// The container a process runs in, from its cgroup path. Name and image
+/// come from the runtime's API and are missing when it cannot be reached.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerInfo {
+    pub id: String,
+    pub runtime: ContainerRuntime,
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub pod_uid: Option<String>, // Kubernetes pod the container belongs to
+}
+
+/// The systemd unit a process belongs to, from its cgroup path.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemdUnit {
+    pub name: String,          // e.g. "nginx.service" or "session-2.scope"
+    pub slice: Option<String>, // innermost slice above the unit, e.g. "system.slice"
+    pub user_id: Option<u32>,  // owner of the per-user manager, for user units
+}
+
+/// The routing table entry that traffic to the remote address follows.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EgressRoute {
+    pub interface: String,
+    pub gateway: Option<String>, // None when the remote is on a directly attached network
+    pub destination: String,     // matched prefix, e.g. "0.0.0.0/0"
+    #[serde(default)]
+    pub table: Option<u32>, // routing table the policy rules picked; 254 is main
+}
+
+/// What the client revealed in its TLS ClientHello.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsInfo {
+    pub sni: Option<String>,
+    pub alpn: Vec<String>,
+    pub version: String, // highest version offered, e.g. "TLS 1.3"
+    pub ja3: String,     // full JA3 string before hashing
+    pub ja3_hash: String,
+    pub ja4: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "lowercase")]
 pub enum RiskLevel {
+    #[default]
     Low,
     Medium,
     High,
@@ -35,7 +232,26 @@ impl RiskLevel {
     }
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionDirection {
+    Inbound,
+    Outbound,
+    Listening,
+    #[default]
+    Unknown,
+}
 
+impl ConnectionDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConnectionDirection::Inbound => "inbound",
+            ConnectionDirection::Outbound => "outbound",
+            ConnectionDirection::Listening => "listening",
+            ConnectionDirection::Unknown => "unknown",
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessInfo {
@@ -43,18 +259,15 @@ pub struct ProcessInfo {
     pub name: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct NetworkEndpoint {
-    pub protocol: String,
-    pub local_address: String,
-    pub local_port: u16,
-    pub remote_address: String,
-    pub remote_port: u16,
-    pub state: String,
-    pub owning_process_id: Option<u32>,
+/// "telnet (23)" when the port has a registered name, "port 23" otherwise.
+pub fn describe_port(service: Option<&str>, port: u16) -> String {
+    match service {
+        Some(name) => format!("{} ({})", name, port),
+        None => format!("port {}", port),
+    }
 }
 
-pub fn calculate_risk(connection: &NetworkEndpoint) -> (RiskLevel, Vec<String>) {
+pub fn calculate_risk(connection: &Connection) -> (RiskLevel, Vec<String>) {
     let mut reasons = Vec::new();
     let mut risk = RiskLevel::Low;
 
@@ -64,22 +277,55 @@ pub fn calculate_risk(connection: &NetworkEndpoint) -> (RiskLevel, Vec<String>)
     // Medium risk ports
     let medium_risk_ports = [21, 22, 25, 110, 143, 993, 995];
 
+    // UDP has no handshake, so a datagram socket with a peer counts as established,
+    // and a captured flow means traffic was actually exchanged
+    let established = connection.state.eq_ignore_ascii_case("established")
+        || (connection.protocol == "UDP" && connection.remote_class != AddressClass::Unspecified)
+        || connection.source == ConnectionSource::Capture;
+
+    // For accepted connections the remote port is the client's ephemeral port,
+    // so port-based rules look at the local service port instead
+    let inbound = connection.direction == ConnectionDirection::Inbound;
+
+    let remote_port = describe_port(connection.remote_service.as_deref(), connection.remote_port);
+    let local_port = describe_port(connection.local_service.as_deref(), connection.local_port);
+
     // Check for high-risk ports in established connections
-    if connection.state == "Established" && high_risk_ports.contains(&connection.remote_port) {
+    if established && !inbound && high_risk_ports.contains(&connection.remote_port) {
         risk = RiskLevel::High;
-        reasons.push(format!("Connection to known high-risk port {}", connection.remote_port));
+        reasons.push(format!("Connection to {}, a known high-risk port", remote_port));
+    }
+
+    // Remote peers reaching a high-risk service on this host
+    if established
+        && inbound
+        && !connection.remote_class.is_local_network()
+        && high_risk_ports.contains(&connection.local_port)
+    {
+        risk = RiskLevel::High;
+        reasons.push(format!("Inbound connection from {} to high-risk {}", connection.remote_addr, local_port));
     }
 
     // Check for medium-risk ports
-    if connection.state == "Established" && medium_risk_ports.contains(&connection.remote_port) {
+    if established && !inbound && medium_risk_ports.contains(&connection.remote_port) {
         if risk == RiskLevel::Low {
             risk = RiskLevel::Medium;
         }
-        reasons.push(format!("Connection to administrative port {}", connection.remote_port));
+        reasons.push(format!("Connection to administrative {}", remote_port));
     }
 
-    // Check for unknown/local process
-    if connection.owning_process_id.unwrap_or(0) == 0 {
+    // Listening sockets are judged by who can reach them; unix sockets never leave the host
+    if connection.direction == ConnectionDirection::Listening && !connection.is_unix_socket() {
+        let (exposure_level, exposure_reasons) = exposure_risk(connection_exposure(connection), connection.local_port);
+        if exposure_level > RiskLevel::Low {
+            risk = risk.max(exposure_level);
+            reasons.extend(exposure_reasons);
+        }
+    }
+
+    // Check for unknown/local process (captures never carry process information, and
+    // unix sockets held by the kernel or by other PID namespaces legitimately have none)
+    if connection.pid == 0 && connection.source == ConnectionSource::Live && !connection.is_unix_socket() {
         if risk == RiskLevel::Low {
             risk = RiskLevel::Medium;
         }
@@ -87,15 +333,27 @@ pub fn calculate_risk(connection: &NetworkEndpoint) -> (RiskLevel, Vec<String>)
     }
 
     // Check for non-standard ports (suggests custom service)
-    if connection.remote_port > 10000 && connection.state == "Established" {
+    if connection.remote_port > 10000
+        && established
+        && !inbound
+        && !connection.remote_class.is_local_network()
+    {
+        if risk == RiskLevel::Low {
+            risk = RiskLevel::Medium;
+        }
+        reasons.push(format!("Connection to non-standard high {}", remote_port));
+    }
+
+    // Legitimate traffic never targets documentation or reserved ranges
+    if connection.remote_class == AddressClass::Reserved {
         if risk == RiskLevel::Low {
             risk = RiskLevel::Medium;
         }
-        reasons.push("Connection to non-standard high port".to_string());
+        reasons.push(format!("Connection to reserved address {}", connection.remote_addr));
     }
 
     // Check for localhost connections (generally lower risk)
-    if connection.remote_address == "127.0.0.1" || connection.remote_address == "::1" {
+    if connection.remote_class == AddressClass::Loopback {
         // Reduce risk level for localhost connections
         match risk {
             RiskLevel::High => risk = RiskLevel::Medium,
@@ -0,0 +1,119 @@
+use super::tls_rules::glob_matches;
+use super::{Connection, ContainerInfo, RiskLevel};
+
+/// Containers to flag, as loaded from the container rules file.
+#[derive(Debug, Clone, Default)]
+pub struct ContainerRules {
+    rules: Vec<ContainerRule>,
+}
+
+#[derive(Debug, Clone)]
+struct ContainerRule {
+    field: ContainerField,
+    pattern: String,
+    note: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ContainerField {
+    Name,
+    Image,
+    Id,
+    Runtime,
+}
+
+impl ContainerRules {
+    /// Each line is `<field>=<pattern> [note]` where the field is `name`,
+    /// `image`, `id` or `runtime`, e.g. `image=*/xmrig* cryptominer`. Patterns
+    /// are case-insensitive `*` globs; `#` starts a comment line.
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let (rule, note) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+                let (field, pattern) = rule.split_once('=')?;
+                let field = match field.to_lowercase().as_str() {
+                    "name" => ContainerField::Name,
+                    "image" => ContainerField::Image,
+                    "id" => ContainerField::Id,
+                    "runtime" => ContainerField::Runtime,
+                    _ => {
+                        tracing::warn!("Ignoring container rule with unknown field: {}", line);
+                        return None;
+                    }
+                };
+                Some(ContainerRule {
+                    field,
+                    pattern: pattern.to_lowercase(),
+                    note: note.trim().to_string(),
+                })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Raises connections from matching containers to high risk.
+    pub fn apply(&self, connection: &mut Connection) {
+        let Some(container) = &connection.container else {
+            return;
+        };
+
+        let mut reasons = Vec::new();
+        for rule in &self.rules {
+            let Some(value) = rule.field.value(container) else {
+                continue;
+            };
+            if !glob_matches(rule.pattern.as_bytes(), value.to_lowercase().as_bytes()) {
+                continue;
+            }
+            let reason = format!(
+                "Container {} matches {}={}",
+                container.name.as_deref().unwrap_or(container.id.get(..12).unwrap_or(&container.id)),
+                rule.field.as_str(),
+                rule.pattern
+            );
+            reasons.push(if rule.note.is_empty() {
+                reason
+            } else {
+                format!("{} ({})", reason, rule.note)
+            });
+        }
+
+        if reasons.is_empty() {
+            return;
+        }
+        connection.risk_reasons.retain(|reason| reason != "Standard connection");
+        connection.risk_reasons.extend(reasons);
+        connection.risk = RiskLevel::High;
+    }
+}
+
+impl ContainerField {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ContainerField::Name => "name",
+            ContainerField::Image => "image",
+            ContainerField::Id => "id",
+            ContainerField::Runtime => "runtime",
+        }
+    }
+
+    fn value<'a>(&self, container: &'a ContainerInfo) -> Option<&'a str> {
+        match self {
+            ContainerField::Name => container.name.as_deref(),
+            ContainerField::Image => container.image.as_deref(),
+            ContainerField::Id => Some(&container.id),
+            ContainerField::Runtime => Some(container.runtime.as_str()),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use super::address::parse_ip_address;
+use super::connection::{Connection, RiskLevel};
+use serde::{Deserialize, Serialize};
+
+/// How reachable a listening or bound socket is from other hosts.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum Exposure {
+    AllInterfaces,   // bound to 0.0.0.0 or ::
+    SpecificAddress, // bound to one LAN/public address
+    NamespaceOnly,   // bound in a container or other network namespace; published ports show up as host listeners
+    LoopbackOnly,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExposureEntry {
+    pub protocol: String,
+    pub local_addr: String,
+    pub local_port: u16,
+    pub exposure: Exposure,
+    pub service: Option<String>,
+    pub risk: RiskLevel,
+    pub risk_reasons: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessExposure {
+    pub pid: u32,
+    pub process_name: String,
+    pub entries: Vec<ExposureEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExposureReport {
+    pub generated_at: u64, // Unix timestamp in milliseconds
+    pub processes: Vec<ProcessExposure>,
+}
+
+pub fn classify_exposure(local_addr: &str) -> Exposure {
+    match parse_ip_address(local_addr) {
+        Some(ip) if ip.is_loopback() => Exposure::LoopbackOnly,
+        Some(ip) if !ip.is_unspecified() => Exposure::SpecificAddress,
+        // Wildcard binds, and placeholders such as "*" used by netstat
+        _ => Exposure::AllInterfaces,
+    }
+}
+
+/// Like [`classify_exposure`], but a non-loopback bind inside another network
+/// namespace is only reachable through that namespace's own interfaces, not
+/// the host's.
+pub fn connection_exposure(conn: &Connection) -> Exposure {
+    match classify_exposure(&conn.local_addr) {
+        Exposure::LoopbackOnly => Exposure::LoopbackOnly,
+        _ if conn.namespace.as_ref().is_some_and(|ns| !ns.is_host) => Exposure::NamespaceOnly,
+        exposure => exposure,
+    }
+}
+
+/// Services that should never be reachable from arbitrary networks.
+pub fn sensitive_service(port: u16) -> Option<&'static str> {
+    let name = match port {
+        23 => "Telnet",
+        135 => "MS RPC",
+        137..=139 => "NetBIOS",
+        445 => "SMB",
+        1433 => "Microsoft SQL Server",
+        1521 => "Oracle Database",
+        2375 | 2376 => "Docker API",
+        2379 | 2380 => "etcd",
+        3306 => "MySQL",
+        3389 => "RDP",
+        5432 => "PostgreSQL",
+        5900..=5909 => "VNC",
+        5984 => "CouchDB",
+        6379 => "Redis",
+        9042 => "Cassandra",
+        9200 | 9300 => "Elasticsearch",
+        10250 => "Kubelet API",
+        11211 => "Memcached",
+        27017..=27019 => "MongoDB",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Risk of a listening socket based on where it is bound and what it serves.
+pub fn exposure_risk(exposure: Exposure, local_port: u16) -> (RiskLevel, Vec<String>) {
+    match (exposure, sensitive_service(local_port)) {
+        (Exposure::AllInterfaces, Some(service)) => (
+            RiskLevel::High,
+            vec![format!("{} (port {}) exposed on all interfaces", service, local_port)],
+        ),
+        (Exposure::SpecificAddress, Some(service)) => (
+            RiskLevel::Medium,
+            vec![format!("{} (port {}) reachable on a network address", service, local_port)],
+        ),
+        (Exposure::AllInterfaces, None) => (
+            RiskLevel::Low,
+            vec![format!("Listening on all interfaces (port {})", local_port)],
+        ),
+        (Exposure::SpecificAddress, None) => (
+            RiskLevel::Low,
+            vec![format!("Listening on a network address (port {})", local_port)],
+        ),
+        (Exposure::NamespaceOnly, Some(service)) => (
+            RiskLevel::Low,
+            vec![format!("{} (port {}) listening inside a container or network namespace", service, local_port)],
+        ),
+        (Exposure::NamespaceOnly, None) => (
+            RiskLevel::Low,
+            vec![format!("Listening inside a container or network namespace (port {})", local_port)],
+        ),
+        (Exposure::LoopbackOnly, _) => (RiskLevel::Low, vec!["Only reachable from this host".to_string()]),
+    }
+}
@@ -0,0 +1,52 @@
+use super::connection::{ConnectionDirection, RiskLevel};
+use serde::{Deserialize, Serialize};
+
+/// A span of polls during which a socket existed unchanged, as kept in the
+/// history store. A new state or risk starts a new interval; the host name
+/// is the latest seen.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionInterval {
+    pub id: i64,
+    pub process_name: String,
+    pub pid: u32,
+    pub namespace: Option<String>,
+    pub container: Option<String>,
+    pub systemd_unit: Option<String>,
+    pub protocol: String,
+    pub local_addr: String,
+    pub local_port: u16,
+    pub remote_addr: String,
+    pub remote_port: u16,
+    pub hostname: Option<String>, // resolved or PTR name
+    pub direction: ConnectionDirection,
+    pub state: String,
+    pub risk: RiskLevel,
+    pub risk_reasons: Vec<String>,
+    pub first_seen: u64,        // Unix timestamp in milliseconds
+    pub last_seen: Option<u64>, // None while the socket is still open
+}
+
+/// Narrows a history query. Every field is optional; text matches are
+/// case-insensitive substrings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryFilter {
+    pub process: Option<String>,
+    pub pid: Option<u32>,
+    pub protocol: Option<String>,
+    pub remote: Option<String>, // remote address or host name
+    pub port: Option<u16>,      // local or remote
+    pub min_risk: Option<RiskLevel>,
+    pub limit: Option<usize>,
+}
+
+/// The connections that existed at a moment, as seen by the last poll at
+/// or before it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistorySnapshot {
+    pub at: u64,                // requested time, Unix milliseconds
+    pub polled_at: Option<u64>, // None when no poll was recorded before `at`
+    pub connections: Vec<ConnectionInterval>,
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum InterfaceKind {
+    Ethernet,
+    Wifi,
+    Loopback,
+    Tun, // tun and tap devices, as used by most VPN clients
+    Wireguard,
+    Bridge,
+    Veth,
+    Vlan,
+    Bond,
+    #[default]
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterfaceAddress {
+    pub address: String,
+    pub prefix_len: u8,
+}
+
+/// Cumulative counters since the interface came up (`/proc/net/dev`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterfaceCounters {
+    pub rx_bytes: u64,
+    pub rx_packets: u64,
+    pub rx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_bytes: u64,
+    pub tx_packets: u64,
+    pub tx_errors: u64,
+    pub tx_dropped: u64,
+}
+
+/// Per-second rates of [`InterfaceCounters`] since the previous query.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterfaceRates {
+    pub rx_bytes: f64,
+    pub rx_packets: f64,
+    pub rx_errors: f64,
+    pub rx_dropped: f64,
+    pub tx_bytes: f64,
+    pub tx_packets: f64,
+    pub tx_errors: f64,
+    pub tx_dropped: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkInterface {
+    pub name: String,
+    pub index: u32,
+    pub kind: InterfaceKind,
+    pub mac_address: Option<String>,
+    pub mtu: u32,
+    pub oper_state: String, // "up", "down", "dormant", "unknown", ...
+    pub addresses: Vec<InterfaceAddress>,
+    pub counters: InterfaceCounters,
+    pub rates: Option<InterfaceRates>, // None on the first query
+}
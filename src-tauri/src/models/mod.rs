@@ -1,3 +1,35 @@
+pub mod address;
+pub mod alert;
+pub mod bandwidth;
+pub mod baseline;
+pub mod beaconing;
+pub mod capture;
 pub mod connection;
+pub mod container_rules;
+pub mod exposure;
+pub mod history;
+pub mod interface;
+pub mod neighbor;
+pub mod process;
+pub mod raw_socket_rules;
+pub mod route_rules;
+pub mod settings;
+pub mod tls_rules;
 
-pub use connection::*;
\ No newline at end of file
+pub use address::*;
+pub use alert::*;
+pub use bandwidth::*;
+pub use baseline::*;
+pub use beaconing::*;
+pub use capture::*;
+pub use connection::*;
+pub use container_rules::*;
+pub use exposure::*;
+pub use history::*;
+pub use interface::*;
+pub use neighbor::*;
+pub use process::*;
+pub use raw_socket_rules::*;
+pub use route_rules::*;
+pub use settings::*;
+pub use tls_rules::*;
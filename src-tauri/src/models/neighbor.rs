@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// An ARP (IPv4) or NDP (IPv6) neighbor table entry.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Neighbor {
+    pub ip_address: String,
+    pub mac_address: Option<String>, // None while resolution is incomplete or has failed
+    pub vendor: Option<String>,      // from the MAC's OUI
+    pub interface: String,
+    pub state: String,   // "reachable", "stale", "delay", "probe", "failed", "incomplete", "permanent"
+    pub is_router: bool, // advertised itself as a router (IPv6 only)
+}
@@ -0,0 +1,16 @@
+use super::connection::{ContainerInfo, SystemdUnit};
+use serde::{Deserialize, Serialize};
+
+/// What we know about a socket-owning process beyond its name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessDetails {
+    pub pid: u32,
+    pub name: String,
+    pub executable: Option<String>, // None when the process belongs to another user and we are not root
+    pub command_line: Vec<String>,
+    pub parent_pid: Option<u32>,
+    pub user_id: Option<u32>, // real UID
+    pub systemd_unit: Option<SystemdUnit>,
+    pub container: Option<ContainerInfo>,
+}
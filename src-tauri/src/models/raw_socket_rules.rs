@@ -0,0 +1,80 @@
+use super::tls_rules::glob_matches;
+use super::{Connection, RiskLevel};
+
+/// Processes that hold raw or packet sockets for a living: DHCP clients,
+/// network managers, 802.1X supplicants, LLDP and VRRP daemons, ping.
+const DEFAULT_ALLOWLIST: &[&str] = &[
+    "dhclient",
+    "dhcpcd",
+    "networkmanager",
+    "systemd-network*",
+    "wpa_supplicant",
+    "hostapd",
+    "lldpd",
+    "keepalived",
+    "ping",
+    "ping6",
+];
+
+/// Processes allowed to hold raw IP and packet sockets: the built-in list
+/// plus whatever the raw socket allowlist file adds.
+#[derive(Debug, Clone, Default)]
+pub struct RawSocketAllowlist {
+    processes: Vec<String>,
+}
+
+impl RawSocketAllowlist {
+    /// Each line is a process name, case-insensitive and possibly a `*`
+    /// glob, optionally followed by a note, e.g. `tcpdump ops debugging`;
+    /// `#` starts a comment line.
+    pub fn parse(content: &str) -> Self {
+        let processes = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| line.split_whitespace().next())
+            .map(str::to_lowercase)
+            .collect();
+        Self { processes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.processes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.processes.len()
+    }
+
+    /// Flags raw and packet sockets held by processes not on the allowlist.
+    /// Packet sockets see every frame on the link and rate high; raw IP
+    /// sockets rate medium. ICMP ping sockets need no privileges and pass.
+    pub fn apply(&self, connection: &mut Connection) {
+        let (level, kind) = match connection.protocol.as_str() {
+            "PACKET" => (RiskLevel::High, "packet"),
+            "RAW" => (RiskLevel::Medium, "raw IP"),
+            _ => return,
+        };
+        // Our own live capture opens a packet socket. Ownerless sockets belong
+        // to the kernel or to another PID namespace; there is no name to check
+        if connection.pid == std::process::id() || connection.pid == 0 {
+            return;
+        }
+        let process = connection.process_name.to_lowercase();
+        let allowed = DEFAULT_ALLOWLIST
+            .iter()
+            .copied()
+            .chain(self.processes.iter().map(String::as_str))
+            .any(|pattern| glob_matches(pattern.as_bytes(), process.as_bytes()));
+        if allowed {
+            return;
+        }
+
+        connection.risk_reasons.retain(|reason| reason != "Standard connection");
+        connection.risk_reasons.push(format!(
+            "{} holds a {} socket and is not on the raw socket allowlist",
+            connection.process_name, kind
+        ));
+        connection.risk = connection.risk.clone().max(level);
+    }
+}
@@ -0,0 +1,96 @@
+use super::tls_rules::glob_matches;
+use super::{Connection, RiskLevel};
+
+/// Which interfaces a process's traffic is expected to leave through, as
+/// loaded from the route policy file.
+#[derive(Debug, Clone, Default)]
+pub struct RouteRules {
+    policies: Vec<RoutePolicy>,
+}
+
+#[derive(Debug, Clone)]
+struct RoutePolicy {
+    process: String,
+    interfaces: Vec<String>,
+    note: String,
+}
+
+impl RouteRules {
+    /// Each line is `<process> <interface>[,<interface>...] [note]`, e.g.
+    /// `firefox tun*,wg0 browsing stays on the VPN`. Process names and
+    /// interfaces are case-insensitive and may use `*` globs; `#` starts a
+    /// comment line.
+    pub fn parse(content: &str) -> Self {
+        let policies = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.splitn(3, char::is_whitespace);
+                let process = fields.next()?.to_lowercase();
+                let interfaces = fields
+                    .next()?
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|interface| !interface.is_empty())
+                    .map(str::to_lowercase)
+                    .collect();
+                let note = fields.next().unwrap_or("").trim().to_string();
+                Some(RoutePolicy { process, interfaces, note })
+            })
+            .collect();
+        Self { policies }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.policies.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.policies.len()
+    }
+
+    /// Raises connections whose process has a policy but whose egress
+    /// interface is not one of the allowed ones. Connections without a
+    /// resolved route (listeners, loopback) are left alone.
+    pub fn apply(&self, connection: &mut Connection) {
+        let Some(route) = &connection.route else {
+            return;
+        };
+        let process = connection.process_name.to_lowercase();
+        let interface = route.interface.to_lowercase();
+
+        let mut reasons = Vec::new();
+        for policy in &self.policies {
+            if !glob_matches(policy.process.as_bytes(), process.as_bytes()) {
+                continue;
+            }
+            if policy
+                .interfaces
+                .iter()
+                .any(|allowed| glob_matches(allowed.as_bytes(), interface.as_bytes()))
+            {
+                continue;
+            }
+
+            let reason = format!(
+                "{} routed via {}, expected {}",
+                connection.process_name,
+                route.interface,
+                policy.interfaces.join(" or ")
+            );
+            reasons.push(if policy.note.is_empty() {
+                reason
+            } else {
+                format!("{} ({})", reason, policy.note)
+            });
+        }
+
+        if reasons.is_empty() {
+            return;
+        }
+        connection.risk_reasons.retain(|reason| reason != "Standard connection");
+        connection.risk_reasons.extend(reasons);
+        connection.risk = RiskLevel::High;
+    }
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+/// User-tunable backend settings, persisted as `settings.json` in the app data dir.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub reverse_dns: ReverseDnsSettings,
+    pub history: HistorySettings,
+    pub beaconing: BeaconingSettings,
+    pub baseline: BaselineSettings,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReverseDnsSettings {
+    pub enabled: bool,
+    /// `None` uses the operating system resolver, otherwise PTR queries are
+    /// sent straight to this DNS server ("192.168.1.1" or "127.0.0.1:5353").
+    pub server: Option<String>,
+    pub max_concurrent_lookups: usize,
+    pub timeout_ms: u64,
+    pub positive_ttl_secs: u64,
+    pub negative_ttl_secs: u64,
+    pub max_cache_entries: usize,
+}
+
+impl Default for ReverseDnsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            server: None,
+            max_concurrent_lookups: 8,
+            timeout_ms: 2000,
+            positive_ttl_secs: 3600,
+            negative_ttl_secs: 300,
+            max_cache_entries: 4096,
+        }
+    }
+}
+
+/// Connection history kept in `history.sqlite3` in the app data dir.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistorySettings {
+    pub enabled: bool,
+    pub retention_days: u32,
+    /// The oldest history is dropped once the database grows past this.
+    pub max_size_mb: u64,
+}
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            retention_days: 30,
+            max_size_mb: 512,
+        }
+    }
+}
+
+/// Thresholds for spotting periodic outbound connects in the history.
+/// Polled history only holds sockets a poll found open, so connects that
+/// close between polls are missed and periods are only accurate to the
+/// refresh interval, unless live capture is running.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BeaconingSettings {
+    pub enabled: bool,
+    pub window_hours: u32, // how much history each analysis looks at
+    pub min_connections: usize,
+    /// Largest coefficient of variation (jitter / period) still counted as periodic.
+    pub max_variation: f64,
+    pub min_period_secs: u64,
+    pub max_period_secs: u64,
+}
+
+impl Default for BeaconingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_hours: 24,
+            min_connections: 8,
+            max_variation: 0.15,
+            min_period_secs: 10,
+            max_period_secs: 6 * 60 * 60,
+        }
+    }
+}
+
+/// Baseline learning mode; see `start_baseline_learning`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BaselineSettings {
+    pub learning_window_hours: u32,
+    /// Peers without a known ASN are grouped into blocks of these sizes. A
+    /// baseline keeps the sizes it was learned with.
+    pub ipv4_prefix_len: u8,
+    pub ipv6_prefix_len: u8,
+}
+
+impl Default for BaselineSettings {
+    fn default() -> Self {
+        Self {
+            learning_window_hours: 7 * 24,
+            ipv4_prefix_len: 24,
+            ipv6_prefix_len: 48,
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use super::{Connection, RiskLevel};
+
+/// Known-bad TLS client fingerprints and server names, as loaded from the
+/// rules files. Each entry keeps the free-text note that followed it.
+#[derive(Debug, Clone, Default)]
+pub struct TlsRules {
+    fingerprints: Vec<(String, String)>,
+    sni_patterns: Vec<(String, String)>,
+}
+
+impl TlsRules {
+    /// Fingerprint lines hold a JA3 hash or a JA4 string; SNI lines hold a
+    /// domain (matching it and its subdomains) or a `*` glob. Anything after
+    /// the first whitespace is a note, and `#` starts a comment line.
+    pub fn parse(fingerprints: &str, sni_patterns: &str) -> Self {
+        Self {
+            fingerprints: parse_entries(fingerprints),
+            sni_patterns: parse_entries(sni_patterns),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fingerprints.is_empty() && self.sni_patterns.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.fingerprints.len() + self.sni_patterns.len()
+    }
+
+    /// Raises matching connections to high risk with a reason per rule hit.
+    pub fn apply(&self, connection: &mut Connection) {
+        let Some(tls) = &connection.tls else {
+            return;
+        };
+
+        let mut reasons = Vec::new();
+        for (fingerprint, note) in &self.fingerprints {
+            let kind = if *fingerprint == tls.ja3_hash {
+                "JA3"
+            } else if *fingerprint == tls.ja4 {
+                "JA4"
+            } else {
+                continue;
+            };
+            reasons.push(with_note(format!("Known-bad {} fingerprint {}", kind, fingerprint), note));
+        }
+
+        if let Some(sni) = &tls.sni {
+            let sni = sni.to_lowercase();
+            for (pattern, note) in &self.sni_patterns {
+                if sni_matches(pattern, &sni) {
+                    reasons.push(with_note(format!("TLS server name {} matches {}", sni, pattern), note));
+                }
+            }
+        }
+
+        if reasons.is_empty() {
+            return;
+        }
+        connection.risk_reasons.retain(|reason| reason != "Standard connection");
+        connection.risk_reasons.extend(reasons);
+        connection.risk = RiskLevel::High;
+    }
+}
+
+fn parse_entries(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| match line.split_once(char::is_whitespace) {
+            Some((value, note)) => (value.to_lowercase(), note.trim().to_string()),
+            None => (line.to_lowercase(), String::new()),
+        })
+        .collect()
+}
+
+fn with_note(reason: String, note: &str) -> String {
+    if note.is_empty() {
+        reason
+    } else {
+        format!("{} ({})", reason, note)
+    }
+}
+
+fn sni_matches(pattern: &str, sni: &str) -> bool {
+    if pattern.contains('*') {
+        glob_matches(pattern.as_bytes(), sni.as_bytes())
+    } else {
+        sni == pattern || sni.strip_suffix(pattern).is_some_and(|prefix| prefix.ends_with('.'))
+    }
+}
+
+/// `*` matches any run of characters, dots included.
+pub(super) fn glob_matches(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == b'*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == text[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last star swallow one more character and retry
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glob(pattern: &str, text: &str) -> bool {
+        glob_matches(pattern.as_bytes(), text.as_bytes())
+    }
+
+    #[test]
+    fn glob_matches_literals_and_stars() {
+        assert!(glob("sshd", "sshd"));
+        assert!(!glob("sshd", "sshd2"));
+        assert!(!glob("sshd", "ssh"));
+        assert!(glob("*", ""));
+        assert!(glob("*", "anything.at.all"));
+        assert!(glob("*.example.com", "cdn.eu.example.com"));
+        assert!(!glob("*.example.com", "example.com"));
+        assert!(glob("wg*", "wg0"));
+        assert!(glob("a*b*c", "axxbyyc"));
+        assert!(!glob("a*b*c", "axxbyy"));
+        assert!(glob("**", "x"));
+        assert!(!glob("", "x"));
+        assert!(glob("", ""));
+    }
+
+    #[test]
+    fn glob_backtracks_to_the_last_star() {
+        assert!(glob("*abc", "ababc"));
+        assert!(glob("a*ab", "aaab"));
+        assert!(glob("*.evil.*", "login.evil.example"));
+        assert!(!glob("*.evil.*", "login.evil"));
+        assert!(glob("*x*x*x", "axbxcxdx"));
+    }
+
+    #[test]
+    fn sni_domains_match_subdomains_only_at_label_boundaries() {
+        assert!(sni_matches("example.com", "example.com"));
+        assert!(sni_matches("example.com", "www.example.com"));
+        assert!(!sni_matches("example.com", "badexample.com"));
+        assert!(sni_matches("*.example.*", "api.example.net"));
+    }
+}
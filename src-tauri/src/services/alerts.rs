@@ -0,0 +1,43 @@
+use crate::models::Alert;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Alerts kept for `get_alerts`; older ones are dropped first.
+const MAX_ALERTS: usize = 500;
+
+type AlertListener = Box<dyn Fn(&Alert) + Send + Sync>;
+
+/// Recent alerts from every detector, newest last. A listener, set once the
+/// app is up, forwards each new alert to the frontend as it is raised.
+#[derive(Default)]
+pub struct AlertLog {
+    alerts: Mutex<VecDeque<Alert>>,
+    listener: Mutex<Option<AlertListener>>,
+}
+
+impl AlertLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_listener(&self, listener: impl Fn(&Alert) + Send + Sync + 'static) {
+        *self.listener.lock().unwrap() = Some(Box::new(listener));
+    }
+
+    pub fn raise(&self, alert: Alert) {
+        tracing::warn!("Alert: {}", alert.message);
+        if let Some(listener) = self.listener.lock().unwrap().as_ref() {
+            listener(&alert);
+        }
+
+        let mut alerts = self.alerts.lock().unwrap();
+        alerts.push_back(alert);
+        while alerts.len() > MAX_ALERTS {
+            alerts.pop_front();
+        }
+    }
+
+    pub fn list(&self) -> Vec<Alert> {
+        self.alerts.lock().unwrap().iter().cloned().collect()
+    }
+}
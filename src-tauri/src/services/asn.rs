@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// IP range to autonomous system lookup from the iptoasn.com TSV dumps
+/// (`ip2asn-v4.tsv`, `ip2asn-v6.tsv` or `ip2asn-combined.tsv`) dropped into
+/// `<app data>/asn/`. Empty when no dump is present.
+#[derive(Default)]
+pub struct AsnTable {
+    // (range start, range end, AS number), sorted by start; IPv4 as mapped IPv6
+    ranges: Vec<(u128, u128, u32)>,
+    names: HashMap<u32, String>,
+}
+
+impl AsnTable {
+    pub fn load(dir: &Path) -> Self {
+        let mut table = Self::default();
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return table;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().and_then(|ext| ext.to_str()) != Some("tsv") {
+                continue;
+            }
+            match std::fs::read_to_string(&path) {
+                Ok(content) => table.parse(&content),
+                Err(e) => tracing::warn!("Failed to read ASN table {}: {}", path.display(), e),
+            }
+        }
+
+        table.ranges.sort_unstable();
+        if !table.ranges.is_empty() {
+            tracing::debug!("Loaded {} ASN ranges from {}", table.ranges.len(), dir.display());
+        }
+        table
+    }
+
+    /// `range_start<tab>range_end<tab>AS_number<tab>country_code<tab>AS_description`;
+    /// AS 0 marks unrouted space.
+    fn parse(&mut self, content: &str) {
+        for line in content.lines() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() < 3 {
+                continue;
+            }
+            let (Ok(start), Ok(end), Ok(asn)) = (
+                fields[0].parse::<IpAddr>(),
+                fields[1].parse::<IpAddr>(),
+                fields[2].parse::<u32>(),
+            ) else {
+                continue;
+            };
+            if asn == 0 {
+                continue;
+            }
+            self.ranges.push((to_u128(start), to_u128(end), asn));
+            if let Some(name) = fields.get(4).filter(|name| !name.is_empty() && **name != "Not routed") {
+                self.names.entry(asn).or_insert_with(|| name.to_string());
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn lookup(&self, ip: IpAddr) -> Option<u32> {
+        let ip = to_u128(ip);
+        let index = self.ranges.partition_point(|(start, _, _)| *start <= ip).checked_sub(1)?;
+        let (_, end, asn) = self.ranges[index];
+        (ip <= end).then_some(asn)
+    }
+
+    pub fn name(&self, asn: u32) -> Option<&str> {
+        self.names.get(&asn).map(String::as_str)
+    }
+}
+
+/// Latest modification time of `dir` and the dumps in it, which changes when
+/// a dump is added, replaced or removed.
+pub fn last_modified(dir: &Path) -> Option<SystemTime> {
+    let mut latest = std::fs::metadata(dir).and_then(|metadata| metadata.modified()).ok()?;
+    for entry in std::fs::read_dir(dir).ok()?.flatten() {
+        if let Ok(modified) = entry.metadata().and_then(|metadata| metadata.modified()) {
+            latest = latest.max(modified);
+        }
+    }
+    Some(latest)
+}
+
+fn to_u128(ip: IpAddr) -> u128 {
+    match ip {
+        IpAddr::V4(v4) => u128::from(v4.to_ipv6_mapped()),
+        IpAddr::V6(v6) => u128::from(v6),
+    }
+}
@@ -0,0 +1,228 @@
+use crate::models::{
+    AddressClass, Connection, HostTalker, ProcessTalker, TalkerWindow, Throughput, TopTalkers, UnitTalker,
+};
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Ranked entries returned per category.
+const MAX_TALKERS: usize = 20;
+
+/// Turns cumulative per-socket byte counters (`tcp_info`, or captured flows)
+/// into per-poll deltas. Deltas set the socket's own rates and are kept,
+/// grouped by process, systemd unit and remote host, for the top talker windows.
+#[derive(Default)]
+pub struct BandwidthTracker {
+    state: Mutex<TrackerState>,
+}
+
+#[derive(Default)]
+struct TrackerState {
+    started_at: Option<Instant>,
+    previous: HashMap<String, Counters>,
+    samples: VecDeque<PollSample>,
+}
+
+struct Counters {
+    at: Instant,
+    sent: u64,
+    received: u64,
+}
+
+/// Bytes moved between two polls.
+struct PollSample {
+    at: Instant,
+    processes: HashMap<(u32, String), (u64, u64)>,
+    units: HashMap<String, (u64, u64)>,
+    hosts: HashMap<String, (Option<String>, u64, u64)>,
+}
+
+impl BandwidthTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn annotate(&self, connections: &mut [Connection]) {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        let first_poll = state.started_at.is_none();
+        state.started_at.get_or_insert(now);
+
+        let mut current = HashMap::with_capacity(state.previous.len());
+        let mut sample = PollSample {
+            at: now,
+            processes: HashMap::new(),
+            units: HashMap::new(),
+            hosts: HashMap::new(),
+        };
+
+        for conn in connections.iter_mut() {
+            let Some((sent, received)) = byte_counters(conn) else {
+                continue;
+            };
+            let key = socket_key(conn);
+
+            let delta = match state.previous.get(&key) {
+                // Counters going backwards means the 4-tuple was reused by a new socket
+                Some(previous) if sent >= previous.sent && received >= previous.received => {
+                    let elapsed = now.duration_since(previous.at).as_secs_f64();
+                    if let Some(metrics) = conn.tcp_metrics.as_mut().filter(|_| elapsed > 0.0) {
+                        metrics.send_rate = Some((sent - previous.sent) as f64 / elapsed);
+                        metrics.receive_rate = Some((received - previous.received) as f64 / elapsed);
+                    }
+                    Some((sent - previous.sent, received - previous.received))
+                }
+                // A socket that appeared since the last poll moved all of its bytes since then
+                _ if !first_poll => Some((sent, received)),
+                _ => None,
+            };
+            current.insert(key, Counters { at: now, sent, received });
+
+            let Some((sent, received)) = delta.filter(|(sent, received)| sent + received > 0) else {
+                continue;
+            };
+            // Loopback traffic never reaches the uplink, and both ends would count it
+            if conn.remote_class == AddressClass::Loopback {
+                continue;
+            }
+            let process = sample
+                .processes
+                .entry((conn.pid, conn.process_name.clone()))
+                .or_default();
+            process.0 += sent;
+            process.1 += received;
+
+            if let Some(unit) = &conn.systemd_unit {
+                let unit = sample.units.entry(unit.name.clone()).or_default();
+                unit.0 += sent;
+                unit.1 += received;
+            }
+
+            let host = sample.hosts.entry(conn.remote_addr.clone()).or_default();
+            if host.0.is_none() {
+                host.0 = conn.resolved_name.clone().or_else(|| conn.hostname.clone());
+            }
+            host.1 += sent;
+            host.2 += received;
+        }
+
+        // Sockets missing from this poll are gone
+        state.previous = current;
+        state.samples.push_back(sample);
+        let longest = TalkerWindow::longest().duration();
+        while state.samples.front().is_some_and(|s| now.duration_since(s.at) > longest) {
+            state.samples.pop_front();
+        }
+    }
+
+    /// Processes, units and remote hosts ranked by bytes moved within `window`.
+    /// Only traffic seen between polls counts, so it needs regular polling.
+    pub fn top_talkers(&self, window: TalkerWindow) -> TopTalkers {
+        let now = Instant::now();
+        let state = self.state.lock().unwrap();
+        let span = window.duration();
+        let observed = state
+            .started_at
+            .map_or(Duration::ZERO, |started| now.duration_since(started).min(span));
+
+        let mut processes: HashMap<(u32, String), (u64, u64)> = HashMap::new();
+        let mut units: HashMap<String, (u64, u64)> = HashMap::new();
+        let mut hosts: HashMap<String, (Option<String>, u64, u64)> = HashMap::new();
+        for sample in state.samples.iter().filter(|s| now.duration_since(s.at) <= span) {
+            for (process, (sent, received)) in &sample.processes {
+                let entry = processes.entry(process.clone()).or_default();
+                entry.0 += sent;
+                entry.1 += received;
+            }
+            for (unit, (sent, received)) in &sample.units {
+                let entry = units.entry(unit.clone()).or_default();
+                entry.0 += sent;
+                entry.1 += received;
+            }
+            for (addr, (name, sent, received)) in &sample.hosts {
+                let entry = hosts.entry(addr.clone()).or_default();
+                if name.is_some() {
+                    entry.0 = name.clone();
+                }
+                entry.1 += sent;
+                entry.2 += received;
+            }
+        }
+
+        let seconds = observed.as_secs_f64().max(1.0);
+        let throughput = |sent: u64, received: u64| Throughput {
+            bytes_sent: sent,
+            bytes_received: received,
+            send_rate: sent as f64 / seconds,
+            receive_rate: received as f64 / seconds,
+        };
+
+        let mut process_talkers: Vec<ProcessTalker> = processes
+            .into_iter()
+            .map(|((pid, process_name), (sent, received))| ProcessTalker {
+                pid,
+                process_name,
+                throughput: throughput(sent, received),
+            })
+            .collect();
+        process_talkers.sort_by_key(|talker| Reverse(talker.throughput.total_bytes()));
+        process_talkers.truncate(MAX_TALKERS);
+
+        let mut unit_talkers: Vec<UnitTalker> = units
+            .into_iter()
+            .map(|(unit, (sent, received))| UnitTalker {
+                unit,
+                throughput: throughput(sent, received),
+            })
+            .collect();
+        unit_talkers.sort_by_key(|talker| Reverse(talker.throughput.total_bytes()));
+        unit_talkers.truncate(MAX_TALKERS);
+
+        let mut host_talkers: Vec<HostTalker> = hosts
+            .into_iter()
+            .map(|(remote_addr, (hostname, sent, received))| HostTalker {
+                remote_addr,
+                hostname,
+                throughput: throughput(sent, received),
+            })
+            .collect();
+        host_talkers.sort_by_key(|talker| Reverse(talker.throughput.total_bytes()));
+        host_talkers.truncate(MAX_TALKERS);
+
+        TopTalkers {
+            window,
+            generated_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            observed_secs: observed.as_secs_f64(),
+            processes: process_talkers,
+            units: unit_talkers,
+            remote_hosts: host_talkers,
+        }
+    }
+}
+
+/// Cumulative (sent, received) bytes: the kernel's TCP counters when we
+/// have them, otherwise a captured flow's.
+fn byte_counters(conn: &Connection) -> Option<(u64, u64)> {
+    if let Some(metrics) = &conn.tcp_metrics {
+        return Some((metrics.bytes_acked, metrics.bytes_received));
+    }
+    conn.flow.as_ref().map(|flow| (flow.bytes_sent, flow.bytes_received))
+}
+
+fn socket_key(conn: &Connection) -> String {
+    // Containers commonly reuse the same addresses, so the namespace is part of the key
+    format!(
+        "{}|{}|{}|{}:{}|{}:{}",
+        conn.namespace.as_ref().map_or(0, |ns| ns.id),
+        conn.protocol,
+        conn.pid,
+        conn.local_addr,
+        conn.local_port,
+        conn.remote_addr,
+        conn.remote_port
+    )
+}
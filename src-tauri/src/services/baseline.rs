@@ -0,0 +1,455 @@
+use super::asn::{self, AsnTable};
+use super::direction::{ephemeral_port_range, is_service_listener};
+use crate::models::{
+    parse_ip_address, Alert, AlertKind, AddressClass, Baseline, BaselineSettings, BaselineState, BaselineStatus,
+    BaselineTuple, Connection, ConnectionDirection, PeerKeying, RiskLevel,
+};
+use std::collections::{BTreeSet, HashSet};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::SystemTime;
+
+const BASELINE_FILE: &str = "baseline.json";
+const ASN_DIR: &str = "asn";
+
+/// Learning mode: records the (process, port, peer, direction) tuples seen
+/// while learning, then flags connections whose tuple the baseline lacks.
+/// Peers are autonomous systems when an ASN table is installed, otherwise
+/// address blocks of the configured prefix length; the choice is made when
+/// learning starts and kept with the baseline.
+pub struct BaselineLearner {
+    path: PathBuf,
+    asn_dir: PathBuf,
+    // reloaded when the dumps in `asn_dir` change
+    asn: Mutex<Option<(Option<SystemTime>, Arc<AsnTable>)>>,
+    settings: RwLock<BaselineSettings>,
+    ephemeral_ports: (u16, u16),
+    state: Mutex<LearnerState>,
+}
+
+#[derive(Default)]
+struct LearnerState {
+    baseline: Option<Baseline>,
+    // novel tuples already alerted on since the baseline last changed
+    reported: HashSet<BaselineTuple>,
+    // enforcement is paused because the ASN table the baseline needs is gone
+    missing_asn_logged: bool,
+}
+
+impl BaselineLearner {
+    pub fn new(app_data_dir: &Path, settings: BaselineSettings) -> Self {
+        let path = app_data_dir.join(BASELINE_FILE);
+        let baseline = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)
+                .inspect_err(|e| tracing::warn!("Ignoring invalid baseline {}: {}", path.display(), e))
+                .ok(),
+            Err(_) => None,
+        };
+
+        Self {
+            path,
+            asn_dir: app_data_dir.join(ASN_DIR),
+            asn: Mutex::new(None),
+            settings: RwLock::new(settings),
+            ephemeral_ports: ephemeral_port_range(),
+            state: Mutex::new(LearnerState {
+                baseline,
+                reported: HashSet::new(),
+                missing_asn_logged: false,
+            }),
+        }
+    }
+
+    pub fn configure(&self, settings: BaselineSettings) {
+        *self.settings.write().unwrap() = settings;
+    }
+
+    pub fn status(&self) -> BaselineStatus {
+        let state = self.state.lock().unwrap();
+        match &state.baseline {
+            Some(baseline) => baseline.status(now_millis()),
+            None => BaselineStatus {
+                state: BaselineState::None,
+                learning_started_at: None,
+                learning_ends_at: None,
+                tuples: 0,
+            },
+        }
+    }
+
+    /// Throws away any current baseline and learns a new one over the
+    /// configured window.
+    pub fn start_learning(&self) -> Result<BaselineStatus, String> {
+        let settings = self.settings.read().unwrap().clone();
+        let peer_keying = self.current_keying(&settings);
+        let now = now_millis();
+        let baseline = Baseline {
+            learning_started_at: now,
+            learning_ends_at: now + u64::from(settings.learning_window_hours) * 60 * 60 * 1000,
+            peer_keying: Some(peer_keying),
+            tuples: BTreeSet::new(),
+        };
+        save(&self.path, &baseline)?;
+        tracing::info!(
+            "Learning a connection baseline for {} hours, keying peers by {}",
+            settings.learning_window_hours,
+            peer_keying.describe()
+        );
+
+        let status = baseline.status(now);
+        let mut state = self.state.lock().unwrap();
+        state.baseline = Some(baseline);
+        state.reported.clear();
+        state.missing_asn_logged = false;
+        Ok(status)
+    }
+
+    /// Writes the baseline to `dir` for rolling out to other machines.
+    pub fn export(&self, dir: &Path) -> Result<PathBuf, String> {
+        let state = self.state.lock().unwrap();
+        let baseline = state.baseline.as_ref().ok_or("No baseline has been learned yet")?;
+        let path = dir.join(format!("netwatch_baseline_{}.json", now_millis() / 1000));
+        save(&path, baseline)?;
+        Ok(path)
+    }
+
+    /// Replaces the baseline with an exported one, which is enforced right
+    /// away even if its learning window had not ended. Refused when the
+    /// baseline keys peers by autonomous system and no ASN table is installed.
+    pub fn import(&self, path: &Path) -> Result<BaselineStatus, String> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let mut baseline: Baseline =
+            serde_json::from_str(&content).map_err(|e| format!("Invalid baseline {}: {}", path.display(), e))?;
+        match baseline.peer_keying {
+            Some(keying) if keying.asn && self.asn_table().is_empty() => {
+                return Err(format!(
+                    "The baseline keys peers by autonomous system; install the ASN table it was learned with in {} first",
+                    self.asn_dir.display()
+                ));
+            }
+            Some(_) => {}
+            None => tracing::warn!(
+                "Baseline {} doesn't record how its peers were keyed; assuming this machine's settings",
+                path.display()
+            ),
+        }
+        let now = now_millis();
+        baseline.learning_ends_at = baseline.learning_ends_at.min(now);
+        save(&self.path, &baseline)?;
+        tracing::info!("Imported a baseline of {} tuples from {}", baseline.tuples.len(), path.display());
+
+        let status = baseline.status(now);
+        let mut state = self.state.lock().unwrap();
+        state.baseline = Some(baseline);
+        state.reported.clear();
+        state.missing_asn_logged = false;
+        Ok(status)
+    }
+
+    /// Records tuples while learning; afterwards flags connections with a
+    /// tuple the baseline lacks and returns alerts for the ones not reported
+    /// before.
+    pub fn observe(&self, connections: &mut [Connection]) -> Vec<Alert> {
+        let mut state = self.state.lock().unwrap();
+        let LearnerState {
+            baseline,
+            reported,
+            missing_asn_logged,
+        } = &mut *state;
+        let Some(baseline) = baseline.as_mut() else {
+            return Vec::new();
+        };
+        let asn = self.asn_table();
+        // Baselines from before the keying was recorded follow the settings
+        let keying = baseline
+            .peer_keying
+            .unwrap_or_else(|| self.current_keying(&self.settings.read().unwrap()));
+        if keying.asn && asn.is_empty() {
+            if !*missing_asn_logged {
+                tracing::warn!(
+                    "Baseline keys peers by autonomous system but {} has no ASN table; not enforcing it",
+                    self.asn_dir.display()
+                );
+                *missing_asn_logged = true;
+            }
+            return Vec::new();
+        }
+        *missing_asn_logged = false;
+        let now = connections.first().map_or_else(now_millis, |conn| conn.captured_at);
+
+        if baseline.is_learning(now) {
+            let before = baseline.tuples.len();
+            for conn in connections.iter() {
+                if let Some((tuple, _)) = baseline_tuple(conn, &asn, keying, self.ephemeral_ports) {
+                    baseline.tuples.insert(tuple);
+                }
+            }
+            if baseline.tuples.len() != before {
+                if let Err(e) = save(&self.path, baseline) {
+                    tracing::warn!("{}", e);
+                }
+            }
+            return Vec::new();
+        }
+
+        let mut alerts = Vec::new();
+        for conn in connections.iter_mut() {
+            let Some((tuple, peer_name)) = baseline_tuple(conn, &asn, keying, self.ephemeral_ports) else {
+                continue;
+            };
+            if baseline.tuples.contains(&tuple) {
+                continue;
+            }
+
+            let finding = tuple.describe_novel();
+            conn.risk_reasons.retain(|reason| reason != "Standard connection");
+            conn.risk_reasons.push(format!("{} (not in baseline)", finding));
+            conn.risk = conn.risk.clone().max(RiskLevel::Medium);
+
+            if reported.insert(tuple.clone()) {
+                let mut details = vec![
+                    format!("Process: {} (PID {})", conn.process_name, conn.pid),
+                    format!(
+                        "Connection: {} {}:{} -> {}:{}",
+                        conn.protocol, conn.local_addr, conn.local_port, conn.remote_addr, conn.remote_port
+                    ),
+                ];
+                if let Some(name) = peer_name {
+                    details.push(format!("Peer: {} {}", tuple.peer, name));
+                }
+                alerts.push(Alert::new(AlertKind::NovelConnection, RiskLevel::Medium, finding, details));
+            }
+        }
+        alerts
+    }
+
+    /// The ASN table, reloaded when a dump in `asn/` is added, replaced or
+    /// removed.
+    fn asn_table(&self) -> Arc<AsnTable> {
+        let modified = asn::last_modified(&self.asn_dir);
+        let mut cached = self.asn.lock().unwrap();
+        match &*cached {
+            Some((loaded, table)) if *loaded == modified => table.clone(),
+            _ => {
+                let table = Arc::new(AsnTable::load(&self.asn_dir));
+                *cached = Some((modified, table.clone()));
+                table
+            }
+        }
+    }
+
+    fn current_keying(&self, settings: &BaselineSettings) -> PeerKeying {
+        PeerKeying {
+            asn: !self.asn_table().is_empty(),
+            ipv4_prefix_len: settings.ipv4_prefix_len.min(32),
+            ipv6_prefix_len: settings.ipv6_prefix_len.min(128),
+        }
+    }
+}
+
+/// The tuple for a TCP or UDP socket, with the AS name when the peer is one.
+fn baseline_tuple<'a>(
+    conn: &Connection,
+    asn: &'a AsnTable,
+    keying: PeerKeying,
+    ephemeral_ports: (u16, u16),
+) -> Option<(BaselineTuple, Option<&'a str>)> {
+    if !matches!(conn.protocol.as_str(), "TCP" | "UDP") {
+        return None;
+    }
+    // Unconnected UDP clients are "listening" on a fresh ephemeral port each time
+    if conn.direction == ConnectionDirection::Listening && !is_service_listener(conn, ephemeral_ports) {
+        return None;
+    }
+
+    let (port, peer, peer_name) = match conn.direction {
+        ConnectionDirection::Listening => (conn.local_port, "*".to_string(), None),
+        direction => {
+            let remote = parse_ip_address(&conn.remote_addr).filter(|ip| !ip.is_unspecified())?;
+            let port = if direction == ConnectionDirection::Inbound {
+                conn.local_port
+            } else {
+                conn.remote_port
+            };
+            match asn
+                .lookup(remote)
+                .filter(|_| keying.asn && conn.remote_class == AddressClass::Public)
+            {
+                Some(number) => (port, format!("AS{}", number), asn.name(number)),
+                None => (port, address_block(remote, keying), None),
+            }
+        }
+    };
+
+    Some((
+        BaselineTuple {
+            process_name: conn.process_name.clone(),
+            port,
+            peer,
+            direction: conn.direction,
+        },
+        peer_name,
+    ))
+}
+
+fn address_block(ip: IpAddr, keying: PeerKeying) -> String {
+    match ip {
+        IpAddr::V4(v4) => {
+            let prefix_len = keying.ipv4_prefix_len.min(32);
+            let mask = u32::MAX.checked_shl(32 - u32::from(prefix_len)).unwrap_or(0);
+            format!("{}/{}", std::net::Ipv4Addr::from(u32::from(v4) & mask), prefix_len)
+        }
+        IpAddr::V6(v6) => {
+            let prefix_len = keying.ipv6_prefix_len.min(128);
+            let mask = u128::MAX.checked_shl(128 - u32::from(prefix_len)).unwrap_or(0);
+            format!("{}/{}", std::net::Ipv6Addr::from(u128::from(v6) & mask), prefix_len)
+        }
+    }
+}
+
+fn save(path: &Path, baseline: &Baseline) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(baseline).map_err(|e| format!("JSON serialization error: {}", e))?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write baseline {}: {}", path.display(), e))
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GOOGLE_DUMP: &str = "8.8.8.0\t8.8.8.255\t15169\tUS\tGOOGLE\n";
+
+    fn app_data_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("netwatch-baseline-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn install_dump(dir: &Path) {
+        std::fs::create_dir_all(dir.join(ASN_DIR)).unwrap();
+        std::fs::write(dir.join(ASN_DIR).join("ip2asn-v4.tsv"), GOOGLE_DUMP).unwrap();
+    }
+
+    fn outbound(remote_addr: &str, captured_at: u64) -> Connection {
+        Connection {
+            process_name: "curl".to_string(),
+            protocol: "TCP".to_string(),
+            remote_addr: remote_addr.to_string(),
+            remote_port: 443,
+            remote_class: AddressClass::Public,
+            direction: ConnectionDirection::Outbound,
+            captured_at,
+            ..Default::default()
+        }
+    }
+
+    fn write_baseline(dir: &Path, peer_keying: Option<PeerKeying>, peer: &str) -> PathBuf {
+        let baseline = Baseline {
+            learning_started_at: 0,
+            learning_ends_at: 1,
+            peer_keying,
+            tuples: BTreeSet::from([BaselineTuple {
+                process_name: "curl".to_string(),
+                port: 443,
+                peer: peer.to_string(),
+                direction: ConnectionDirection::Outbound,
+            }]),
+        };
+        let path = dir.join("golden.json");
+        save(&path, &baseline).unwrap();
+        path
+    }
+
+    #[test]
+    fn learning_records_how_peers_are_keyed() {
+        let dir = app_data_dir("keying");
+        let settings = BaselineSettings {
+            ipv4_prefix_len: 16,
+            ..Default::default()
+        };
+        let learner = BaselineLearner::new(&dir, settings);
+        learner.start_learning().unwrap();
+        let keying = learner.state.lock().unwrap().baseline.as_ref().unwrap().peer_keying;
+        assert_eq!(
+            keying,
+            Some(PeerKeying {
+                asn: false,
+                ipv4_prefix_len: 16,
+                ipv6_prefix_len: 48,
+            })
+        );
+
+        // Later settings don't change how the baseline keys peers
+        learner.configure(BaselineSettings::default());
+        let saved: Baseline = serde_json::from_str(&std::fs::read_to_string(dir.join(BASELINE_FILE)).unwrap()).unwrap();
+        assert_eq!(saved.peer_keying, keying);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn asn_keyed_import_waits_for_an_asn_table() {
+        let dir = app_data_dir("import");
+        let keying = PeerKeying {
+            asn: true,
+            ipv4_prefix_len: 24,
+            ipv6_prefix_len: 48,
+        };
+        let path = write_baseline(&dir, Some(keying), "AS15169");
+        let learner = BaselineLearner::new(&dir, BaselineSettings::default());
+        assert!(learner.import(&path).is_err());
+
+        // The dump is picked up without restarting
+        install_dump(&dir);
+        assert_eq!(learner.import(&path).unwrap().tuples, 1);
+        assert!(learner.observe(&mut [outbound("8.8.8.8", 2)]).is_empty());
+        assert_eq!(learner.observe(&mut [outbound("1.1.1.1", 2)]).len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn block_keyed_baseline_ignores_a_local_asn_table() {
+        let dir = app_data_dir("blocks");
+        install_dump(&dir);
+        let keying = PeerKeying {
+            asn: false,
+            ipv4_prefix_len: 16,
+            ipv6_prefix_len: 48,
+        };
+        let path = write_baseline(&dir, Some(keying), "8.8.0.0/16");
+        let learner = BaselineLearner::new(&dir, BaselineSettings::default());
+        learner.import(&path).unwrap();
+        assert!(learner.observe(&mut [outbound("8.8.4.4", 2)]).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn asn_keyed_baseline_pauses_when_the_table_is_removed() {
+        let dir = app_data_dir("removed");
+        install_dump(&dir);
+        let keying = PeerKeying {
+            asn: true,
+            ipv4_prefix_len: 24,
+            ipv6_prefix_len: 48,
+        };
+        let path = write_baseline(&dir, Some(keying), "AS15169");
+        let learner = BaselineLearner::new(&dir, BaselineSettings::default());
+        learner.import(&path).unwrap();
+        assert_eq!(learner.observe(&mut [outbound("1.1.1.1", 2)]).len(), 1);
+
+        std::fs::remove_dir_all(dir.join(ASN_DIR)).unwrap();
+        assert!(learner.observe(&mut [outbound("9.9.9.9", 2)]).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,406 @@
+use super::capture::CapturedConnect;
+use super::history::HistoryStore;
+use crate::models::{
+    format_secs, parse_ip_address, Alert, AlertKind, BeaconFinding, BeaconingSettings, ConnectEvent, Connection,
+    ConnectionDirection, RiskLevel,
+};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// Beacons change slowly; the history is re-read this often.
+const ANALYSIS_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// Bounds the captured connects kept in memory over the analysis window.
+const MAX_CAPTURED_CONNECTS: usize = 200_000;
+/// Bounds the endpoint to process map used to attribute captured connects.
+const MAX_ENDPOINT_OWNERS: usize = 50_000;
+
+/// Looks for periodic outbound connects in the stored history and flags
+/// live connections to the endpoints it finds. History only holds sockets
+/// that some poll saw open; while live capture runs, the connects it sees
+/// on the wire replace them, including ones opened and closed between polls.
+pub struct BeaconDetector {
+    settings: RwLock<BeaconingSettings>,
+    state: Mutex<DetectorState>,
+}
+
+#[derive(Default)]
+struct DetectorState {
+    analyzed_at: Option<Instant>,
+    findings: Vec<BeaconFinding>,
+    // keys of the findings already alerted on, until they stop being found
+    reported: HashSet<String>,
+    captured: VecDeque<ConnectEvent>,
+    // (protocol, remote endpoint) -> process last seen connected to it
+    owners: HashMap<(&'static str, SocketAddr), String>,
+}
+
+impl BeaconDetector {
+    pub fn new(settings: BeaconingSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            state: Mutex::new(DetectorState::default()),
+        }
+    }
+
+    pub fn configure(&self, settings: BeaconingSettings) {
+        let mut current = self.settings.write().unwrap();
+        if *current != settings {
+            // New thresholds apply on the next poll rather than minutes later
+            self.state.lock().unwrap().analyzed_at = None;
+        }
+        *current = settings;
+    }
+
+    /// Re-analyses the history when due and returns alerts for beacons not
+    /// reported before.
+    pub fn analyze(&self, history: &HistoryStore) -> Vec<Alert> {
+        let settings = self.settings.read().unwrap().clone();
+        let mut state = self.state.lock().unwrap();
+        if !settings.enabled {
+            state.findings.clear();
+            state.reported.clear();
+            state.captured.clear();
+            return Vec::new();
+        }
+        if state.analyzed_at.is_some_and(|at| at.elapsed() < ANALYSIS_INTERVAL) {
+            return Vec::new();
+        }
+        state.analyzed_at = Some(Instant::now());
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let since = now.saturating_sub(u64::from(settings.window_hours) * 60 * 60 * 1000);
+        let mut events = match history.connects_since(since) {
+            Ok(events) => events,
+            Err(e) => {
+                tracing::debug!("Skipping beaconing analysis: {}", e);
+                return Vec::new();
+            }
+        };
+        while state.captured.front().is_some_and(|event| event.at < since) {
+            state.captured.pop_front();
+        }
+        merge_captured(&mut events, &state.captured);
+        let findings = detect_beacons(&events, &settings);
+
+        let alerts = findings
+            .iter()
+            .filter(|finding| !state.reported.contains(&finding.key()))
+            .map(beacon_alert)
+            .collect();
+        state.reported = findings.iter().map(BeaconFinding::key).collect();
+        state.findings = findings;
+        alerts
+    }
+
+    /// Keeps connects seen by live capture for the next analyses. Each is
+    /// attributed to the process whose socket it matches in `connections`,
+    /// else to the last process seen talking to that endpoint. Conversations
+    /// opened by other hosts are dropped.
+    pub fn record_captured(
+        &self,
+        connects: Vec<CapturedConnect>,
+        connections: &[Connection],
+        local_addresses: &HashSet<IpAddr>,
+    ) {
+        if !self.settings.read().unwrap().enabled {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+
+        let mut sockets: HashMap<(&str, SocketAddr), &Connection> = HashMap::new();
+        for conn in connections
+            .iter()
+            .filter(|conn| conn.is_host_socket() && conn.direction == ConnectionDirection::Outbound)
+        {
+            let (Some(local), Some(remote)) = (parse_ip_address(&conn.local_addr), parse_ip_address(&conn.remote_addr))
+            else {
+                continue;
+            };
+            let protocol = match conn.protocol.as_str() {
+                "TCP" => "TCP",
+                "UDP" => "UDP",
+                _ => continue,
+            };
+            sockets.insert((protocol, SocketAddr::new(local, conn.local_port)), conn);
+            if state.owners.len() >= MAX_ENDPOINT_OWNERS {
+                state.owners.clear();
+            }
+            state
+                .owners
+                .insert((protocol, SocketAddr::new(remote, conn.remote_port)), conn.process_name.clone());
+        }
+
+        for connect in connects {
+            let socket = sockets.get(&(connect.protocol, connect.client));
+            if socket.is_none() && !local_addresses.contains(&connect.client.ip()) {
+                continue;
+            }
+            let process_name = match socket {
+                Some(conn) => conn.process_name.clone(),
+                None => state
+                    .owners
+                    .get(&(connect.protocol, connect.server))
+                    .cloned()
+                    .unwrap_or_else(|| "unknown".to_string()),
+            };
+            if state.captured.len() >= MAX_CAPTURED_CONNECTS {
+                state.captured.pop_front();
+            }
+            state.captured.push_back(ConnectEvent {
+                process_name,
+                protocol: connect.protocol.to_string(),
+                remote_addr: connect.server.ip().to_string(),
+                remote_port: connect.server.port(),
+                hostname: socket.and_then(|conn| conn.hostname.clone()),
+                at: connect.at,
+            });
+        }
+    }
+
+    pub fn annotate(&self, connections: &mut [Connection]) {
+        let state = self.state.lock().unwrap();
+        if state.findings.is_empty() {
+            return;
+        }
+        for conn in connections.iter_mut() {
+            if let Some(finding) = state.findings.iter().find(|finding| finding.matches(conn)) {
+                finding.apply(conn);
+            }
+        }
+    }
+}
+
+/// Replaces the polled connects to each endpoint with the captured ones
+/// from the moment capture first saw that endpoint: polls miss short-lived
+/// sockets and round connect times to the refresh interval.
+fn merge_captured(events: &mut Vec<ConnectEvent>, captured: &VecDeque<ConnectEvent>) {
+    if captured.is_empty() {
+        return;
+    }
+    let mut captured_since: HashMap<(&str, IpAddr, u16), u64> = HashMap::new();
+    for event in captured {
+        let Some(ip) = parse_ip_address(&event.remote_addr) else {
+            continue;
+        };
+        let since = captured_since
+            .entry((event.protocol.as_str(), ip, event.remote_port))
+            .or_insert(event.at);
+        *since = (*since).min(event.at);
+    }
+
+    events.retain(|event| {
+        let Some(ip) = parse_ip_address(&event.remote_addr) else {
+            return true;
+        };
+        captured_since
+            .get(&(event.protocol.as_str(), ip, event.remote_port))
+            .is_none_or(|since| event.at < *since)
+    });
+    events.extend(captured.iter().cloned());
+}
+
+/// Groups connects by process, remote host and port, and reports the groups
+/// whose connect intervals vary little relative to their mean. Connects from
+/// history are accurate to about one poll; captured ones are exact.
+fn detect_beacons(events: &[ConnectEvent], settings: &BeaconingSettings) -> Vec<BeaconFinding> {
+    // Names resolve after the first connects, so an address takes the name any of its connects got
+    let names: HashMap<&str, &str> = events
+        .iter()
+        .filter_map(|event| Some((event.remote_addr.as_str(), event.hostname.as_deref()?)))
+        .collect();
+
+    // (process, protocol, host, port) -> connect times and the addresses behind the host
+    let mut groups: HashMap<_, (Vec<u64>, BTreeSet<String>)> = HashMap::new();
+    for event in events {
+        let host = names
+            .get(event.remote_addr.as_str())
+            .copied()
+            .unwrap_or(&event.remote_addr);
+        let (times, addrs) = groups
+            .entry((event.process_name.as_str(), event.protocol.as_str(), host, event.remote_port))
+            .or_default();
+        times.push(event.at);
+        addrs.insert(event.remote_addr.clone());
+    }
+
+    let mut findings = Vec::new();
+    for ((process_name, protocol, host, port), (mut times, remote_addrs)) in groups {
+        // Sockets opened in the same poll are one check-in
+        times.sort_unstable();
+        times.dedup();
+        if times.len() < settings.min_connections.max(3) {
+            continue;
+        }
+
+        let gaps: Vec<f64> = times.windows(2).map(|pair| (pair[1] - pair[0]) as f64 / 1000.0).collect();
+        let period = gaps.iter().sum::<f64>() / gaps.len() as f64;
+        let jitter = (gaps.iter().map(|gap| (gap - period).powi(2)).sum::<f64>() / gaps.len() as f64).sqrt();
+        if period < settings.min_period_secs as f64 || period > settings.max_period_secs as f64 {
+            continue;
+        }
+        if jitter / period > settings.max_variation {
+            continue;
+        }
+
+        findings.push(BeaconFinding {
+            process_name: process_name.to_string(),
+            protocol: protocol.to_string(),
+            remote_host: host.to_string(),
+            remote_addrs,
+            remote_port: port,
+            connections: times.len(),
+            period_secs: period,
+            jitter_secs: jitter,
+            first_seen: times[0],
+            last_seen: times[times.len() - 1],
+        });
+    }
+
+    findings.sort_by_key(BeaconFinding::key);
+    findings
+}
+
+fn beacon_alert(finding: &BeaconFinding) -> Alert {
+    Alert::new(
+        AlertKind::Beaconing,
+        RiskLevel::Medium,
+        format!("{}: {}", finding.process_name, finding.describe()),
+        vec![
+            format!("Endpoint: {} {}:{}", finding.protocol, finding.remote_host, finding.remote_port),
+            format!(
+                "Addresses: {}",
+                finding.remote_addrs.iter().cloned().collect::<Vec<_>>().join(", ")
+            ),
+            format!("Period: {:.1}s", finding.period_secs),
+            format!(
+                "Jitter: {:.1}s ({:.1}% of the period)",
+                finding.jitter_secs,
+                finding.variation() * 100.0
+            ),
+            format!(
+                "Connections: {} over {}",
+                finding.connections,
+                format_secs((finding.last_seen - finding.first_seen) as f64 / 1000.0)
+            ),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE_MS: u64 = 60_000;
+
+    fn event(at: u64) -> ConnectEvent {
+        ConnectEvent {
+            process_name: "implant".to_string(),
+            protocol: "TCP".to_string(),
+            remote_addr: "203.0.113.50".to_string(),
+            remote_port: 443,
+            hostname: None,
+            at,
+        }
+    }
+
+    /// Connects whose gaps alternate between `60 + jitter` and `60 - jitter`
+    /// seconds: a 60 s period with a standard deviation of `jitter`.
+    fn jittered_series(count: u64, jitter_ms: u64) -> Vec<ConnectEvent> {
+        (0..count)
+            .map(|i| event(i * MINUTE_MS + if i % 2 == 1 { jitter_ms } else { 0 }))
+            .collect()
+    }
+
+    #[test]
+    fn flags_a_regular_series() {
+        let settings = BeaconingSettings::default();
+        let findings = detect_beacons(&jittered_series(12, 0), &settings);
+
+        assert_eq!(findings.len(), 1);
+        let finding = &findings[0];
+        assert_eq!(finding.process_name, "implant");
+        assert_eq!(finding.remote_host, "203.0.113.50");
+        assert_eq!(finding.connections, 12);
+        assert_eq!(finding.period_secs, 60.0);
+        assert_eq!(finding.jitter_secs, 0.0);
+        assert_eq!(finding.first_seen, 0);
+        assert_eq!(finding.last_seen, 11 * MINUTE_MS);
+    }
+
+    #[test]
+    fn compares_jitter_with_the_variation_threshold() {
+        let settings = BeaconingSettings::default();
+        assert_eq!(settings.max_variation, 0.15);
+
+        // An odd count gives an even number of gaps, so the mean stays at 60 s
+        let below = detect_beacons(&jittered_series(11, 8_400), &settings);
+        assert_eq!(below.len(), 1);
+        assert!((below[0].variation() - 0.14).abs() < 1e-9);
+
+        assert!(detect_beacons(&jittered_series(11, 9_600), &settings).is_empty());
+    }
+
+    #[test]
+    fn needs_min_connections() {
+        let settings = BeaconingSettings::default();
+
+        let too_few = jittered_series(settings.min_connections as u64 - 1, 0);
+        assert!(detect_beacons(&too_few, &settings).is_empty());
+
+        // Connects within the same poll count once
+        let mut duplicated = too_few.clone();
+        duplicated.extend(too_few.iter().cloned());
+        assert!(detect_beacons(&duplicated, &settings).is_empty());
+
+        let enough = jittered_series(settings.min_connections as u64, 0);
+        assert_eq!(detect_beacons(&enough, &settings).len(), 1);
+    }
+
+    #[test]
+    fn captured_connects_replace_polled_ones() {
+        let settings = BeaconingSettings::default();
+
+        // Polls see each connect up to 2 s late; capture starts at the sixth
+        let mut polled: Vec<ConnectEvent> = (0..12)
+            .map(|i| ConnectEvent {
+                hostname: Some("c2.example.net".to_string()),
+                ..event(i * MINUTE_MS + 2_000)
+            })
+            .collect();
+        let captured: VecDeque<ConnectEvent> = (5..12).map(|i| event(i * MINUTE_MS)).collect();
+
+        // Counting both would see two connects a minute, 2 s apart
+        let mut doubled = polled.clone();
+        doubled.extend(captured.iter().cloned());
+        assert!(detect_beacons(&doubled, &settings).is_empty());
+
+        merge_captured(&mut polled, &captured);
+        assert_eq!(polled.len(), 12);
+        assert_eq!(polled.iter().filter(|event| event.hostname.is_some()).count(), 5);
+
+        let findings = detect_beacons(&polled, &settings);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].connections, 12);
+        // The captured connects take the name the polled ones resolved
+        assert_eq!(findings[0].remote_host, "c2.example.net");
+        assert_eq!(findings[0].first_seen, 2_000);
+        assert_eq!(findings[0].last_seen, 11 * MINUTE_MS);
+    }
+
+    #[test]
+    fn keeps_polled_connects_to_other_endpoints() {
+        let mut polled = vec![event(0), ConnectEvent { remote_port: 80, ..event(0) }];
+        let captured: VecDeque<ConnectEvent> = [event(0)].into_iter().collect();
+
+        merge_captured(&mut polled, &captured);
+        let ports: BTreeSet<u16> = polled.iter().map(|event| event.remote_port).collect();
+        assert_eq!(polled.len(), 2);
+        assert_eq!(ports, BTreeSet::from([80, 443]));
+    }
+}
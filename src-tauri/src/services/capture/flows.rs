@@ -0,0 +1,245 @@
+use super::packet::{DecodedPacket, Transport, TCP_ACK, TCP_FIN, TCP_RST, TCP_SYN};
+use super::tls::{parse_client_hello, ClientHello, HelloParse, MAX_HELLO_BYTES};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+/// Order-independent identity of a conversation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct FlowKey {
+    tcp: bool,
+    low: SocketAddr,
+    high: SocketAddr,
+}
+
+impl FlowKey {
+    fn new(tcp: bool, a: SocketAddr, b: SocketAddr) -> Self {
+        let (low, high) = if a <= b { (a, b) } else { (b, a) };
+        Self { tcp, low, high }
+    }
+}
+
+/// A TCP or UDP conversation rebuilt from captured packets. "Sent" counters
+/// are client to server, the client being whoever opened the conversation.
+#[derive(Debug, Clone)]
+pub struct Flow {
+    pub protocol: &'static str,
+    pub client: SocketAddr,
+    pub server: SocketAddr,
+    pub first_seen_us: u64,
+    pub last_seen_us: u64,
+    pub packets_sent: u64,
+    pub packets_received: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub client_hello: Option<ClientHello>,
+    tcp: TcpFlagsSeen,
+    hello: Option<HelloBuffer>,
+}
+
+/// Client stream bytes collected until a ClientHello parses or the stream
+/// turns out not to be TLS. Segments are only taken in sequence order.
+#[derive(Debug, Clone, Default)]
+struct HelloBuffer {
+    data: Vec<u8>,
+    next_seq: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct TcpFlagsSeen {
+    syn: bool,
+    syn_ack: bool,
+    established: bool,
+    client_fin: bool,
+    server_fin: bool,
+    reset: bool,
+}
+
+impl Flow {
+    /// TCP state at the end of the capture, inferred from the flags seen.
+    pub fn state(&self) -> &'static str {
+        if self.protocol != "TCP" {
+            return "Active";
+        }
+
+        let tcp = &self.tcp;
+        if tcp.reset || (tcp.client_fin && tcp.server_fin) {
+            "CLOSED"
+        } else if tcp.client_fin || tcp.server_fin {
+            "CLOSING"
+        } else if tcp.established {
+            "ESTABLISHED"
+        } else if tcp.syn_ack {
+            "SYN_RECEIVED"
+        } else if tcp.syn {
+            "SYN_SENT"
+        } else {
+            "UNKNOWN"
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.tcp.reset || (self.tcp.client_fin && self.tcp.server_fin)
+    }
+}
+
+#[derive(Default)]
+pub struct FlowTable {
+    active: HashMap<FlowKey, Flow>,
+    finished: Vec<Flow>,
+}
+
+impl FlowTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a packet to its flow. Returns whether it opened a conversation:
+    /// a client SYN, or the first datagram of a UDP flow. Flows picked up
+    /// mid-stream don't count.
+    pub fn add(&mut self, timestamp_us: u64, packet: &DecodedPacket) -> bool {
+        let (src_port, dst_port) = packet.transport.ports();
+        let src = SocketAddr::new(packet.src, src_port);
+        let dst = SocketAddr::new(packet.dst, dst_port);
+        let (flags, seq) = match packet.transport {
+            Transport::Tcp { flags, seq, .. } => (Some(flags), seq),
+            Transport::Udp { .. } => (None, 0),
+        };
+        let key = FlowKey::new(flags.is_some(), src, dst);
+
+        // A fresh SYN on a finished conversation means the 4-tuple was reused
+        let new_syn = flags.is_some_and(|f| f & TCP_SYN != 0 && f & TCP_ACK == 0);
+        if new_syn && self.active.get(&key).is_some_and(|flow| flow.is_closed()) {
+            if let Some(old) = self.active.remove(&key) {
+                self.finished.push(old);
+            }
+        }
+
+        let mut opened = false;
+        let flow = self.active.entry(key).or_insert_with(|| {
+            opened = flags.is_none() || new_syn;
+            new_flow(timestamp_us, src, dst, src_port, dst_port, flags)
+        });
+
+        let from_client = src == flow.client;
+        flow.last_seen_us = flow.last_seen_us.max(timestamp_us);
+        if from_client {
+            flow.packets_sent += 1;
+            flow.bytes_sent += packet.ip_len as u64;
+        } else {
+            flow.packets_received += 1;
+            flow.bytes_received += packet.ip_len as u64;
+        }
+
+        if let Some(flags) = flags {
+            let tcp = &mut flow.tcp;
+            if flags & TCP_RST != 0 {
+                tcp.reset = true;
+            }
+            if flags & TCP_FIN != 0 {
+                if from_client {
+                    tcp.client_fin = true;
+                } else {
+                    tcp.server_fin = true;
+                }
+            }
+            if flags & TCP_SYN != 0 {
+                if flags & TCP_ACK != 0 {
+                    tcp.syn_ack = true;
+                } else {
+                    tcp.syn = true;
+                }
+            } else if flags & TCP_ACK != 0 && (tcp.syn_ack || !tcp.syn || !packet.transport.payload().is_empty()) {
+                // The handshake's final ACK, or any traffic on a flow picked up mid-stream
+                tcp.established = true;
+            }
+
+            if from_client {
+                collect_hello(flow, flags, seq, packet.transport.payload());
+            }
+        }
+        opened
+    }
+
+    /// The current conversation between `a` and `b`, if one has been seen.
+    pub fn get(&self, tcp: bool, a: SocketAddr, b: SocketAddr) -> Option<&Flow> {
+        self.active.get(&FlowKey::new(tcp, a, b))
+    }
+
+    pub fn len(&self) -> usize {
+        self.active.len() + self.finished.len()
+    }
+
+    /// Forgets flows idle since before `cutoff_us`; bounds memory during live capture.
+    pub fn prune_idle(&mut self, cutoff_us: u64) {
+        self.active.retain(|_, flow| flow.last_seen_us >= cutoff_us);
+        self.finished.retain(|flow| flow.last_seen_us >= cutoff_us);
+    }
+
+    pub fn into_flows(self) -> Vec<Flow> {
+        let mut flows = self.finished;
+        flows.extend(self.active.into_values());
+        flows.sort_by_key(|flow| flow.first_seen_us);
+        flows
+    }
+}
+
+fn collect_hello(flow: &mut Flow, flags: u8, seq: u32, payload: &[u8]) {
+    let Some(buffer) = flow.hello.as_mut() else {
+        return;
+    };
+    if flags & TCP_SYN != 0 {
+        // The SYN consumes one sequence number
+        buffer.next_seq = Some(seq.wrapping_add(1));
+        return;
+    }
+    if payload.is_empty() {
+        return;
+    }
+    match buffer.next_seq {
+        // Retransmitted or out of order; the in-order copy will follow
+        Some(expected) if expected != seq => return,
+        _ => buffer.next_seq = Some(seq.wrapping_add(payload.len() as u32)),
+    }
+
+    buffer.data.extend_from_slice(payload);
+    match parse_client_hello(&buffer.data) {
+        HelloParse::Hello(hello) => {
+            flow.client_hello = Some(hello);
+            flow.hello = None;
+        }
+        HelloParse::NotTls => flow.hello = None,
+        HelloParse::Incomplete if buffer.data.len() > MAX_HELLO_BYTES => flow.hello = None,
+        HelloParse::Incomplete => {}
+    }
+}
+
+fn new_flow(
+    timestamp_us: u64,
+    src: SocketAddr,
+    dst: SocketAddr,
+    src_port: u16,
+    dst_port: u16,
+    tcp_flags: Option<u8>,
+) -> Flow {
+    let src_is_client = match tcp_flags {
+        Some(flags) if flags & TCP_SYN != 0 => flags & TCP_ACK == 0,
+        // Picked up mid-conversation: a well-known port marks the server
+        _ => !(src_port < 1024 && dst_port >= 1024),
+    };
+    let (client, server) = if src_is_client { (src, dst) } else { (dst, src) };
+
+    Flow {
+        protocol: if tcp_flags.is_some() { "TCP" } else { "UDP" },
+        client,
+        server,
+        first_seen_us: timestamp_us,
+        last_seen_us: timestamp_us,
+        packets_sent: 0,
+        packets_received: 0,
+        bytes_sent: 0,
+        bytes_received: 0,
+        client_hello: None,
+        tcp: TcpFlagsSeen::default(),
+        hello: tcp_flags.map(|_| HelloBuffer::default()),
+    }
+}
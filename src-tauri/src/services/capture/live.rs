@@ -0,0 +1,528 @@
+use super::flows::FlowTable;
+use super::packet::{self, Transport};
+use crate::models::{parse_ip_address, CaptureDiagnostics, Connection};
+use crate::services::passive_dns::PassiveDnsMap;
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How often the capture thread drops idle flows and old DNS answers.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(30);
+/// Flows idle longer than this are forgotten.
+const FLOW_IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// DNS answers older than this no longer label connections.
+const DNS_RETENTION: Duration = Duration::from_secs(60 * 60);
+/// Connects kept for the next poll; the oldest go first if nobody polls.
+const MAX_PENDING_CONNECTS: usize = 10_000;
+
+/// Live packet capture on one interface, feeding the same flow, TLS and
+/// passive DNS enrichment as capture file import. Managed by the
+/// [`ConnectionCollector`](crate::services::ConnectionCollector).
+#[derive(Default)]
+pub struct LiveCapture {
+    state: Mutex<LiveState>,
+}
+
+#[derive(Default)]
+struct LiveState {
+    session: Option<Session>,
+    last_error: Option<String>,
+}
+
+struct Session {
+    interface: String,
+    started_at: u64,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+    tables: Arc<Mutex<Tables>>,
+}
+
+/// Everything the capture thread learns, shared with the pollers.
+#[derive(Default)]
+struct Tables {
+    flows: FlowTable,
+    passive_dns: PassiveDnsMap,
+    connects: VecDeque<CapturedConnect>,
+    packets: u64,
+    drops: u64,
+    error: Option<String>,
+}
+
+/// A conversation seen opening on the wire, with no process attached yet.
+#[derive(Debug, Clone)]
+pub struct CapturedConnect {
+    pub at: u64, // Unix timestamp in milliseconds
+    pub protocol: &'static str,
+    pub client: SocketAddr,
+    pub server: SocketAddr,
+}
+
+impl LiveCapture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts capturing on `interface`, replacing any running capture.
+    pub fn start(&self, interface: &str) -> Result<(), String> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(session) = state.session.take() {
+            session.shutdown();
+        }
+
+        let socket = match sys::PacketSocket::open(interface) {
+            Ok(socket) => socket,
+            Err(e) => {
+                tracing::warn!("Live capture on {} unavailable: {}", interface, e);
+                state.last_error = Some(e.clone());
+                return Err(e);
+            }
+        };
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let tables = Arc::new(Mutex::new(Tables::default()));
+        let thread = {
+            let stop = stop.clone();
+            let tables = tables.clone();
+            std::thread::Builder::new()
+                .name(format!("capture-{}", interface))
+                .spawn(move || capture_loop(socket, &stop, &tables))
+                .map_err(|e| format!("Failed to start capture thread: {}", e))?
+        };
+
+        tracing::info!("Live capture started on {}", interface);
+        state.last_error = None;
+        state.session = Some(Session {
+            interface: interface.to_string(),
+            started_at: now().as_millis() as u64,
+            stop,
+            thread: Some(thread),
+            tables,
+        });
+        Ok(())
+    }
+
+    pub fn stop(&self) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(session) = state.session.take() {
+            tracing::info!("Live capture on {} stopped", session.interface);
+            session.shutdown();
+        }
+    }
+
+    pub fn diagnostics(&self) -> CaptureDiagnostics {
+        let state = self.state.lock().unwrap();
+        let mut diagnostics = CaptureDiagnostics {
+            available: sys::AVAILABLE,
+            has_privileges: sys::has_privileges(),
+            last_error: state.last_error.clone(),
+            ..Default::default()
+        };
+
+        if let Some(session) = &state.session {
+            let tables = session.tables.lock().unwrap();
+            diagnostics.running = session.thread.as_ref().is_some_and(|thread| !thread.is_finished());
+            diagnostics.interface = Some(session.interface.clone());
+            diagnostics.started_at = Some(session.started_at);
+            diagnostics.packets_captured = tables.packets;
+            diagnostics.packets_dropped = tables.drops;
+            diagnostics.flows_tracked = tables.flows.len();
+            diagnostics.dns_addresses = tables.passive_dns.len();
+            if tables.error.is_some() {
+                diagnostics.last_error = tables.error.clone();
+            }
+        }
+        diagnostics
+    }
+
+    /// Attaches packet counters and ClientHello details to socket table
+    /// entries whose 4-tuple matches a captured flow, and names from DNS
+    /// answers seen on the wire.
+    pub fn annotate(&self, connections: &mut [Connection]) {
+        let state = self.state.lock().unwrap();
+        let Some(session) = &state.session else {
+            return;
+        };
+        let tables = session.tables.lock().unwrap();
+
+        for conn in connections.iter_mut() {
+            let (Some(local_ip), Some(remote_ip)) =
+                (parse_ip_address(&conn.local_addr), parse_ip_address(&conn.remote_addr))
+            else {
+                continue;
+            };
+            if remote_ip.is_unspecified() {
+                continue;
+            }
+
+            let local = SocketAddr::new(local_ip, conn.local_port);
+            let remote = SocketAddr::new(remote_ip, conn.remote_port);
+            let tcp = conn.protocol.eq_ignore_ascii_case("TCP");
+            let Some(flow) = tables.flows.get(tcp, local, remote) else {
+                continue;
+            };
+
+            let local_is_client = flow.client == local;
+            conn.flow = Some(super::flow_stats(flow, local_is_client));
+            if local_is_client {
+                conn.tls = flow.client_hello.as_ref().map(|hello| hello.to_info());
+            }
+        }
+
+        tables.passive_dns.annotate(connections);
+    }
+
+    /// Conversations opened since the last call, including ones too short
+    /// lived for any poll to see their socket.
+    pub fn take_connects(&self) -> Vec<CapturedConnect> {
+        let state = self.state.lock().unwrap();
+        let Some(session) = &state.session else {
+            return Vec::new();
+        };
+        let mut tables = session.tables.lock().unwrap();
+        tables.connects.drain(..).collect()
+    }
+}
+
+impl Tables {
+    /// Queues the conversation `packet` just opened for the next poll.
+    fn record_connect(&mut self, timestamp_us: u64, packet: &packet::DecodedPacket) {
+        let (src_port, dst_port) = packet.transport.ports();
+        let tcp = matches!(packet.transport, Transport::Tcp { .. });
+        let Some(flow) = self.flows.get(tcp, SocketAddr::new(packet.src, src_port), SocketAddr::new(packet.dst, dst_port))
+        else {
+            return;
+        };
+        if self.connects.len() >= MAX_PENDING_CONNECTS {
+            self.connects.pop_front();
+        }
+        self.connects.push_back(CapturedConnect {
+            at: timestamp_us / 1000,
+            protocol: flow.protocol,
+            client: flow.client,
+            server: flow.server,
+        });
+    }
+}
+
+impl Session {
+    fn shutdown(mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            // The socket read times out regularly, so this returns promptly
+            let _ = thread.join();
+        }
+    }
+}
+
+fn capture_loop(socket: sys::PacketSocket, stop: &AtomicBool, tables: &Mutex<Tables>) {
+    let mut buffer = vec![0u8; 65536];
+    let mut last_prune = now();
+
+    while !stop.load(Ordering::Relaxed) {
+        let received = socket.recv(&mut buffer);
+        let timestamp = now();
+
+        let mut tables = tables.lock().unwrap();
+        match received {
+            Ok(Some(len)) => {
+                tables.packets += 1;
+                if let Some(packet) = packet::decode(packet::LINKTYPE_RAW, &buffer[..len]) {
+                    let timestamp_us = timestamp.as_micros() as u64;
+                    if let Transport::Udp { src_port: 53, payload, .. } = packet.transport {
+                        tables.passive_dns.observe(timestamp_us / 1000, payload);
+                    }
+                    if tables.flows.add(timestamp_us, &packet) {
+                        tables.record_connect(timestamp_us, &packet);
+                    }
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                tracing::error!("Live capture stopped: {}", e);
+                tables.error = Some(format!("Capture failed: {}", e));
+                return;
+            }
+        }
+
+        if timestamp.saturating_sub(last_prune) >= PRUNE_INTERVAL {
+            last_prune = timestamp;
+            tables.drops += socket.take_drops();
+            let flow_cutoff = timestamp.saturating_sub(FLOW_IDLE_TIMEOUT);
+            tables.flows.prune_idle(flow_cutoff.as_micros() as u64);
+            let dns_cutoff = timestamp.saturating_sub(DNS_RETENTION);
+            tables.passive_dns.prune_before(dns_cutoff.as_millis() as u64);
+        }
+    }
+}
+
+/// Time since the Unix epoch.
+fn now() -> Duration {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default()
+}
+
+#[cfg(all(feature = "capture", target_os = "linux"))]
+mod sys {
+    use std::ffi::CString;
+    use std::io;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+    pub const AVAILABLE: bool = true;
+
+    // linux/if_packet.h
+    const PACKET_OUTGOING: u8 = 4;
+    const PACKET_STATISTICS: libc::c_int = 6;
+    const CAP_NET_RAW: u32 = 13;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct TpacketStats {
+        packets: u32,
+        drops: u32,
+    }
+
+    /// AF_PACKET datagram socket bound to one interface. Datagram mode has
+    /// the kernel strip the link layer, so every frame starts at the IP header.
+    pub struct PacketSocket {
+        fd: OwnedFd,
+        loopback: bool,
+    }
+
+    impl PacketSocket {
+        pub fn open(interface: &str) -> Result<Self, String> {
+            let name = CString::new(interface).map_err(|_| format!("Invalid interface name: {}", interface))?;
+            let index = unsafe { libc::if_nametoindex(name.as_ptr()) };
+            if index == 0 {
+                return Err(format!("No such interface: {}", interface));
+            }
+
+            let protocol = (libc::ETH_P_ALL as u16).to_be();
+            let fd = unsafe { libc::socket(libc::AF_PACKET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, protocol as libc::c_int) };
+            if fd < 0 {
+                let error = io::Error::last_os_error();
+                return Err(match error.raw_os_error() {
+                    Some(libc::EPERM) | Some(libc::EACCES) => {
+                        "Live capture needs root or the CAP_NET_RAW capability".to_string()
+                    }
+                    _ => format!("Failed to open packet socket: {}", error),
+                });
+            }
+            let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+            let mut address: libc::sockaddr_ll = unsafe { std::mem::zeroed() };
+            address.sll_family = libc::AF_PACKET as u16;
+            address.sll_protocol = protocol;
+            address.sll_ifindex = index as i32;
+            let result = unsafe {
+                libc::bind(
+                    fd.as_raw_fd(),
+                    &address as *const libc::sockaddr_ll as *const libc::sockaddr,
+                    std::mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+                )
+            };
+            if result < 0 {
+                return Err(format!("Failed to bind to {}: {}", interface, io::Error::last_os_error()));
+            }
+
+            // Wake up regularly so a stop request is noticed
+            let timeout = libc::timeval {
+                tv_sec: 0,
+                tv_usec: 200_000,
+            };
+            unsafe {
+                libc::setsockopt(
+                    fd.as_raw_fd(),
+                    libc::SOL_SOCKET,
+                    libc::SO_RCVTIMEO,
+                    &timeout as *const libc::timeval as *const libc::c_void,
+                    std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+                );
+            }
+
+            let loopback = std::fs::read_to_string(format!("/sys/class/net/{}/type", interface))
+                .is_ok_and(|kind| kind.trim() == libc::ARPHRD_LOOPBACK.to_string());
+
+            Ok(Self { fd, loopback })
+        }
+
+        /// Reads one frame; `None` on timeout or for frames we skip.
+        pub fn recv(&self, buffer: &mut [u8]) -> io::Result<Option<usize>> {
+            let mut address: libc::sockaddr_ll = unsafe { std::mem::zeroed() };
+            let mut address_len = std::mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t;
+            let len = unsafe {
+                libc::recvfrom(
+                    self.fd.as_raw_fd(),
+                    buffer.as_mut_ptr() as *mut libc::c_void,
+                    buffer.len(),
+                    0,
+                    &mut address as *mut libc::sockaddr_ll as *mut libc::sockaddr,
+                    &mut address_len,
+                )
+            };
+            if len < 0 {
+                let error = io::Error::last_os_error();
+                return match error.kind() {
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut | io::ErrorKind::Interrupted => Ok(None),
+                    _ => Err(error),
+                };
+            }
+
+            // Loopback hands us every packet twice, once outgoing and once incoming
+            if self.loopback && address.sll_pkttype == PACKET_OUTGOING {
+                return Ok(None);
+            }
+            Ok(Some(len as usize))
+        }
+
+        /// Packets the kernel dropped since the last call.
+        pub fn take_drops(&self) -> u64 {
+            let mut stats = TpacketStats::default();
+            let mut len = std::mem::size_of::<TpacketStats>() as libc::socklen_t;
+            let result = unsafe {
+                libc::getsockopt(
+                    self.fd.as_raw_fd(),
+                    libc::SOL_PACKET,
+                    PACKET_STATISTICS,
+                    &mut stats as *mut TpacketStats as *mut libc::c_void,
+                    &mut len,
+                )
+            };
+            if result < 0 {
+                0
+            } else {
+                u64::from(stats.drops)
+            }
+        }
+    }
+
+    /// Whether the effective capability set includes CAP_NET_RAW.
+    pub fn has_privileges() -> bool {
+        let Ok(status) = std::fs::read_to_string("/proc/self/status") else {
+            return false;
+        };
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix("CapEff:"))
+            .and_then(|mask| u64::from_str_radix(mask.trim(), 16).ok())
+            .is_some_and(|mask| mask & (1 << CAP_NET_RAW) != 0)
+    }
+}
+
+#[cfg(not(all(feature = "capture", target_os = "linux")))]
+mod sys {
+    use std::io;
+
+    pub const AVAILABLE: bool = false;
+
+    pub struct PacketSocket;
+
+    impl PacketSocket {
+        pub fn open(_interface: &str) -> Result<Self, String> {
+            if cfg!(target_os = "linux") {
+                Err("Live capture is not available: NetWatch was built without the `capture` feature".to_string())
+            } else {
+                Err("Live capture is only supported on Linux".to_string())
+            }
+        }
+
+        pub fn recv(&self, _buffer: &mut [u8]) -> io::Result<Option<usize>> {
+            Ok(None)
+        }
+
+        pub fn take_drops(&self) -> u64 {
+            0
+        }
+    }
+
+    pub fn has_privileges() -> bool {
+        false
+    }
+}
+
+#[cfg(all(test, feature = "capture", target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::net::UdpSocket;
+    use std::time::Instant;
+
+    /// A response to an A query for `name`, answering `address`.
+    fn dns_answer(name: &str, address: [u8; 4]) -> Vec<u8> {
+        let mut message = vec![0x12, 0x34, 0x81, 0x80, 0, 1, 0, 1, 0, 0, 0, 0];
+        for label in name.split('.') {
+            message.push(label.len() as u8);
+            message.extend_from_slice(label.as_bytes());
+        }
+        message.extend_from_slice(&[0, 0, 1, 0, 1]);
+        message.extend_from_slice(&[0xc0, 12, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4]);
+        message.extend_from_slice(&address);
+        message
+    }
+
+    fn wait_for_packets(capture: &LiveCapture, count: u64) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while capture.diagnostics().packets_captured < count {
+            assert!(Instant::now() < deadline, "{:?}", capture.diagnostics());
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    #[test]
+    fn captures_udp_flow_and_dns_answer_on_loopback() {
+        if !sys::has_privileges() {
+            eprintln!("skipping: live capture needs root or CAP_NET_RAW");
+            return;
+        }
+        let capture = LiveCapture::new();
+        capture.start("lo").unwrap();
+
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        client.send_to(b"ping", server.local_addr().unwrap()).unwrap();
+        client.send_to(b"ping again", server.local_addr().unwrap()).unwrap();
+
+        // The answer has to come from port 53 to be read as DNS
+        let resolver = UdpSocket::bind("127.0.0.77:53").unwrap();
+        let stub = UdpSocket::bind("127.0.0.1:0").unwrap();
+        resolver
+            .send_to(&dns_answer("service.example.test", [192, 0, 2, 10]), stub.local_addr().unwrap())
+            .unwrap();
+
+        wait_for_packets(&capture, 3);
+        let diagnostics = capture.diagnostics();
+        assert!(diagnostics.running);
+        assert_eq!(diagnostics.interface.as_deref(), Some("lo"));
+
+        let (client_addr, server_addr) = (client.local_addr().unwrap(), server.local_addr().unwrap());
+        let mut connections = vec![
+            Connection {
+                protocol: "UDP".to_string(),
+                local_addr: client_addr.ip().to_string(),
+                local_port: client_addr.port(),
+                remote_addr: server_addr.ip().to_string(),
+                remote_port: server_addr.port(),
+                ..Default::default()
+            },
+            Connection {
+                protocol: "TCP".to_string(),
+                local_addr: "127.0.0.1".to_string(),
+                local_port: 40000,
+                remote_addr: "192.0.2.10".to_string(),
+                remote_port: 443,
+                captured_at: now().as_millis() as u64 + 1000,
+                ..Default::default()
+            },
+        ];
+        capture.annotate(&mut connections);
+        capture.stop();
+
+        let flow = connections[0].flow.as_ref().expect("no flow for the UDP datagrams");
+        assert_eq!(flow.packets_sent, 2);
+        assert_eq!(flow.packets_received, 0);
+        // IPv4 and UDP headers plus the payloads
+        assert_eq!(flow.bytes_sent, 2 * 28 + 4 + 10);
+        assert_eq!(connections[1].resolved_name.as_deref(), Some("service.example.test"));
+        assert!(!capture.diagnostics().running);
+    }
+}
@@ -0,0 +1,93 @@
+mod flows;
+mod live;
+mod packet;
+mod pcap;
+mod tls;
+
+use crate::models::{classify_ip, Connection, ConnectionSource, FlowStats, TlsRules};
+use super::passive_dns::PassiveDnsMap;
+use flows::{Flow, FlowTable};
+use packet::Transport;
+use pcap::CaptureReader;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use uuid::Uuid;
+
+pub use live::{CapturedConnect, LiveCapture};
+
+/// Rebuilds TCP/UDP flows from a pcap or pcapng file and scores them like
+/// live connections, plus the TLS rules for flows that sent a ClientHello.
+/// Process fields are "unknown" since captures carry no PIDs.
+pub fn import_capture_file(path: &Path, tls_rules: &TlsRules) -> Result<Vec<Connection>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open capture {}: {}", path.display(), e))?;
+    let mut reader = CaptureReader::new(BufReader::new(file))?;
+
+    let mut table = FlowTable::new();
+    let mut passive_dns = PassiveDnsMap::new();
+    let mut packet_count = 0u64;
+    while let Some(raw) = reader.next_packet()? {
+        packet_count += 1;
+        if let Some(packet) = packet::decode(raw.link_type, &raw.data) {
+            if let Transport::Udp { src_port: 53, payload, .. } = packet.transport {
+                passive_dns.observe(raw.timestamp_us / 1000, payload);
+            }
+            table.add(raw.timestamp_us, &packet);
+        }
+    }
+
+    let flows = table.into_flows();
+    tracing::info!(
+        "Rebuilt {} flows from {} packets in {} ({} addresses seen in DNS answers)",
+        flows.len(),
+        packet_count,
+        path.display(),
+        passive_dns.len()
+    );
+
+    let mut connections: Vec<Connection> = flows.iter().map(flow_to_connection).collect();
+    passive_dns.annotate(&mut connections);
+    super::assess_snapshot(&mut connections, tls_rules);
+    Ok(connections)
+}
+
+fn flow_to_connection(flow: &Flow) -> Connection {
+    let last_seen = flow.last_seen_us / 1000;
+
+    Connection {
+        id: Uuid::new_v4().to_string(),
+        process_name: "unknown".to_string(),
+        pid: 0,
+        protocol: flow.protocol.to_string(),
+        local_addr: flow.client.ip().to_string(),
+        local_port: flow.client.port(),
+        remote_addr: flow.server.ip().to_string(),
+        remote_port: flow.server.port(),
+        remote_class: classify_ip(flow.server.ip()),
+        state: flow.state().to_string(),
+        captured_at: last_seen,
+        source: ConnectionSource::Capture,
+        flow: Some(flow_stats(flow, true)),
+        tls: flow.client_hello.as_ref().map(|hello| hello.to_info()),
+        ..Default::default()
+    }
+}
+
+/// Counters oriented from the local side: "sent" is the client's direction
+/// when the local endpoint opened the flow, the server's otherwise.
+fn flow_stats(flow: &Flow, local_is_client: bool) -> FlowStats {
+    let (packets_sent, packets_received, bytes_sent, bytes_received) = if local_is_client {
+        (flow.packets_sent, flow.packets_received, flow.bytes_sent, flow.bytes_received)
+    } else {
+        (flow.packets_received, flow.packets_sent, flow.bytes_received, flow.bytes_sent)
+    };
+
+    FlowStats {
+        first_seen: flow.first_seen_us / 1000,
+        last_seen: flow.last_seen_us / 1000,
+        packets_sent,
+        packets_received,
+        bytes_sent,
+        bytes_received,
+    }
+}
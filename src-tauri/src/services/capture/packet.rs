@@ -0,0 +1,357 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+// pcap LINKTYPE_* values
+const LINKTYPE_NULL: u16 = 0;
+const LINKTYPE_ETHERNET: u16 = 1;
+const LINKTYPE_RAW_OPENBSD: u16 = 12;
+const LINKTYPE_RAW_BSD: u16 = 14;
+pub const LINKTYPE_RAW: u16 = 101;
+const LINKTYPE_LOOP: u16 = 108;
+const LINKTYPE_LINUX_SLL: u16 = 113;
+const LINKTYPE_IPV4: u16 = 228;
+const LINKTYPE_IPV6: u16 = 229;
+const LINKTYPE_LINUX_SLL2: u16 = 276;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86dd;
+const ETHERTYPE_VLAN: u16 = 0x8100;
+const ETHERTYPE_QINQ: u16 = 0x88a8;
+
+const IPPROTO_TCP: u8 = 6;
+const IPPROTO_UDP: u8 = 17;
+
+pub const TCP_FIN: u8 = 0x01;
+pub const TCP_SYN: u8 = 0x02;
+pub const TCP_RST: u8 = 0x04;
+pub const TCP_ACK: u8 = 0x10;
+
+/// A TCP or UDP packet decoded down to its transport payload.
+pub struct DecodedPacket<'a> {
+    pub src: IpAddr,
+    pub dst: IpAddr,
+    /// Length of the IP packet on the wire, headers included.
+    pub ip_len: usize,
+    pub transport: Transport<'a>,
+}
+
+pub enum Transport<'a> {
+    Tcp {
+        src_port: u16,
+        dst_port: u16,
+        seq: u32,
+        flags: u8,
+        payload: &'a [u8],
+    },
+    Udp {
+        src_port: u16,
+        dst_port: u16,
+        payload: &'a [u8],
+    },
+}
+
+impl Transport<'_> {
+    pub fn ports(&self) -> (u16, u16) {
+        match *self {
+            Transport::Tcp { src_port, dst_port, .. } | Transport::Udp { src_port, dst_port, .. } => (src_port, dst_port),
+        }
+    }
+
+    pub fn payload(&self) -> &[u8] {
+        match self {
+            Transport::Tcp { payload, .. } | Transport::Udp { payload, .. } => payload,
+        }
+    }
+}
+
+/// Decodes a captured frame; anything that is not TCP or UDP over IP
+/// (ARP, ICMP, non-first fragments, truncated headers) yields `None`.
+pub fn decode(link_type: u16, frame: &[u8]) -> Option<DecodedPacket<'_>> {
+    let ip = match link_type {
+        LINKTYPE_ETHERNET => {
+            let mut ethertype = u16::from_be_bytes([*frame.get(12)?, *frame.get(13)?]);
+            let mut offset = 14;
+            while ethertype == ETHERTYPE_VLAN || ethertype == ETHERTYPE_QINQ {
+                ethertype = u16::from_be_bytes([*frame.get(offset + 2)?, *frame.get(offset + 3)?]);
+                offset += 4;
+            }
+            if ethertype != ETHERTYPE_IPV4 && ethertype != ETHERTYPE_IPV6 {
+                return None;
+            }
+            frame.get(offset..)?
+        }
+        LINKTYPE_NULL | LINKTYPE_LOOP => frame.get(4..)?,
+        LINKTYPE_LINUX_SLL => frame.get(16..)?,
+        LINKTYPE_LINUX_SLL2 => frame.get(20..)?,
+        LINKTYPE_RAW | LINKTYPE_RAW_OPENBSD | LINKTYPE_RAW_BSD | LINKTYPE_IPV4 | LINKTYPE_IPV6 => frame,
+        _ => return None,
+    };
+
+    // The IP version nibble is authoritative whatever the link layer claimed
+    match ip.first()? >> 4 {
+        4 => decode_ipv4(ip),
+        6 => decode_ipv6(ip),
+        _ => None,
+    }
+}
+
+fn decode_ipv4(data: &[u8]) -> Option<DecodedPacket<'_>> {
+    if data.len() < 20 {
+        return None;
+    }
+    let header_len = ((data[0] & 0x0f) as usize) * 4;
+    let total_len = u16::from_be_bytes([data[2], data[3]]) as usize;
+    let fragment_offset = u16::from_be_bytes([data[6], data[7]]) & 0x1fff;
+    if header_len < 20 || fragment_offset != 0 {
+        return None;
+    }
+
+    let protocol = data[9];
+    let src = IpAddr::V4(Ipv4Addr::new(data[12], data[13], data[14], data[15]));
+    let dst = IpAddr::V4(Ipv4Addr::new(data[16], data[17], data[18], data[19]));
+
+    // Captures may be truncated by the snap length; trust what we have
+    let end = total_len.clamp(header_len, data.len().max(header_len));
+    let transport = decode_transport(protocol, data.get(header_len..end)?)?;
+
+    Some(DecodedPacket {
+        src,
+        dst,
+        ip_len: total_len,
+        transport,
+    })
+}
+
+fn decode_ipv6(data: &[u8]) -> Option<DecodedPacket<'_>> {
+    let payload_len = u16::from_be_bytes([*data.get(4)?, *data.get(5)?]) as usize;
+    let mut next_header = *data.get(6)?;
+    let src = IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(data.get(8..24)?).ok()?));
+    let dst = IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(data.get(24..40)?).ok()?));
+
+    let mut offset = 40;
+    loop {
+        match next_header {
+            // Hop-by-hop, routing and destination options
+            0 | 43 | 60 => {
+                let len = (*data.get(offset + 1)? as usize + 1) * 8;
+                next_header = *data.get(offset)?;
+                offset += len;
+            }
+            // Fragment header: only the first fragment carries the transport header
+            44 => {
+                let fragment_offset = u16::from_be_bytes([*data.get(offset + 2)?, *data.get(offset + 3)?]) >> 3;
+                if fragment_offset != 0 {
+                    return None;
+                }
+                next_header = *data.get(offset)?;
+                offset += 8;
+            }
+            // Authentication header
+            51 => {
+                let len = (*data.get(offset + 1)? as usize + 2) * 4;
+                next_header = *data.get(offset)?;
+                offset += len;
+            }
+            _ => break,
+        }
+    }
+
+    let end = (40 + payload_len).clamp(offset, data.len().max(offset));
+    let transport = decode_transport(next_header, data.get(offset..end)?)?;
+
+    Some(DecodedPacket {
+        src,
+        dst,
+        ip_len: 40 + payload_len,
+        transport,
+    })
+}
+
+fn decode_transport(protocol: u8, data: &[u8]) -> Option<Transport<'_>> {
+    match protocol {
+        IPPROTO_TCP => {
+            let header_len = ((*data.get(12)? >> 4) as usize) * 4;
+            Some(Transport::Tcp {
+                src_port: u16::from_be_bytes([data[0], data[1]]),
+                dst_port: u16::from_be_bytes([data[2], data[3]]),
+                seq: u32::from_be_bytes([data[4], data[5], data[6], data[7]]),
+                flags: *data.get(13)?,
+                payload: data.get(header_len..).unwrap_or(&[]),
+            })
+        }
+        IPPROTO_UDP => Some(Transport::Udp {
+            src_port: u16::from_be_bytes([*data.first()?, *data.get(1)?]),
+            dst_port: u16::from_be_bytes([*data.get(2)?, *data.get(3)?]),
+            payload: data.get(8..).unwrap_or(&[]),
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ipv4(protocol: u8, fragment: u16, transport: &[u8]) -> Vec<u8> {
+        let total_len = (20 + transport.len()) as u16;
+        let mut packet = vec![0x45, 0];
+        packet.extend_from_slice(&total_len.to_be_bytes());
+        packet.extend_from_slice(&[0, 1]);
+        packet.extend_from_slice(&fragment.to_be_bytes());
+        packet.extend_from_slice(&[64, protocol, 0, 0]);
+        packet.extend_from_slice(&[192, 168, 1, 10]);
+        packet.extend_from_slice(&[93, 184, 216, 34]);
+        packet.extend_from_slice(transport);
+        packet
+    }
+
+    fn ipv6(next_header: u8, extensions: &[u8], transport: &[u8]) -> Vec<u8> {
+        let payload_len = (extensions.len() + transport.len()) as u16;
+        let mut packet = vec![0x60, 0, 0, 0];
+        packet.extend_from_slice(&payload_len.to_be_bytes());
+        packet.extend_from_slice(&[next_header, 64]);
+        packet.extend_from_slice(&"2001:db8::1".parse::<Ipv6Addr>().unwrap().octets());
+        packet.extend_from_slice(&"2001:db8::2".parse::<Ipv6Addr>().unwrap().octets());
+        packet.extend_from_slice(extensions);
+        packet.extend_from_slice(transport);
+        packet
+    }
+
+    fn tcp(src_port: u16, dst_port: u16, flags: u8, payload: &[u8]) -> Vec<u8> {
+        let mut segment = Vec::new();
+        segment.extend_from_slice(&src_port.to_be_bytes());
+        segment.extend_from_slice(&dst_port.to_be_bytes());
+        segment.extend_from_slice(&1000u32.to_be_bytes());
+        segment.extend_from_slice(&0u32.to_be_bytes());
+        segment.extend_from_slice(&[0x50, flags, 0xff, 0xff, 0, 0, 0, 0]);
+        segment.extend_from_slice(payload);
+        segment
+    }
+
+    fn udp(src_port: u16, dst_port: u16, payload: &[u8]) -> Vec<u8> {
+        let mut datagram = Vec::new();
+        datagram.extend_from_slice(&src_port.to_be_bytes());
+        datagram.extend_from_slice(&dst_port.to_be_bytes());
+        datagram.extend_from_slice(&((8 + payload.len()) as u16).to_be_bytes());
+        datagram.extend_from_slice(&[0, 0]);
+        datagram.extend_from_slice(payload);
+        datagram
+    }
+
+    fn ethernet(ethertypes: &[u16], ip: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0x02, 0, 0, 0, 0, 1, 0x02, 0, 0, 0, 0, 2];
+        for (index, ethertype) in ethertypes.iter().enumerate() {
+            frame.extend_from_slice(&ethertype.to_be_bytes());
+            if index + 1 < ethertypes.len() {
+                frame.extend_from_slice(&[0, 100]); // VLAN tag control information
+            }
+        }
+        frame.extend_from_slice(ip);
+        frame
+    }
+
+    #[test]
+    fn decodes_ethernet_ipv4_tcp() {
+        let frame = ethernet(&[ETHERTYPE_IPV4], &ipv4(IPPROTO_TCP, 0, &tcp(51000, 443, TCP_SYN | TCP_ACK, b"hello")));
+        let packet = decode(LINKTYPE_ETHERNET, &frame).unwrap();
+        assert_eq!(packet.src, "192.168.1.10".parse::<IpAddr>().unwrap());
+        assert_eq!(packet.dst, "93.184.216.34".parse::<IpAddr>().unwrap());
+        assert_eq!(packet.ip_len, 20 + 20 + 5);
+        assert_eq!(packet.transport.ports(), (51000, 443));
+        assert_eq!(packet.transport.payload(), b"hello");
+        let Transport::Tcp { seq, flags, .. } = packet.transport else {
+            panic!("expected TCP");
+        };
+        assert_eq!(seq, 1000);
+        assert_eq!(flags, TCP_SYN | TCP_ACK);
+    }
+
+    #[test]
+    fn decodes_vlan_tagged_ipv6_udp() {
+        let ip = ipv6(IPPROTO_UDP, &[], &udp(5353, 53, b"query"));
+        let frame = ethernet(&[ETHERTYPE_QINQ, ETHERTYPE_VLAN, ETHERTYPE_IPV6], &ip);
+        let packet = decode(LINKTYPE_ETHERNET, &frame).unwrap();
+        assert_eq!(packet.src, "2001:db8::1".parse::<IpAddr>().unwrap());
+        assert_eq!(packet.dst, "2001:db8::2".parse::<IpAddr>().unwrap());
+        assert_eq!(packet.ip_len, 40 + 8 + 5);
+        assert!(matches!(packet.transport, Transport::Udp { .. }));
+        assert_eq!(packet.transport.ports(), (5353, 53));
+        assert_eq!(packet.transport.payload(), b"query");
+    }
+
+    #[test]
+    fn skips_ipv6_extension_headers() {
+        // Hop-by-hop options, then a first fragment
+        let extensions = [44, 0, 0, 0, 0, 0, 0, 0, IPPROTO_TCP, 0, 0, 0x01, 0, 0, 0, 7];
+        let ip = ipv6(0, &extensions, &tcp(40000, 22, TCP_ACK, b"ssh"));
+        let packet = decode(LINKTYPE_RAW, &ip).unwrap();
+        assert_eq!(packet.transport.ports(), (40000, 22));
+        assert_eq!(packet.transport.payload(), b"ssh");
+
+        // Later fragments carry no transport header
+        let extensions = [IPPROTO_TCP, 0, 0x00, 0x09, 0, 0, 0, 7];
+        let ip = ipv6(44, &extensions, b"continuation");
+        assert!(decode(LINKTYPE_RAW, &ip).is_none());
+    }
+
+    #[test]
+    fn decodes_other_link_types() {
+        let ip = ipv4(IPPROTO_UDP, 0, &udp(1234, 53, b"q"));
+
+        let mut sll = vec![0; 16];
+        sll.extend_from_slice(&ip);
+        assert!(decode(LINKTYPE_LINUX_SLL, &sll).is_some());
+
+        let mut sll2 = vec![0; 20];
+        sll2.extend_from_slice(&ip);
+        assert!(decode(LINKTYPE_LINUX_SLL2, &sll2).is_some());
+
+        let mut null = 2u32.to_le_bytes().to_vec();
+        null.extend_from_slice(&ip);
+        assert!(decode(LINKTYPE_NULL, &null).is_some());
+
+        assert!(decode(LINKTYPE_IPV4, &ip).is_some());
+        assert!(decode(147, &ip).is_none());
+    }
+
+    #[test]
+    fn ignores_non_ip_and_non_first_fragments() {
+        let arp = ethernet(&[0x0806], &[0; 28]);
+        assert!(decode(LINKTYPE_ETHERNET, &arp).is_none());
+
+        let icmp = ipv4(1, 0, &[8, 0, 0, 0, 0, 1, 0, 1]);
+        assert!(decode(LINKTYPE_RAW, &icmp).is_none());
+
+        let fragment = ipv4(IPPROTO_TCP, 185, &tcp(1, 2, 0, b""));
+        assert!(decode(LINKTYPE_RAW, &fragment).is_none());
+    }
+
+    #[test]
+    fn tolerates_truncated_frames() {
+        let frame = ethernet(&[ETHERTYPE_IPV4], &ipv4(IPPROTO_TCP, 0, &tcp(51000, 443, TCP_ACK, b"payload")));
+
+        // Cut by the snap length inside the payload: decoded with what was captured
+        let packet = decode(LINKTYPE_ETHERNET, &frame[..frame.len() - 3]).unwrap();
+        assert_eq!(packet.transport.payload(), b"payl");
+        assert_eq!(packet.ip_len, 20 + 20 + 7);
+
+        // Cut inside the TCP header, the IP header or the Ethernet header
+        for len in [14 + 20 + 10, 14 + 12, 13, 0] {
+            assert!(decode(LINKTYPE_ETHERNET, &frame[..len]).is_none(), "len = {}", len);
+        }
+
+        // Cut inside the UDP header or the IPv6 addresses
+        let ip = ipv6(IPPROTO_UDP, &[], &udp(1, 2, b"x"));
+        assert!(decode(LINKTYPE_RAW, &ip[..43]).is_none());
+        assert!(decode(LINKTYPE_RAW, &ip[..30]).is_none());
+
+        // VLAN tag with nothing behind it
+        assert!(decode(LINKTYPE_ETHERNET, &ethernet(&[ETHERTYPE_VLAN], &[])).is_none());
+    }
+
+    #[test]
+    fn rejects_short_ipv4_header_length() {
+        let mut ip = ipv4(IPPROTO_TCP, 0, &tcp(1, 2, 0, b""));
+        ip[0] = 0x44;
+        assert!(decode(LINKTYPE_RAW, &ip).is_none());
+    }
+}
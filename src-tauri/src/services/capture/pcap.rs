@@ -0,0 +1,520 @@
+use std::io::Read;
+
+const PCAP_MAGIC_MICROS: u32 = 0xa1b2_c3d4;
+const PCAP_MAGIC_NANOS: u32 = 0xa1b2_3c4d;
+const PCAPNG_SECTION_HEADER: u32 = 0x0a0d_0d0a;
+const PCAPNG_BYTE_ORDER_MAGIC: u32 = 0x1a2b_3c4d;
+
+const BLOCK_INTERFACE_DESCRIPTION: u32 = 0x0000_0001;
+const BLOCK_OBSOLETE_PACKET: u32 = 0x0000_0002;
+const BLOCK_SIMPLE_PACKET: u32 = 0x0000_0003;
+const BLOCK_ENHANCED_PACKET: u32 = 0x0000_0006;
+
+const OPTION_IF_TSRESOL: u16 = 9;
+
+/// Records larger than this are treated as file corruption.
+const MAX_RECORD_LEN: usize = 256 * 1024 * 1024;
+
+/// One captured frame, link-layer header included.
+pub struct RawPacket {
+    pub timestamp_us: u64,
+    pub link_type: u16,
+    pub data: Vec<u8>,
+}
+
+/// Streaming reader for classic pcap and pcapng files.
+pub struct CaptureReader<R: Read> {
+    reader: R,
+    format: Format,
+}
+
+enum Format {
+    Pcap {
+        big_endian: bool,
+        nanos: bool,
+        link_type: u16,
+    },
+    PcapNg {
+        big_endian: bool,
+        interfaces: Vec<Interface>,
+    },
+}
+
+struct Interface {
+    link_type: u16,
+    units_per_second: u64,
+}
+
+impl<R: Read> CaptureReader<R> {
+    pub fn new(mut reader: R) -> Result<Self, String> {
+        let mut magic = [0u8; 4];
+        reader
+            .read_exact(&mut magic)
+            .map_err(|e| format!("Failed to read capture header: {}", e))?;
+
+        let format = match (u32::from_le_bytes(magic), u32::from_be_bytes(magic)) {
+            (PCAP_MAGIC_MICROS, _) | (_, PCAP_MAGIC_MICROS) | (PCAP_MAGIC_NANOS, _) | (_, PCAP_MAGIC_NANOS) => {
+                let big_endian = matches!(u32::from_be_bytes(magic), PCAP_MAGIC_MICROS | PCAP_MAGIC_NANOS);
+                let nanos = read_u32(&magic, 0, big_endian) == PCAP_MAGIC_NANOS;
+
+                let mut header = [0u8; 20];
+                reader
+                    .read_exact(&mut header)
+                    .map_err(|e| format!("Truncated pcap header: {}", e))?;
+                Format::Pcap {
+                    big_endian,
+                    nanos,
+                    link_type: read_u32(&header, 16, big_endian) as u16,
+                }
+            }
+            (PCAPNG_SECTION_HEADER, _) => Format::PcapNg {
+                big_endian: false,
+                interfaces: Vec::new(),
+            },
+            _ => return Err("Not a pcap or pcapng file".to_string()),
+        };
+
+        let mut capture = Self { reader, format };
+        if matches!(capture.format, Format::PcapNg { .. }) {
+            // The magic we consumed is the type of the first section header block
+            capture.read_section_header()?;
+        }
+        Ok(capture)
+    }
+
+    pub fn next_packet(&mut self) -> Result<Option<RawPacket>, String> {
+        match self.format {
+            Format::Pcap { .. } => self.next_pcap_packet(),
+            Format::PcapNg { .. } => self.next_pcapng_packet(),
+        }
+    }
+
+    fn next_pcap_packet(&mut self) -> Result<Option<RawPacket>, String> {
+        let Format::Pcap { big_endian, nanos, link_type } = self.format else {
+            unreachable!()
+        };
+
+        let mut header = [0u8; 16];
+        if !read_or_eof(&mut self.reader, &mut header)? {
+            return Ok(None);
+        }
+
+        let seconds = read_u32(&header, 0, big_endian) as u64;
+        let fraction = read_u32(&header, 4, big_endian) as u64;
+        let captured_len = read_u32(&header, 8, big_endian) as usize;
+        let data = self.read_body(captured_len)?;
+
+        let micros = if nanos { fraction / 1000 } else { fraction };
+        Ok(Some(RawPacket {
+            timestamp_us: seconds * 1_000_000 + micros,
+            link_type,
+            data,
+        }))
+    }
+
+    fn next_pcapng_packet(&mut self) -> Result<Option<RawPacket>, String> {
+        loop {
+            let mut header = [0u8; 8];
+            if !read_or_eof(&mut self.reader, &mut header)? {
+                return Ok(None);
+            }
+
+            let big_endian = match self.format {
+                Format::PcapNg { big_endian, .. } => big_endian,
+                Format::Pcap { .. } => unreachable!(),
+            };
+            let block_type = read_u32(&header, 0, big_endian);
+
+            if block_type == PCAPNG_SECTION_HEADER {
+                // A new section restarts byte order and interface numbering
+                self.read_section_header_with_length(&header[4..8])?;
+                continue;
+            }
+
+            let total_len = read_u32(&header, 4, big_endian) as usize;
+            if total_len < 12 || total_len % 4 != 0 {
+                return Err(format!("Invalid pcapng block length {}", total_len));
+            }
+            // Body plus the trailing copy of the block length
+            let body = self.read_body(total_len - 8)?;
+            let body = &body[..body.len() - 4];
+
+            let Format::PcapNg { big_endian, ref mut interfaces } = self.format else {
+                unreachable!()
+            };
+
+            match block_type {
+                BLOCK_INTERFACE_DESCRIPTION => interfaces.push(parse_interface(body, big_endian)?),
+                BLOCK_ENHANCED_PACKET | BLOCK_OBSOLETE_PACKET => {
+                    if body.len() < 20 {
+                        return Err("Truncated pcapng packet block".to_string());
+                    }
+                    let interface_id = if block_type == BLOCK_ENHANCED_PACKET {
+                        read_u32(body, 0, big_endian) as usize
+                    } else {
+                        read_u16(body, 0, big_endian) as usize
+                    };
+                    let interface = interfaces
+                        .get(interface_id)
+                        .ok_or_else(|| format!("Packet references unknown interface {}", interface_id))?;
+
+                    let timestamp = ((read_u32(body, 4, big_endian) as u64) << 32) | read_u32(body, 8, big_endian) as u64;
+                    let captured_len = read_u32(body, 12, big_endian) as usize;
+                    let data = body
+                        .get(20..20 + captured_len)
+                        .ok_or("Truncated pcapng packet data")?
+                        .to_vec();
+
+                    return Ok(Some(RawPacket {
+                        timestamp_us: (timestamp as u128 * 1_000_000 / interface.units_per_second as u128) as u64,
+                        link_type: interface.link_type,
+                        data,
+                    }));
+                }
+                BLOCK_SIMPLE_PACKET => {
+                    // Simple packet blocks carry no timestamp and always use interface 0
+                    let interface = interfaces.first().ok_or("Simple packet block without an interface")?;
+                    if body.len() < 4 {
+                        return Err("Truncated pcapng simple packet block".to_string());
+                    }
+                    let original_len = read_u32(body, 0, big_endian) as usize;
+                    let data_len = original_len.min(body.len().saturating_sub(4));
+                    return Ok(Some(RawPacket {
+                        timestamp_us: 0,
+                        link_type: interface.link_type,
+                        data: body[4..4 + data_len].to_vec(),
+                    }));
+                }
+                // Name resolution, statistics and custom blocks are not needed
+                _ => continue,
+            }
+        }
+    }
+
+    fn read_section_header(&mut self) -> Result<(), String> {
+        let mut length = [0u8; 4];
+        self.reader
+            .read_exact(&mut length)
+            .map_err(|e| format!("Truncated pcapng section header: {}", e))?;
+        self.read_section_header_with_length(&length)
+    }
+
+    fn read_section_header_with_length(&mut self, raw_length: &[u8]) -> Result<(), String> {
+        let mut byte_order = [0u8; 4];
+        self.reader
+            .read_exact(&mut byte_order)
+            .map_err(|e| format!("Truncated pcapng section header: {}", e))?;
+
+        let big_endian = match (u32::from_le_bytes(byte_order), u32::from_be_bytes(byte_order)) {
+            (PCAPNG_BYTE_ORDER_MAGIC, _) => false,
+            (_, PCAPNG_BYTE_ORDER_MAGIC) => true,
+            _ => return Err("Invalid pcapng byte-order magic".to_string()),
+        };
+
+        let total_len = read_u32(raw_length, 0, big_endian) as usize;
+        if total_len < 28 {
+            return Err(format!("Invalid pcapng section header length {}", total_len));
+        }
+        // Skip version, section length, options and trailing length
+        self.read_body(total_len - 12)?;
+
+        self.format = Format::PcapNg {
+            big_endian,
+            interfaces: Vec::new(),
+        };
+        Ok(())
+    }
+
+    fn read_body(&mut self, len: usize) -> Result<Vec<u8>, String> {
+        if len > MAX_RECORD_LEN {
+            return Err(format!("Capture record of {} bytes is too large", len));
+        }
+        let mut body = vec![0u8; len];
+        self.reader
+            .read_exact(&mut body)
+            .map_err(|e| format!("Truncated capture record: {}", e))?;
+        Ok(body)
+    }
+}
+
+fn parse_interface(body: &[u8], big_endian: bool) -> Result<Interface, String> {
+    if body.len() < 8 {
+        return Err("Truncated pcapng interface description".to_string());
+    }
+
+    let mut interface = Interface {
+        link_type: read_u16(body, 0, big_endian),
+        units_per_second: 1_000_000,
+    };
+
+    let mut offset = 8;
+    while offset + 4 <= body.len() {
+        let code = read_u16(body, offset, big_endian);
+        let len = read_u16(body, offset + 2, big_endian) as usize;
+        if code == 0 {
+            break;
+        }
+        if code == OPTION_IF_TSRESOL && len >= 1 {
+            if let Some(&resolution) = body.get(offset + 4) {
+                let exponent = (resolution & 0x7f) as u32;
+                let units = if resolution & 0x80 == 0 {
+                    10u64.checked_pow(exponent)
+                } else {
+                    2u64.checked_pow(exponent)
+                };
+                interface.units_per_second = units.filter(|u| *u > 0).unwrap_or(1_000_000);
+            }
+        }
+        offset += 4 + len.div_ceil(4) * 4;
+    }
+
+    Ok(interface)
+}
+
+/// Fills `buf` completely, or returns `false` on a clean end of file.
+fn read_or_eof<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<bool, String> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err("Capture file ends in the middle of a record".to_string()),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(format!("Failed to read capture file: {}", e)),
+        }
+    }
+    Ok(true)
+}
+
+fn read_u16(data: &[u8], offset: usize, big_endian: bool) -> u16 {
+    let bytes = [data[offset], data[offset + 1]];
+    if big_endian {
+        u16::from_be_bytes(bytes)
+    } else {
+        u16::from_le_bytes(bytes)
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize, big_endian: bool) -> u32 {
+    let bytes = [data[offset], data[offset + 1], data[offset + 2], data[offset + 3]];
+    if big_endian {
+        u32::from_be_bytes(bytes)
+    } else {
+        u32::from_le_bytes(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn put_u16(out: &mut Vec<u8>, value: u16, big_endian: bool) {
+        out.extend_from_slice(&if big_endian { value.to_be_bytes() } else { value.to_le_bytes() });
+    }
+
+    fn put_u32(out: &mut Vec<u8>, value: u32, big_endian: bool) {
+        out.extend_from_slice(&if big_endian { value.to_be_bytes() } else { value.to_le_bytes() });
+    }
+
+    fn pcap_file(magic: u32, big_endian: bool, link_type: u32, records: &[(u32, u32, &[u8])]) -> Vec<u8> {
+        let mut file = Vec::new();
+        put_u32(&mut file, magic, big_endian);
+        put_u16(&mut file, 2, big_endian);
+        put_u16(&mut file, 4, big_endian);
+        put_u32(&mut file, 0, big_endian); // thiszone
+        put_u32(&mut file, 0, big_endian); // sigfigs
+        put_u32(&mut file, 65535, big_endian);
+        put_u32(&mut file, link_type, big_endian);
+        for (seconds, fraction, data) in records {
+            put_u32(&mut file, *seconds, big_endian);
+            put_u32(&mut file, *fraction, big_endian);
+            put_u32(&mut file, data.len() as u32, big_endian);
+            put_u32(&mut file, data.len() as u32, big_endian);
+            file.extend_from_slice(data);
+        }
+        file
+    }
+
+    fn block(block_type: u32, body: &[u8], big_endian: bool) -> Vec<u8> {
+        let mut padded = body.to_vec();
+        padded.resize(body.len().div_ceil(4) * 4, 0);
+        let total_len = padded.len() as u32 + 12;
+        let mut out = Vec::new();
+        put_u32(&mut out, block_type, big_endian);
+        put_u32(&mut out, total_len, big_endian);
+        out.extend_from_slice(&padded);
+        put_u32(&mut out, total_len, big_endian);
+        out
+    }
+
+    fn section_header(big_endian: bool) -> Vec<u8> {
+        let mut body = Vec::new();
+        put_u32(&mut body, PCAPNG_BYTE_ORDER_MAGIC, big_endian);
+        put_u16(&mut body, 1, big_endian);
+        put_u16(&mut body, 0, big_endian);
+        body.extend_from_slice(&[0xff; 8]); // section length not specified
+        block(PCAPNG_SECTION_HEADER, &body, big_endian)
+    }
+
+    fn interface_description(link_type: u16, tsresol: Option<u8>, big_endian: bool) -> Vec<u8> {
+        let mut body = Vec::new();
+        put_u16(&mut body, link_type, big_endian);
+        put_u16(&mut body, 0, big_endian);
+        put_u32(&mut body, 65535, big_endian);
+        if let Some(resolution) = tsresol {
+            put_u16(&mut body, OPTION_IF_TSRESOL, big_endian);
+            put_u16(&mut body, 1, big_endian);
+            body.extend_from_slice(&[resolution, 0, 0, 0]);
+            put_u32(&mut body, 0, big_endian); // opt_endofopt
+        }
+        block(BLOCK_INTERFACE_DESCRIPTION, &body, big_endian)
+    }
+
+    fn enhanced_packet(interface_id: u32, timestamp: u64, data: &[u8], big_endian: bool) -> Vec<u8> {
+        let mut body = Vec::new();
+        put_u32(&mut body, interface_id, big_endian);
+        put_u32(&mut body, (timestamp >> 32) as u32, big_endian);
+        put_u32(&mut body, timestamp as u32, big_endian);
+        put_u32(&mut body, data.len() as u32, big_endian);
+        put_u32(&mut body, data.len() as u32, big_endian);
+        body.extend_from_slice(data);
+        block(BLOCK_ENHANCED_PACKET, &body, big_endian)
+    }
+
+    fn read_all(file: &[u8]) -> Result<Vec<RawPacket>, String> {
+        let mut reader = CaptureReader::new(file)?;
+        let mut packets = Vec::new();
+        while let Some(packet) = reader.next_packet()? {
+            packets.push(packet);
+        }
+        Ok(packets)
+    }
+
+    #[test]
+    fn reads_little_endian_pcap() {
+        let file = pcap_file(PCAP_MAGIC_MICROS, false, 1, &[(10, 250, b"abcd"), (11, 0, b"ef")]);
+        let packets = read_all(&file).unwrap();
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0].timestamp_us, 10_000_250);
+        assert_eq!(packets[0].link_type, 1);
+        assert_eq!(packets[0].data, b"abcd");
+        assert_eq!(packets[1].timestamp_us, 11_000_000);
+        assert_eq!(packets[1].data, b"ef");
+    }
+
+    #[test]
+    fn reads_big_endian_nanosecond_pcap() {
+        let file = pcap_file(PCAP_MAGIC_NANOS, true, 101, &[(2, 1_500_000, b"xyz")]);
+        let packets = read_all(&file).unwrap();
+        assert_eq!(packets.len(), 1);
+        assert_eq!(packets[0].timestamp_us, 2_001_500);
+        assert_eq!(packets[0].link_type, 101);
+        assert_eq!(packets[0].data, b"xyz");
+    }
+
+    #[test]
+    fn rejects_unknown_magic_and_short_headers() {
+        assert!(CaptureReader::new(&b"GIF89a"[..]).is_err());
+        assert!(CaptureReader::new(&b"\xd4\xc3"[..]).is_err());
+        let file = pcap_file(PCAP_MAGIC_MICROS, false, 1, &[]);
+        assert!(CaptureReader::new(&file[..12]).is_err());
+    }
+
+    #[test]
+    fn reports_truncated_pcap_records() {
+        let file = pcap_file(PCAP_MAGIC_MICROS, false, 1, &[(1, 0, b"abcdef")]);
+        // Header only, no records: a clean end of file
+        assert!(read_all(&file[..24]).unwrap().is_empty());
+        // Cut inside the record header
+        assert!(read_all(&file[..30]).is_err());
+        // Cut inside the packet data
+        assert!(read_all(&file[..file.len() - 2]).is_err());
+    }
+
+    #[test]
+    fn rejects_oversized_pcap_records() {
+        let mut file = pcap_file(PCAP_MAGIC_MICROS, false, 1, &[(1, 0, b"a")]);
+        file[32..36].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(read_all(&file).is_err());
+    }
+
+    #[test]
+    fn reads_pcapng_in_both_byte_orders() {
+        for big_endian in [false, true] {
+            let mut file = section_header(big_endian);
+            file.extend(interface_description(1, None, big_endian));
+            file.extend(interface_description(101, Some(9), big_endian));
+            file.extend(block(0x0000_0004, &[0; 8], big_endian)); // name resolution, skipped
+            file.extend(enhanced_packet(0, 5_000_123, b"first", big_endian));
+            file.extend(enhanced_packet(1, 7_000_000_456, b"second", big_endian));
+
+            let packets = read_all(&file).unwrap();
+            assert_eq!(packets.len(), 2, "big_endian = {}", big_endian);
+            assert_eq!(packets[0].link_type, 1);
+            assert_eq!(packets[0].timestamp_us, 5_000_123);
+            assert_eq!(packets[0].data, b"first");
+            // if_tsresol 9 means nanosecond timestamps
+            assert_eq!(packets[1].link_type, 101);
+            assert_eq!(packets[1].timestamp_us, 7_000_000);
+            assert_eq!(packets[1].data, b"second");
+        }
+    }
+
+    #[test]
+    fn new_pcapng_section_resets_byte_order_and_interfaces() {
+        let mut file = section_header(false);
+        file.extend(interface_description(1, None, false));
+        file.extend(enhanced_packet(0, 1, b"le", false));
+        file.extend(section_header(true));
+        file.extend(interface_description(101, None, true));
+        file.extend(enhanced_packet(0, 2, b"be", true));
+
+        let packets = read_all(&file).unwrap();
+        assert_eq!(packets.len(), 2);
+        assert_eq!((packets[0].link_type, packets[0].data.as_slice()), (1, &b"le"[..]));
+        assert_eq!((packets[1].link_type, packets[1].data.as_slice()), (101, &b"be"[..]));
+
+        // Interfaces from an earlier section are not visible in the next one
+        let mut file = section_header(false);
+        file.extend(interface_description(1, None, false));
+        file.extend(section_header(false));
+        file.extend(enhanced_packet(0, 1, b"orphan", false));
+        assert!(read_all(&file).is_err());
+    }
+
+    #[test]
+    fn reports_malformed_pcapng_blocks() {
+        // Packet before any interface description
+        let mut file = section_header(false);
+        file.extend(enhanced_packet(0, 1, b"data", false));
+        assert!(read_all(&file).is_err());
+
+        // Block cut short
+        let mut file = section_header(false);
+        file.extend(interface_description(1, None, false));
+        let packet = enhanced_packet(0, 1, b"data", false);
+        file.extend(&packet[..packet.len() - 6]);
+        assert!(read_all(&file).is_err());
+
+        // Block length that is not a multiple of four
+        let mut file = section_header(false);
+        file.extend(interface_description(1, None, false));
+        let mut packet = enhanced_packet(0, 1, b"data", false);
+        packet[4..8].copy_from_slice(&33u32.to_le_bytes());
+        file.extend(packet);
+        assert!(read_all(&file).is_err());
+
+        // Captured length past the end of the block
+        let mut file = section_header(false);
+        file.extend(interface_description(1, None, false));
+        let mut packet = enhanced_packet(0, 1, b"data", false);
+        packet[20..24].copy_from_slice(&64u32.to_le_bytes());
+        file.extend(packet);
+        assert!(read_all(&file).is_err());
+
+        // Section header with a bad byte-order magic
+        let mut file = section_header(false);
+        file[8..12].copy_from_slice(&[1, 2, 3, 4]);
+        assert!(CaptureReader::new(&file[..]).is_err());
+    }
+}
@@ -0,0 +1,509 @@
+use crate::models::TlsInfo;
+use md5::{Digest, Md5};
+use sha2::Sha256;
+
+const CONTENT_HANDSHAKE: u8 = 22;
+const HANDSHAKE_CLIENT_HELLO: u8 = 1;
+
+const EXT_SERVER_NAME: u16 = 0x0000;
+const EXT_SUPPORTED_GROUPS: u16 = 0x000a;
+const EXT_EC_POINT_FORMATS: u16 = 0x000b;
+const EXT_SIGNATURE_ALGORITHMS: u16 = 0x000d;
+const EXT_ALPN: u16 = 0x0010;
+const EXT_SUPPORTED_VERSIONS: u16 = 0x002b;
+
+/// Client bytes buffered while waiting for a ClientHello split over segments.
+pub const MAX_HELLO_BYTES: usize = 16 * 1024;
+
+pub enum HelloParse {
+    /// Looks like TLS but the handshake message is not complete yet.
+    Incomplete,
+    NotTls,
+    Hello(ClientHello),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ClientHello {
+    pub legacy_version: u16,
+    pub cipher_suites: Vec<u16>,
+    pub extensions: Vec<u16>,
+    pub server_name: Option<String>,
+    pub alpn: Vec<String>,
+    pub supported_groups: Vec<u16>,
+    pub ec_point_formats: Vec<u8>,
+    pub signature_algorithms: Vec<u16>,
+    pub supported_versions: Vec<u16>,
+}
+
+/// Parses the start of a client-to-server TCP stream as TLS records carrying
+/// a ClientHello, reassembling the handshake across records if needed.
+pub fn parse_client_hello(stream: &[u8]) -> HelloParse {
+    let mut handshake = Vec::new();
+    let mut offset = 0;
+
+    loop {
+        let Some(header) = stream.get(offset..offset + 5) else {
+            return HelloParse::Incomplete;
+        };
+        // Record type, then a 3.x record version
+        if header[0] != CONTENT_HANDSHAKE || header[1] != 3 {
+            return HelloParse::NotTls;
+        }
+        let record_len = u16::from_be_bytes([header[3], header[4]]) as usize;
+        let Some(fragment) = stream.get(offset + 5..offset + 5 + record_len) else {
+            return HelloParse::Incomplete;
+        };
+        handshake.extend_from_slice(fragment);
+        offset += 5 + record_len;
+
+        if handshake.len() < 4 {
+            continue;
+        }
+        if handshake[0] != HANDSHAKE_CLIENT_HELLO {
+            return HelloParse::NotTls;
+        }
+        let body_len = u32::from_be_bytes([0, handshake[1], handshake[2], handshake[3]]) as usize;
+        if handshake.len() >= 4 + body_len {
+            return match parse_hello_body(&handshake[4..4 + body_len]) {
+                Some(hello) => HelloParse::Hello(hello),
+                None => HelloParse::NotTls,
+            };
+        }
+    }
+}
+
+fn parse_hello_body(body: &[u8]) -> Option<ClientHello> {
+    let mut reader = Reader::new(body);
+    let mut hello = ClientHello {
+        legacy_version: reader.u16()?,
+        ..Default::default()
+    };
+    reader.skip(32)?; // random
+    let session_id_len = reader.u8()? as usize;
+    reader.skip(session_id_len)?;
+
+    let mut ciphers = Reader::new(reader.vec16()?);
+    while let Some(cipher) = ciphers.u16() {
+        hello.cipher_suites.push(cipher);
+    }
+    let compression_len = reader.u8()? as usize;
+    reader.skip(compression_len)?;
+
+    // Extensions are optional in very old clients
+    let Some(extensions) = reader.vec16() else {
+        return Some(hello);
+    };
+    let mut extensions = Reader::new(extensions);
+    while let Some(ext_type) = extensions.u16() {
+        let data = extensions.vec16()?;
+        hello.extensions.push(ext_type);
+        let mut data = Reader::new(data);
+
+        match ext_type {
+            EXT_SERVER_NAME => {
+                let mut names = Reader::new(data.vec16()?);
+                while let Some(name_type) = names.u8() {
+                    let name = names.vec16()?;
+                    if name_type == 0 {
+                        hello.server_name = Some(String::from_utf8_lossy(name).to_lowercase());
+                    }
+                }
+            }
+            EXT_ALPN => {
+                let mut protocols = Reader::new(data.vec16()?);
+                while let Some(protocol) = protocols.vec8() {
+                    hello.alpn.push(String::from_utf8_lossy(protocol).into_owned());
+                }
+            }
+            EXT_SUPPORTED_GROUPS => {
+                let mut groups = Reader::new(data.vec16()?);
+                while let Some(group) = groups.u16() {
+                    hello.supported_groups.push(group);
+                }
+            }
+            EXT_EC_POINT_FORMATS => hello.ec_point_formats = data.vec8()?.to_vec(),
+            EXT_SIGNATURE_ALGORITHMS => {
+                let mut algorithms = Reader::new(data.vec16()?);
+                while let Some(algorithm) = algorithms.u16() {
+                    hello.signature_algorithms.push(algorithm);
+                }
+            }
+            EXT_SUPPORTED_VERSIONS => {
+                let mut versions = Reader::new(data.vec8()?);
+                while let Some(version) = versions.u16() {
+                    hello.supported_versions.push(version);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Some(hello)
+}
+
+/// GREASE values (RFC 8701) are random per connection and excluded from fingerprints.
+fn is_grease(value: u16) -> bool {
+    value & 0x0f0f == 0x0a0a && value >> 8 == value & 0xff
+}
+
+impl ClientHello {
+    /// The highest version offered, preferring the supported_versions extension.
+    fn max_version(&self) -> u16 {
+        self.supported_versions
+            .iter()
+            .copied()
+            .filter(|v| !is_grease(*v))
+            .max()
+            .unwrap_or(self.legacy_version)
+    }
+
+    pub fn to_info(&self) -> TlsInfo {
+        let ja3 = self.ja3();
+        TlsInfo {
+            sni: self.server_name.clone(),
+            alpn: self.alpn.clone(),
+            version: version_name(self.max_version()),
+            ja3_hash: hex(&Md5::digest(ja3.as_bytes())),
+            ja3,
+            ja4: self.ja4(),
+        }
+    }
+
+    /// `SSLVersion,Ciphers,Extensions,EllipticCurves,EllipticCurvePointFormats`
+    fn ja3(&self) -> String {
+        fn join<T: ToString>(values: impl Iterator<Item = T>) -> String {
+            values.map(|v| v.to_string()).collect::<Vec<_>>().join("-")
+        }
+
+        format!(
+            "{},{},{},{},{}",
+            self.legacy_version,
+            join(self.cipher_suites.iter().filter(|v| !is_grease(**v))),
+            join(self.extensions.iter().filter(|v| !is_grease(**v))),
+            join(self.supported_groups.iter().filter(|v| !is_grease(**v))),
+            join(self.ec_point_formats.iter()),
+        )
+    }
+
+    /// JA4 for TLS over TCP: `t<ver><sni><ciphers><exts><alpn>_<ciphers hash>_<exts hash>`.
+    fn ja4(&self) -> String {
+        let ciphers: Vec<u16> = self.cipher_suites.iter().copied().filter(|v| !is_grease(*v)).collect();
+        let extensions: Vec<u16> = self.extensions.iter().copied().filter(|v| !is_grease(*v)).collect();
+
+        let version = match self.max_version() {
+            0x0304 => "13",
+            0x0303 => "12",
+            0x0302 => "11",
+            0x0301 => "10",
+            0x0300 => "s3",
+            _ => "00",
+        };
+        let sni = if self.server_name.is_some() { 'd' } else { 'i' };
+        let alpn = match self.alpn.first().map(String::as_bytes) {
+            Some([first, .., last]) if first.is_ascii_alphanumeric() && last.is_ascii_alphanumeric() => {
+                format!("{}{}", *first as char, *last as char)
+            }
+            Some([single]) if single.is_ascii_alphanumeric() => format!("{}{}", *single as char, *single as char),
+            // Non-alphanumeric edges fall back to the hex of the first and last bytes
+            Some(bytes @ [first, ..]) => {
+                let first = format!("{:02x}", first);
+                let last = format!("{:02x}", bytes[bytes.len() - 1]);
+                format!("{}{}", &first[..1], &last[1..])
+            }
+            _ => "00".to_string(),
+        };
+        let prefix = format!(
+            "t{}{}{:02}{:02}{}",
+            version,
+            sni,
+            ciphers.len().min(99),
+            extensions.len().min(99),
+            alpn
+        );
+
+        let mut sorted_ciphers = ciphers;
+        sorted_ciphers.sort_unstable();
+        let cipher_list = hex_list(&sorted_ciphers);
+
+        // SNI and ALPN are already in the prefix
+        let mut sorted_extensions: Vec<u16> = extensions
+            .into_iter()
+            .filter(|ext| *ext != EXT_SERVER_NAME && *ext != EXT_ALPN)
+            .collect();
+        sorted_extensions.sort_unstable();
+        let mut extension_list = hex_list(&sorted_extensions);
+        if !self.signature_algorithms.is_empty() {
+            extension_list.push('_');
+            extension_list.push_str(&hex_list(&self.signature_algorithms));
+        }
+
+        format!("{}_{}_{}", prefix, truncated_sha256(&cipher_list), truncated_sha256(&extension_list))
+    }
+}
+
+fn version_name(version: u16) -> String {
+    match version {
+        0x0304 => "TLS 1.3".to_string(),
+        0x0303 => "TLS 1.2".to_string(),
+        0x0302 => "TLS 1.1".to_string(),
+        0x0301 => "TLS 1.0".to_string(),
+        0x0300 => "SSL 3.0".to_string(),
+        other => format!("0x{:04x}", other),
+    }
+}
+
+fn hex_list(values: &[u16]) -> String {
+    values.iter().map(|v| format!("{:04x}", v)).collect::<Vec<_>>().join(",")
+}
+
+fn truncated_sha256(input: &str) -> String {
+    if input.is_empty() {
+        return "000000000000".to_string();
+    }
+    hex(&Sha256::digest(input.as_bytes()))[..12].to_string()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Bounds-checked big-endian cursor; every read returns `None` past the end.
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let slice = self.data.get(self.offset..self.offset + len)?;
+        self.offset += len;
+        Some(slice)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.take(len).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn vec8(&mut self) -> Option<&'a [u8]> {
+        let len = self.u8()? as usize;
+        self.take(len)
+    }
+
+    fn vec16(&mut self) -> Option<&'a [u8]> {
+        let len = self.u16()? as usize;
+        self.take(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vec8(data: &[u8]) -> Vec<u8> {
+        let mut out = vec![data.len() as u8];
+        out.extend_from_slice(data);
+        out
+    }
+
+    fn vec16(data: &[u8]) -> Vec<u8> {
+        let mut out = (data.len() as u16).to_be_bytes().to_vec();
+        out.extend_from_slice(data);
+        out
+    }
+
+    fn u16_list(values: &[u16]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_be_bytes()).collect()
+    }
+
+    fn server_name(name: &str) -> Vec<u8> {
+        let mut entry = vec![0];
+        entry.extend(vec16(name.as_bytes()));
+        vec16(&entry)
+    }
+
+    fn alpn(protocols: &[&str]) -> Vec<u8> {
+        vec16(&protocols.iter().flat_map(|p| vec8(p.as_bytes())).collect::<Vec<_>>())
+    }
+
+    /// A ClientHello handshake message wrapped in one TLS record.
+    fn client_hello(version: u16, ciphers: &[u16], extensions: &[(u16, Vec<u8>)]) -> Vec<u8> {
+        let mut body = version.to_be_bytes().to_vec();
+        body.extend_from_slice(&[0x11; 32]);
+        body.extend(vec8(&[0x22; 32]));
+        body.extend(vec16(&u16_list(ciphers)));
+        body.extend(vec8(&[0]));
+        let encoded: Vec<u8> = extensions
+            .iter()
+            .flat_map(|(ext_type, data)| {
+                let mut ext = ext_type.to_be_bytes().to_vec();
+                ext.extend(vec16(data));
+                ext
+            })
+            .collect();
+        body.extend(vec16(&encoded));
+
+        let mut handshake = vec![HANDSHAKE_CLIENT_HELLO];
+        handshake.extend_from_slice(&(body.len() as u32).to_be_bytes()[1..]);
+        handshake.extend(body);
+        record(&handshake)
+    }
+
+    fn record(fragment: &[u8]) -> Vec<u8> {
+        let mut out = vec![CONTENT_HANDSHAKE, 3, 1];
+        out.extend(vec16(fragment));
+        out
+    }
+
+    fn parse(stream: &[u8]) -> ClientHello {
+        match parse_client_hello(stream) {
+            HelloParse::Hello(hello) => hello,
+            HelloParse::Incomplete => panic!("incomplete ClientHello"),
+            HelloParse::NotTls => panic!("not a ClientHello"),
+        }
+    }
+
+    /// A Chrome ClientHello, with GREASE values and a shuffled extension order.
+    fn chrome_hello() -> Vec<u8> {
+        let ciphers = [
+            0x6a6a, 0x1301, 0x1302, 0x1303, 0xc02b, 0xc02f, 0xc02c, 0xc030, 0xcca9, 0xcca8, 0xc013, 0xc014, 0x009c,
+            0x009d, 0x002f, 0x0035,
+        ];
+        let signature_algorithms = [0x0403, 0x0804, 0x0401, 0x0503, 0x0805, 0x0501, 0x0806, 0x0601];
+        client_hello(
+            0x0303,
+            &ciphers,
+            &[
+                (0x0a0a, vec![]),
+                (0x0033, vec16(&[0x2a, 0x2a, 0, 1, 0])),
+                (0x0012, vec![]),
+                (EXT_SIGNATURE_ALGORITHMS, vec16(&u16_list(&signature_algorithms))),
+                (EXT_SERVER_NAME, server_name("Www.Example.com")),
+                (0x002d, vec8(&[1])),
+                (0x001b, vec16(&[0, 2])),
+                (EXT_SUPPORTED_GROUPS, vec16(&u16_list(&[0x4a4a, 0x001d, 0x0017, 0x0018]))),
+                (0x0023, vec![]),
+                (EXT_ALPN, alpn(&["h2", "http/1.1"])),
+                (0x4469, vec16(&vec8(b"h2"))),
+                (EXT_EC_POINT_FORMATS, vec8(&[0])),
+                (0xff01, vec8(&[])),
+                (0x0017, vec![]),
+                (0x0005, vec![1, 0, 0, 0, 0]),
+                (EXT_SUPPORTED_VERSIONS, vec8(&u16_list(&[0x8a8a, 0x0304, 0x0303]))),
+                (0x0015, vec![0; 8]),
+                (0x1a1a, vec![0]),
+            ],
+        )
+    }
+
+    #[test]
+    fn ja3_matches_published_vector() {
+        // From the JA3 README: 769,47-53-5-10-49161-49162-49171-49172-50-56-19-4,0-10-11,23-24-25,0
+        let hello = parse(&client_hello(
+            0x0301,
+            &[47, 53, 5, 10, 49161, 49162, 49171, 49172, 50, 56, 19, 4],
+            &[
+                (EXT_SERVER_NAME, server_name("example.com")),
+                (EXT_SUPPORTED_GROUPS, vec16(&u16_list(&[23, 24, 25]))),
+                (EXT_EC_POINT_FORMATS, vec8(&[0])),
+            ],
+        ));
+        let info = hello.to_info();
+        assert_eq!(info.ja3, "769,47-53-5-10-49161-49162-49171-49172-50-56-19-4,0-10-11,23-24-25,0");
+        assert_eq!(info.ja3_hash, "ada70206e40642a3e4461f35503241d5");
+        assert_eq!(info.version, "TLS 1.0");
+        assert!(info.ja4.starts_with("t10d120300_"), "{}", info.ja4);
+    }
+
+    #[test]
+    fn ja4_matches_published_vector() {
+        // The Chrome example from the JA4 specification
+        let info = parse(&chrome_hello()).to_info();
+        assert_eq!(info.ja4, "t13d1516h2_8daaf6152771_e5627efa2ab1");
+        assert_eq!(info.version, "TLS 1.3");
+        assert_eq!(info.sni.as_deref(), Some("www.example.com"));
+        assert_eq!(info.alpn, ["h2", "http/1.1"]);
+    }
+
+    #[test]
+    fn ja3_drops_grease_and_keeps_wire_order() {
+        let info = parse(&chrome_hello()).to_info();
+        assert_eq!(
+            info.ja3,
+            "771,4865-4866-4867-49195-49199-49196-49200-52393-52392-49171-49172-156-157-47-53,\
+             51-18-13-0-45-27-10-35-16-17513-11-65281-23-5-43-21,29-23-24,0"
+        );
+        assert_eq!(info.ja3_hash, hex(&Md5::digest(info.ja3.as_bytes())));
+    }
+
+    #[test]
+    fn ja4_without_sni_or_alpn() {
+        let info = parse(&client_hello(0x0303, &[0xc02f, 0x1301], &[(EXT_SUPPORTED_GROUPS, vec16(&u16_list(&[29])))]))
+            .to_info();
+        assert!(info.ja4.starts_with("t12i020100_"), "{}", info.ja4);
+        // No signature algorithms: the extension hash covers the extension list alone
+        assert!(info.ja4.ends_with(&truncated_sha256("000a")), "{}", info.ja4);
+
+        let info = parse(&client_hello(0x0303, &[], &[])).to_info();
+        assert_eq!(info.ja4, "t12i000000_000000000000_000000000000");
+    }
+
+    #[test]
+    fn ja4_alpn_edge_characters() {
+        let ja4_alpn = |protocol: &str| {
+            let info = parse(&client_hello(0x0303, &[0x1301], &[(EXT_ALPN, alpn(&[protocol]))])).to_info();
+            info.ja4[8..10].to_string()
+        };
+        assert_eq!(ja4_alpn("http/1.1"), "h1");
+        assert_eq!(ja4_alpn("x"), "xx");
+        // Non-alphanumeric first or last byte: first hex digit of the first, last of the last
+        assert_eq!(ja4_alpn("h2\u{0}"), "60");
+        assert_eq!(ja4_alpn("/ab"), "22");
+    }
+
+    #[test]
+    fn reassembles_hello_across_records() {
+        let stream = chrome_hello();
+        let handshake = &stream[5..];
+        let mut split = record(&handshake[..3]);
+        split.extend(record(&handshake[3..100]));
+        split.extend(record(&handshake[100..]));
+
+        assert!(matches!(parse_client_hello(&split[..split.len() - 1]), HelloParse::Incomplete));
+        assert!(matches!(parse_client_hello(&split[..4]), HelloParse::Incomplete));
+        assert_eq!(parse(&split).to_info().ja4, "t13d1516h2_8daaf6152771_e5627efa2ab1");
+    }
+
+    #[test]
+    fn rejects_non_hello_streams() {
+        assert!(matches!(parse_client_hello(b"GET / HTTP/1.1\r\n\r\n"), HelloParse::NotTls));
+        // A ServerHello is a handshake but not a ClientHello
+        assert!(matches!(parse_client_hello(&record(&[2, 0, 0, 0])), HelloParse::NotTls));
+        // Extension length running past the hello
+        let mut stream = client_hello(0x0303, &[0x1301], &[(EXT_SERVER_NAME, server_name("a.example"))]);
+        let len = stream.len();
+        stream[len - 16] = 0xff;
+        assert!(matches!(parse_client_hello(&stream), HelloParse::NotTls));
+    }
+
+    #[test]
+    fn recognises_grease_values() {
+        for value in [0x0a0a, 0x1a1a, 0x2a2a, 0xaaaa, 0xfafa] {
+            assert!(is_grease(value), "{:04x}", value);
+        }
+        for value in [0x0a1a, 0x1301, 0x0000, 0x0b0b, 0x0a0b] {
+            assert!(!is_grease(value), "{:04x}", value);
+        }
+    }
+}
@@ -1,13 +1,703 @@
-use crate::models::Connection;
+use super::{inet_diag, unix_diag};
+use crate::models::{classify_ip, Connection, NetworkNamespace, RawSocketInfo, UnixSocketInfo};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
 
-pub struct LinuxConnectionCollector;
+const NETNS_RUN_DIR: &str = "/run/netns";
+
+/// Reads the kernel socket tables of every network namespace in use and maps
+/// socket inodes to their owning processes through `/proc/<pid>/fd`.
+pub struct LinuxConnectionCollector {
+    proc_root: PathBuf,
+}
+
+/// One row of `/proc/net/{tcp,udp}[6]`.
+struct SocketEntry {
+    local: (IpAddr, u16),
+    remote: (IpAddr, u16),
+    state: u8,
+    inode: u64,
+}
+
+/// One row of `/proc/net/packet`.
+struct PacketEntry {
+    ethertype: u16,
+    ifindex: u32,
+    inode: u64,
+}
+
+/// One row of `/proc/net/unix`.
+struct UnixEntry {
+    path: String,
+    socket_type: u16,
+    state: u8,
+    listening: bool,
+    inode: u64,
+}
+
+/// A network namespace and a process inside it, through whose `/proc/<pid>/net`
+/// the namespace's socket tables are read.
+struct Namespace {
+    info: NetworkNamespace,
+    pid: u32,
+}
 
 impl LinuxConnectionCollector {
     pub fn new() -> Self {
-        Self
+        Self {
+            proc_root: PathBuf::from("/proc"),
+        }
     }
 
     pub fn get_connections(&self) -> Result<Vec<Connection>, String> {
-        Err("Linux connection collection not implemented yet. Planned implementation using 'ss' or 'netstat' commands.".to_string())
+        // Socket inodes are unique across namespaces, so one scan attributes them all
+        let owners = socket_owners(&self.proc_root);
+        tracing::debug!("Mapped {} socket inodes to processes", owners.len());
+
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(|e| format!("Time error: {}", e))?
+            .as_millis() as u64;
+
+        let mut connections = Vec::new();
+        for namespace in self.network_namespaces() {
+            match self.collect_namespace(&namespace, &owners, timestamp) {
+                Ok(found) => connections.extend(found),
+                Err(e) if namespace.info.is_host => return Err(e),
+                // The process may have exited since we listed it
+                Err(e) => tracing::debug!("Skipping namespace {}: {}", namespace.info.name, e),
+            }
+        }
+
+        link_unix_peers(&mut connections, &owners);
+        Ok(connections)
+    }
+
+    fn collect_namespace(
+        &self,
+        namespace: &Namespace,
+        owners: &HashMap<u64, (u32, String)>,
+        timestamp: u64,
+    ) -> Result<Vec<Connection>, String> {
+        let process_dir = self.proc_root.join(namespace.pid.to_string());
+        let net_dir = process_dir.join("net");
+
+        // tcp_info is a bonus; the socket table is still useful without it
+        let tcp_metrics = if namespace.info.is_host {
+            inet_diag::tcp_metrics_by_inode()
+        } else {
+            inet_diag::tcp_metrics_in_namespace(&process_dir.join("ns/net"))
+        }
+        .unwrap_or_else(|e| {
+            tracing::debug!("TCP metrics unavailable in {}: {}", namespace.info.name, e);
+            HashMap::new()
+        });
+
+        let mut connections = Vec::new();
+        for (file, protocol) in [
+            ("tcp", "TCP"),
+            ("tcp6", "TCP"),
+            ("udp", "UDP"),
+            ("udp6", "UDP"),
+            ("raw", "RAW"),
+            ("raw6", "RAW"),
+            ("icmp", "ICMP"),
+            ("icmp6", "ICMP"),
+        ] {
+            let path = net_dir.join(file);
+            let entries = match read_table(&path).map(|content| parse_socket_table(&content)) {
+                Ok(entries) => entries,
+                // IPv6 may be disabled and ping sockets are newer; only the IPv4 TCP and UDP tables are required
+                Err(e) if file.ends_with('6') || matches!(protocol, "RAW" | "ICMP") => {
+                    tracing::debug!("Skipping {}: {}", path.display(), e);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            for entry in entries {
+                let (pid, process_name) = match owners.get(&entry.inode) {
+                    Some((pid, name)) => (*pid, name.clone()),
+                    None => (0, "unknown".to_string()),
+                };
+                // Raw sockets print their IP protocol, and ping sockets their ICMP ID, as the port
+                let has_ports = matches!(protocol, "TCP" | "UDP");
+
+                connections.push(Connection {
+                    id: Uuid::new_v4().to_string(),
+                    process_name,
+                    pid,
+                    namespace: Some(namespace.info.clone()),
+                    protocol: protocol.to_string(),
+                    local_addr: entry.local.0.to_string(),
+                    local_port: if has_ports { entry.local.1 } else { 0 },
+                    remote_addr: entry.remote.0.to_string(),
+                    remote_port: if has_ports { entry.remote.1 } else { 0 },
+                    remote_class: classify_ip(entry.remote.0),
+                    state: if protocol == "TCP" {
+                        tcp_state_name(entry.state).to_string()
+                    } else {
+                        "Active".to_string()
+                    },
+                    captured_at: timestamp,
+                    tcp_metrics: if protocol == "TCP" && entry.state != TCP_LISTEN {
+                        tcp_metrics.get(&entry.inode).cloned()
+                    } else {
+                        None
+                    },
+                    raw: match protocol {
+                        "RAW" => Some(RawSocketInfo {
+                            protocol: ip_protocol_name(entry.local.1),
+                            interface: None,
+                        }),
+                        "ICMP" => Some(RawSocketInfo {
+                            protocol: if file == "icmp6" { "ICMPv6" } else { "ICMP" }.to_string(),
+                            interface: None,
+                        }),
+                        _ => None,
+                    },
+                    // Direction, service names and risk need the whole snapshot; see ConnectionCollector
+                    ..Default::default()
+                });
+            }
+        }
+
+        connections.extend(self.collect_packet(namespace, owners, timestamp));
+        connections.extend(self.collect_unix(namespace, owners, timestamp));
+        Ok(connections)
+    }
+
+    /// Packet (link-layer) sockets of a namespace, as used by sniffers and
+    /// DHCP clients.
+    fn collect_packet(
+        &self,
+        namespace: &Namespace,
+        owners: &HashMap<u64, (u32, String)>,
+        timestamp: u64,
+    ) -> Vec<Connection> {
+        let path = self.proc_root.join(namespace.pid.to_string()).join("net/packet");
+        let entries = match read_table(&path).map(|content| parse_packet_table(&content)) {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::debug!("Skipping {}: {}", path.display(), e);
+                return Vec::new();
+            }
+        };
+
+        entries
+            .into_iter()
+            .map(|entry| {
+                let (pid, process_name) = match owners.get(&entry.inode) {
+                    Some((pid, name)) => (*pid, name.clone()),
+                    None => (0, "unknown".to_string()),
+                };
+
+                Connection {
+                    id: Uuid::new_v4().to_string(),
+                    process_name,
+                    pid,
+                    namespace: Some(namespace.info.clone()),
+                    protocol: "PACKET".to_string(),
+                    state: "Active".to_string(),
+                    captured_at: timestamp,
+                    raw: Some(RawSocketInfo {
+                        protocol: ethertype_name(entry.ethertype),
+                        // Interface indexes are per namespace; we can only name our own
+                        interface: match entry.ifindex {
+                            0 => None,
+                            index if namespace.info.is_host => Some(interface_name(index)),
+                            index => Some(format!("ifindex {}", index)),
+                        },
+                    }),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    /// Unix domain sockets of a namespace. Peers are linked up once every
+    /// namespace has been read; see [`link_unix_peers`].
+    fn collect_unix(
+        &self,
+        namespace: &Namespace,
+        owners: &HashMap<u64, (u32, String)>,
+        timestamp: u64,
+    ) -> Vec<Connection> {
+        let process_dir = self.proc_root.join(namespace.pid.to_string());
+        let path = process_dir.join("net/unix");
+        let entries = match read_table(&path).map(|content| parse_unix_table(&content)) {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::debug!("Skipping {}: {}", path.display(), e);
+                return Vec::new();
+            }
+        };
+
+        let peers = if namespace.info.is_host {
+            unix_diag::unix_peers_by_inode()
+        } else {
+            inet_diag::in_namespace(&process_dir.join("ns/net"), unix_diag::unix_peers_by_inode)
+        }
+        .unwrap_or_else(|e| {
+            tracing::debug!("Unix socket peers unavailable in {}: {}", namespace.info.name, e);
+            HashMap::new()
+        });
+
+        entries
+            .into_iter()
+            .map(|entry| {
+                let (pid, process_name) = match owners.get(&entry.inode) {
+                    Some((pid, name)) => (*pid, name.clone()),
+                    None => (0, "unknown".to_string()),
+                };
+
+                Connection {
+                    id: Uuid::new_v4().to_string(),
+                    process_name,
+                    pid,
+                    namespace: Some(namespace.info.clone()),
+                    protocol: "UNIX".to_string(),
+                    local_addr: entry.path,
+                    state: unix_state_name(entry.state, entry.listening).to_string(),
+                    captured_at: timestamp,
+                    unix: Some(UnixSocketInfo {
+                        socket_type: unix_type_name(entry.socket_type).to_string(),
+                        inode: entry.inode,
+                        peer_inode: peers.get(&entry.inode).copied(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    /// Distinct network namespaces from `/proc/*/ns/net`, host first. Each is
+    /// named after its `ip netns` name when it has one, otherwise after the
+    /// lowest-numbered process inside it.
+    fn network_namespaces(&self) -> Vec<Namespace> {
+        let host_id = namespace_id(&self.proc_root.join("self/ns/net"));
+        let named = named_namespaces();
+
+        let mut members: HashMap<u64, u32> = HashMap::new();
+        if let Ok(processes) = std::fs::read_dir(&self.proc_root) {
+            for process in processes.flatten() {
+                let Some(pid) = process.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+                    continue;
+                };
+                if let Some(id) = namespace_id(&process.path().join("ns/net")) {
+                    let lowest = members.entry(id).or_insert(pid);
+                    *lowest = (*lowest).min(pid);
+                }
+            }
+        }
+
+        let mut namespaces: Vec<Namespace> = members
+            .into_iter()
+            .filter(|(id, _)| Some(*id) != host_id)
+            .map(|(id, pid)| Namespace {
+                info: NetworkNamespace {
+                    id,
+                    name: named.get(&id).cloned().unwrap_or_else(|| {
+                        format!("{} ({})", process_name(&self.proc_root.join(pid.to_string())), pid)
+                    }),
+                    is_host: false,
+                },
+                pid,
+            })
+            .collect();
+        namespaces.sort_by_key(|namespace| namespace.info.id);
+
+        // We run in the host namespace, so its tables are read through our own pid
+        namespaces.insert(
+            0,
+            Namespace {
+                info: NetworkNamespace {
+                    id: host_id.unwrap_or(0),
+                    name: "host".to_string(),
+                    is_host: true,
+                },
+                pid: std::process::id(),
+            },
+        );
+        namespaces
+    }
+}
+
+/// The inode in a `net:[4026531840]` namespace link.
+fn namespace_id(link: &Path) -> Option<u64> {
+    let target = std::fs::read_link(link).ok()?;
+    target.to_str()?.strip_prefix("net:[")?.strip_suffix(']')?.parse().ok()
+}
+
+/// Names given with `ip netns add`, keyed by namespace inode.
+fn named_namespaces() -> HashMap<u64, String> {
+    let Ok(entries) = std::fs::read_dir(NETNS_RUN_DIR) else {
+        return HashMap::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            // The bind mount's inode is the namespace's
+            let id = std::fs::metadata(entry.path()).ok()?.ino();
+            Some((id, entry.file_name().to_string_lossy().into_owned()))
+        })
+        .collect()
+}
+
+fn read_table(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+/// `sl local_address rem_address st tx_queue:rx_queue tr:tm->when retrnsmt uid timeout inode`,
+/// shared by the tcp, udp, raw and icmp tables.
+fn parse_socket_table(content: &str) -> Vec<SocketEntry> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 {
+                return None;
+            }
+            Some(SocketEntry {
+                local: parse_socket_address(fields[1])?,
+                remote: parse_socket_address(fields[2])?,
+                state: u8::from_str_radix(fields[3], 16).ok()?,
+                inode: fields[9].parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// `sk RefCnt Type Proto Iface R Rmem User Inode`, the protocol being the
+/// EtherType in hex and the interface an index, 0 for every interface.
+fn parse_packet_table(content: &str) -> Vec<PacketEntry> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 9 {
+                return None;
+            }
+            Some(PacketEntry {
+                ethertype: u16::from_str_radix(fields[3], 16).ok()?,
+                ifindex: fields[4].parse().ok()?,
+                inode: fields[8].parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// `Num RefCount Protocol Flags Type St Inode Path`, where the path is
+/// missing for unnamed sockets and may itself contain spaces.
+fn parse_unix_table(content: &str) -> Vec<UnixEntry> {
+    const SO_ACCEPTCON: u32 = 1 << 16;
+
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut rest = line;
+            let mut fields = Vec::with_capacity(7);
+            for _ in 0..7 {
+                rest = rest.trim_start();
+                let end = rest.find(' ').unwrap_or(rest.len());
+                fields.push(&rest[..end]);
+                rest = &rest[end..];
+            }
+            let flags = u32::from_str_radix(fields[3], 16).ok()?;
+
+            Some(UnixEntry {
+                path: rest.trim().to_string(),
+                socket_type: u16::from_str_radix(fields[4], 16).ok()?,
+                state: u8::from_str_radix(fields[5], 16).ok()?,
+                listening: flags & SO_ACCEPTCON != 0,
+                inode: fields[6].parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// Sets the peer's path and owner on each connected unix socket. A socket
+/// file shared with a container is connected to across namespaces, so this
+/// runs over the whole snapshot.
+fn link_unix_peers(connections: &mut [Connection], owners: &HashMap<u64, (u32, String)>) {
+    let paths: HashMap<u64, String> = connections
+        .iter()
+        .filter_map(|conn| Some((conn.unix.as_ref()?.inode, conn.local_addr.clone())))
+        .collect();
+
+    for conn in connections.iter_mut() {
+        let Some(unix) = conn.unix.as_mut() else {
+            continue;
+        };
+        let Some(peer) = unix.peer_inode else {
+            continue;
+        };
+        if let Some(path) = paths.get(&peer) {
+            conn.remote_addr = path.clone();
+        }
+        if let Some((pid, name)) = owners.get(&peer) {
+            unix.peer_pid = Some(*pid);
+            unix.peer_process = Some(name.clone());
+        }
+    }
+}
+
+/// Parses `0100007F:0050`. The address is printed as 32-bit words in host
+/// byte order, so each word's native bytes are the original network bytes.
+fn parse_socket_address(field: &str) -> Option<(IpAddr, u16)> {
+    let (address, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+
+    let mut octets = Vec::with_capacity(16);
+    for chunk in address.as_bytes().chunks(8) {
+        let word = u32::from_str_radix(std::str::from_utf8(chunk).ok()?, 16).ok()?;
+        octets.extend_from_slice(&word.to_ne_bytes());
+    }
+
+    let ip = match octets.len() {
+        4 => IpAddr::V4(Ipv4Addr::new(octets[0], octets[1], octets[2], octets[3])),
+        16 => {
+            let v6 = Ipv6Addr::from(<[u8; 16]>::try_from(octets.as_slice()).ok()?);
+            // Dual-stack sockets report IPv4 peers as ::ffff:a.b.c.d
+            match v6.to_ipv4_mapped() {
+                Some(v4) => IpAddr::V4(v4),
+                None => IpAddr::V6(v6),
+            }
+        }
+        _ => return None,
+    };
+    Some((ip, port))
+}
+
+const TCP_LISTEN: u8 = 0x0a;
+
+/// `socket_state` from linux/net.h. Connected sockets are reported as
+/// established so they filter like TCP ones.
+fn unix_state_name(state: u8, listening: bool) -> &'static str {
+    match state {
+        _ if listening => "LISTENING",
+        0x01 => "UNCONNECTED",
+        0x02 => "CONNECTING",
+        0x03 => "ESTABLISHED",
+        0x04 => "DISCONNECTING",
+        _ => "UNKNOWN",
+    }
+}
+
+fn ip_protocol_name(protocol: u16) -> String {
+    let name = match protocol {
+        1 => "ICMP",
+        2 => "IGMP",
+        6 => "TCP",
+        17 => "UDP",
+        47 => "GRE",
+        50 => "ESP",
+        58 => "ICMPv6",
+        89 => "OSPF",
+        112 => "VRRP",
+        132 => "SCTP",
+        255 => "any", // IPPROTO_RAW: send-only, the caller builds IP headers
+        _ => return protocol.to_string(),
+    };
+    name.to_string()
+}
+
+fn ethertype_name(ethertype: u16) -> String {
+    let name = match ethertype {
+        0x0000 => "none", // not yet bound to a protocol
+        0x0003 => "all",  // ETH_P_ALL: every frame, the mark of a sniffer
+        0x0800 => "IPv4",
+        0x0806 => "ARP",
+        0x86dd => "IPv6",
+        0x888e => "EAPOL",
+        0x88cc => "LLDP",
+        _ => return format!("0x{:04x}", ethertype),
+    };
+    name.to_string()
+}
+
+fn interface_name(ifindex: u32) -> String {
+    let mut name = [0 as libc::c_char; libc::IF_NAMESIZE];
+    if unsafe { libc::if_indextoname(ifindex, name.as_mut_ptr()) }.is_null() {
+        return ifindex.to_string();
+    }
+    unsafe { std::ffi::CStr::from_ptr(name.as_ptr()) }.to_string_lossy().into_owned()
+}
+
+fn unix_type_name(socket_type: u16) -> &'static str {
+    match socket_type {
+        1 => "stream",
+        2 => "dgram",
+        5 => "seqpacket",
+        _ => "unknown",
+    }
+}
+
+/// Names match what the Windows collector reports.
+fn tcp_state_name(state: u8) -> &'static str {
+    match state {
+        0x01 => "ESTABLISHED",
+        0x02 => "SYN_SENT",
+        0x03 => "SYN_RECEIVED",
+        0x04 => "FIN_WAIT1",
+        0x05 => "FIN_WAIT2",
+        0x06 => "TIME_WAIT",
+        0x07 => "CLOSED",
+        0x08 => "CLOSE_WAIT",
+        0x09 => "LAST_ACK",
+        TCP_LISTEN => "LISTENING",
+        0x0b => "CLOSING",
+        _ => "UNKNOWN",
+    }
+}
+
+/// Maps socket inodes to (pid, process name) by scanning open file
+/// descriptors. Processes we may not inspect are silently skipped.
+fn socket_owners(proc_root: &Path) -> HashMap<u64, (u32, String)> {
+    let mut owners = HashMap::new();
+    let Ok(processes) = std::fs::read_dir(proc_root) else {
+        return owners;
+    };
+
+    for process in processes.flatten() {
+        let Some(pid) = process.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(fds) = std::fs::read_dir(process.path().join("fd")) else {
+            continue;
+        };
+
+        let mut name = None;
+        for fd in fds.flatten() {
+            let Ok(target) = std::fs::read_link(fd.path()) else {
+                continue;
+            };
+            let Some(inode) = target
+                .to_str()
+                .and_then(|target| target.strip_prefix("socket:["))
+                .and_then(|rest| rest.strip_suffix(']'))
+                .and_then(|inode| inode.parse::<u64>().ok())
+            else {
+                continue;
+            };
+
+            let name = name.get_or_insert_with(|| process_name(&process.path()));
+            // Sockets shared across fork belong to whichever process we saw first
+            owners.entry(inode).or_insert_with(|| (pid, name.clone()));
+        }
+    }
+    owners
+}
+
+fn process_name(process_dir: &Path) -> String {
+    std::fs::read_to_string(process_dir.join("comm"))
+        .map(|comm| comm.trim_end().to_string())
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Fixtures come from a little-endian host; the kernel prints in host order
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn parses_ipv4_socket_table() {
+        let table = parse_socket_table(
+            "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n\
+             \x20  0: 0100007F:0277 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 21503 1 0000000000000000 100 0 0 10 0\n\
+             \x20  1: 0A01A8C0:D6F2 22D8B85D:01BB 01 00000000:00000000 02:00000A3F 00000000  1000        0 48812 2 0000000000000000 20 4 30 10 -1\n\
+             \x20  2: garbage\n",
+        );
+
+        assert_eq!(table.len(), 2);
+        assert_eq!(table[0].local, ("127.0.0.1".parse().unwrap(), 631));
+        assert_eq!(table[0].remote, ("0.0.0.0".parse().unwrap(), 0));
+        assert_eq!(table[0].state, TCP_LISTEN);
+        assert_eq!(table[0].inode, 21503);
+        assert_eq!(table[1].local, ("192.168.1.10".parse().unwrap(), 55026));
+        assert_eq!(table[1].remote, ("93.184.216.34".parse().unwrap(), 443));
+        assert_eq!(table[1].state, 0x01);
+    }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn parses_ipv6_words_in_host_order() {
+        let table = parse_socket_table(
+            "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n\
+             \x20  0: 00000000000000000000000001000000:0277 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 21502 1 0000000000000000 100 0 0 10 0\n\
+             \x20  1: B80D0120000000000000000001000000:A2C4 B80D012000000000ADDE00000100EFBE:01BB 01 00000000:00000000 00:00000000 00000000  1000        0 51234 1 0000000000000000 20 4 30 10 -1\n\
+             \x20  2: 0000000000000000FFFF00000A01A8C0:0016 0000000000000000FFFF00006400A8C0:E1F4 01 00000000:00000000 02:0000089F 00000000     0        0 51240 1 0000000000000000 20 4 31 10 -1\n",
+        );
+
+        assert_eq!(table.len(), 3);
+        assert_eq!(table[0].local, ("::1".parse().unwrap(), 631));
+        assert_eq!(table[0].remote, ("::".parse().unwrap(), 0));
+        assert_eq!(table[1].local, ("2001:db8::1".parse().unwrap(), 41668));
+        assert_eq!(table[1].remote, ("2001:db8::dead:beef:1".parse().unwrap(), 443));
+        // Dual-stack sockets show IPv4 peers as ::ffff:a.b.c.d
+        assert_eq!(table[2].local, ("192.168.1.10".parse().unwrap(), 22));
+        assert_eq!(table[2].remote, ("192.168.0.100".parse().unwrap(), 57844));
+    }
+
+    #[test]
+    fn rejects_malformed_socket_addresses() {
+        assert_eq!(parse_socket_address("0100007F"), None);
+        assert_eq!(parse_socket_address("0100007F:XYZ"), None);
+        assert_eq!(parse_socket_address("0100007F00:0050"), None);
+        assert_eq!(parse_socket_address("ZZ00007F:0050"), None);
+    }
+
+    #[test]
+    fn parses_unix_table_paths() {
+        let table = parse_unix_table(
+            "Num       RefCount Protocol Flags    Type St Inode Path\n\
+             0000000000000000: 00000002 00000000 00010000 0001 01 23456 /run/user/1000/bus\n\
+             0000000000000000: 00000002 00000000 00010000 0001 01 34567 /tmp/dir with spaces/app.sock\n\
+             0000000000000000: 00000003 00000000 00000000 0001 03 45678 @/tmp/.X11-unix/X0\n\
+             0000000000000000: 00000003 00000000 00000000 0005 03 45679 @\n\
+             0000000000000000: 00000002 00000000 00000000 0002 01   925\n",
+        );
+
+        assert_eq!(table.len(), 5);
+        assert_eq!(table[0].path, "/run/user/1000/bus");
+        assert!(table[0].listening);
+        assert_eq!(table[0].socket_type, 1);
+        assert_eq!(table[0].state, 1);
+        assert_eq!(table[0].inode, 23456);
+        assert_eq!(table[1].path, "/tmp/dir with spaces/app.sock");
+        assert_eq!(table[1].inode, 34567);
+        assert_eq!(table[2].path, "@/tmp/.X11-unix/X0");
+        assert!(!table[2].listening);
+        assert_eq!(table[2].state, 3);
+        assert_eq!(table[3].path, "@");
+        assert_eq!(table[3].socket_type, 5);
+        assert_eq!(table[4].path, "");
+        assert_eq!(table[4].socket_type, 2);
+        assert_eq!(table[4].inode, 925);
+    }
+
+    #[test]
+    fn parses_packet_table() {
+        let table = parse_packet_table(
+            "sk               RefCnt Type Proto  Iface R Rmem   User   Inode\n\
+             ffff8c1e4a2b5800 3      3      0003   2     1 0      0      78901\n\
+             ffff8c1e4a2b6000 3      2      88cc   0     1 0      0      78902\n\
+             ffff8c1e4a2b6800 3      2\n",
+        );
+
+        assert_eq!(table.len(), 2);
+        assert_eq!(table[0].ethertype, 0x0003);
+        assert_eq!(table[0].ifindex, 2);
+        assert_eq!(table[0].inode, 78901);
+        assert_eq!(table[1].ethertype, 0x88cc);
+        assert_eq!(table[1].ifindex, 0);
     }
-}
\ No newline at end of file
+}
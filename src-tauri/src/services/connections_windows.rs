@@ -1,4 +1,4 @@
-use crate::models::{Connection, NetworkEndpoint, calculate_risk};
+use crate::models::{classify_address, Connection};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::process::Command;
@@ -235,18 +235,6 @@ impl WindowsConnectionCollector {
 
         // Process TCP connections
         for tcp_conn in tcp_connections {
-            let endpoint = NetworkEndpoint {
-                protocol: "TCP".to_string(),
-                local_address: tcp_conn.local_address.clone(),
-                local_port: tcp_conn.local_port,
-                remote_address: tcp_conn.remote_address.clone(),
-                remote_port: tcp_conn.remote_port,
-                state: self.map_tcp_state(&tcp_conn.state),
-                owning_process_id: tcp_conn.owning_process,
-            };
-
-            let (risk, risk_reasons) = calculate_risk(&endpoint);
-            
             let process_name = if let Some(pid) = tcp_conn.owning_process {
                 process_map.get(&pid).cloned().unwrap_or_else(|| "unknown".to_string())
             } else {
@@ -258,31 +246,20 @@ impl WindowsConnectionCollector {
                 process_name,
                 pid: tcp_conn.owning_process.unwrap_or(0),
                 protocol: "TCP".to_string(),
+                remote_class: classify_address(&tcp_conn.remote_address),
                 local_addr: tcp_conn.local_address,
                 local_port: tcp_conn.local_port,
                 remote_addr: tcp_conn.remote_address,
                 remote_port: tcp_conn.remote_port,
                 state: self.map_tcp_state(&tcp_conn.state),
-                risk,
-                risk_reasons,
                 captured_at: timestamp,
+                // Direction, service names and risk need the whole snapshot; see ConnectionCollector
+                ..Default::default()
             });
         }
 
         // Process UDP endpoints
         for udp_endpoint in udp_endpoints {
-            let endpoint = NetworkEndpoint {
-                protocol: "UDP".to_string(),
-                local_address: udp_endpoint.local_address.clone(),
-                local_port: udp_endpoint.local_port,
-                remote_address: udp_endpoint.remote_address.clone(),
-                remote_port: udp_endpoint.remote_port,
-                state: "Established".to_string(), // UDP is connectionless, but we'll mark active ones
-                owning_process_id: udp_endpoint.owning_process,
-            };
-
-            let (risk, risk_reasons) = calculate_risk(&endpoint);
-            
             let process_name = if let Some(pid) = udp_endpoint.owning_process {
                 process_map.get(&pid).cloned().unwrap_or_else(|| "unknown".to_string())
             } else {
@@ -294,14 +271,14 @@ impl WindowsConnectionCollector {
                 process_name,
                 pid: udp_endpoint.owning_process.unwrap_or(0),
                 protocol: "UDP".to_string(),
+                remote_class: classify_address(&udp_endpoint.remote_address),
                 local_addr: udp_endpoint.local_address,
                 local_port: udp_endpoint.local_port,
                 remote_addr: udp_endpoint.remote_address,
                 remote_port: udp_endpoint.remote_port,
                 state: "Active".to_string(),
-                risk,
-                risk_reasons,
                 captured_at: timestamp,
+                ..Default::default()
             });
         }
 
@@ -0,0 +1,331 @@
+use crate::models::{Connection, ContainerInfo, ContainerRuntime};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long an unreachable runtime API is left alone before we ask again.
+const RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Maps socket-owning processes to containers through `/proc/<pid>/cgroup`,
+/// and looks container names and images up in the runtime's local API.
+/// Connections are only ever labelled from the cache; runtime lookups run
+/// in the background and show up on a later poll.
+#[derive(Default)]
+pub struct ContainerResolver {
+    // container ID -> name and image, or when the lookup last failed
+    details: Arc<Mutex<HashMap<String, Result<ContainerDetails, Instant>>>>,
+    pending: Arc<Mutex<HashSet<String>>>,
+}
+
+#[derive(Clone)]
+struct ContainerDetails {
+    name: Option<String>,
+    image: Option<String>,
+}
+
+impl ContainerResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn annotate(&self, connections: &mut [Connection]) {
+        let mut by_pid: HashMap<u32, Option<ContainerInfo>> = HashMap::new();
+
+        for conn in connections.iter_mut().filter(|conn| conn.pid != 0) {
+            conn.container = by_pid
+                .entry(conn.pid)
+                .or_insert_with(|| self.lookup(conn.pid))
+                .clone();
+        }
+
+        // Forget containers that no longer own sockets
+        let seen: HashSet<&str> = by_pid.values().flatten().map(|container| container.id.as_str()).collect();
+        self.details.lock().unwrap().retain(|id, _| seen.contains(id.as_str()));
+    }
+
+    pub fn lookup(&self, pid: u32) -> Option<ContainerInfo> {
+        let cgroup = std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+        let mut container = parse_cgroup(&cgroup)?;
+
+        let details = self.details(&container);
+        container.name = details.as_ref().and_then(|d| d.name.clone());
+        container.image = details.and_then(|d| d.image);
+        Some(container)
+    }
+
+    /// Cached details, queueing a runtime lookup on a miss or once a failed
+    /// one is due for a retry. Never waits on the runtime.
+    fn details(&self, container: &ContainerInfo) -> Option<ContainerDetails> {
+        match self.details.lock().unwrap().get(&container.id) {
+            Some(Ok(details)) => return Some(details.clone()),
+            Some(Err(failed_at)) if failed_at.elapsed() < RETRY_AFTER => return None,
+            _ => {}
+        }
+        self.schedule(container.clone());
+        None
+    }
+
+    fn schedule(&self, container: ContainerInfo) {
+        if !self.pending.lock().unwrap().insert(container.id.clone()) {
+            return;
+        }
+
+        let details = self.details.clone();
+        let pending = self.pending.clone();
+        // The runtime APIs are plain blocking sockets with short timeouts
+        tauri::async_runtime::spawn_blocking(move || {
+            let result = api::inspect(&container).map_err(|e| {
+                tracing::debug!("No details for container {}: {}", short_id(&container.id), e);
+                Instant::now()
+            });
+            details.lock().unwrap().insert(container.id.clone(), result);
+            pending.lock().unwrap().remove(&container.id);
+        });
+    }
+}
+
+/// Finds a container ID in any of the process's cgroup paths. Handles the
+/// cgroupfs layouts (`/docker/<id>`, `/kubepods/burstable/pod<uid>/<id>`)
+/// and the systemd ones (`docker-<id>.scope`, `cri-containerd-<id>.scope`,
+/// `crio-<id>.scope`, `libpod-<id>.scope`).
+fn parse_cgroup(content: &str) -> Option<ContainerInfo> {
+    content.lines().find_map(|line| {
+        // hierarchy-ID:controller-list:cgroup-path
+        let path = line.splitn(3, ':').nth(2)?;
+        parse_cgroup_path(path)
+    })
+}
+
+fn parse_cgroup_path(path: &str) -> Option<ContainerInfo> {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    let (index, id, mut runtime) = segments.iter().enumerate().rev().find_map(|(index, segment)| {
+        if let Some(scope) = segment.strip_suffix(".scope") {
+            for (prefix, runtime) in [
+                ("docker-", ContainerRuntime::Docker),
+                ("cri-containerd-", ContainerRuntime::Containerd),
+                ("crio-", ContainerRuntime::Crio),
+                ("libpod-", ContainerRuntime::Podman),
+            ] {
+                // conmon scopes hold the runtime's monitor process, not the container
+                if let Some(id) = scope.strip_prefix(prefix).filter(|id| is_container_id(id)) {
+                    return Some((index, id.to_string(), runtime));
+                }
+            }
+            None
+        } else {
+            is_container_id(segment).then(|| (index, segment.to_string(), ContainerRuntime::Unknown))
+        }
+    })?;
+
+    // cgroupfs layouts name the runtime in a parent directory
+    if runtime == ContainerRuntime::Unknown {
+        runtime = segments[..index]
+            .iter()
+            .find_map(|segment| match *segment {
+                "docker" => Some(ContainerRuntime::Docker),
+                "libpod_parent" | "machine.slice" => Some(ContainerRuntime::Podman),
+                segment if segment.contains("crio") => Some(ContainerRuntime::Crio),
+                segment if segment.contains("containerd") => Some(ContainerRuntime::Containerd),
+                _ => None,
+            })
+            .unwrap_or(ContainerRuntime::Unknown);
+    }
+
+    // `pod<uid>` (cgroupfs) or `kubepods-<qos>-pod<uid_with_underscores>.slice` (systemd)
+    let pod_uid = segments[..index].iter().find_map(|segment| {
+        let segment = segment.strip_suffix(".slice").unwrap_or(segment);
+        let (_, uid) = segment.rsplit_once("pod")?;
+        (uid.len() == 36).then(|| uid.replace('_', "-"))
+    });
+
+    Some(ContainerInfo {
+        id,
+        runtime,
+        pod_uid,
+        ..Default::default()
+    })
+}
+
+/// Every runtime we know of uses 64 hex digit IDs.
+fn is_container_id(value: &str) -> bool {
+    value.len() == 64 && value.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn short_id(id: &str) -> &str {
+    id.get(..12).unwrap_or(id)
+}
+
+#[cfg(unix)]
+mod api {
+    use super::{ContainerDetails, ContainerInfo, ContainerRuntime};
+    use serde_json::Value;
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+    use std::path::Path;
+    use std::time::Duration;
+
+    const SOCKET_TIMEOUT: Duration = Duration::from_millis(500);
+
+    const DOCKER_SOCKETS: &[&str] = &["/var/run/docker.sock", "/run/docker.sock"];
+    const PODMAN_SOCKETS: &[&str] = &["/run/podman/podman.sock"];
+    const CRIO_SOCKETS: &[&str] = &["/var/run/crio/crio.sock"];
+
+    /// Asks the container's runtime for its name and image. Docker and
+    /// Podman share the Docker Engine API; CRI-O has its own inspect
+    /// endpoint. containerd only speaks gRPC, so its containers keep their
+    /// bare IDs.
+    pub fn inspect(container: &ContainerInfo) -> Result<ContainerDetails, String> {
+        match container.runtime {
+            ContainerRuntime::Docker => engine_inspect(DOCKER_SOCKETS, &container.id),
+            ContainerRuntime::Podman => engine_inspect(PODMAN_SOCKETS, &container.id),
+            ContainerRuntime::Crio => crio_inspect(&container.id),
+            ContainerRuntime::Containerd => Err("containerd has no HTTP API".to_string()),
+            ContainerRuntime::Unknown => {
+                engine_inspect(DOCKER_SOCKETS, &container.id).or_else(|_| crio_inspect(&container.id))
+            }
+        }
+    }
+
+    fn engine_inspect(sockets: &[&str], id: &str) -> Result<ContainerDetails, String> {
+        let json = get_json(sockets, &format!("/containers/{}/json", id))?;
+        Ok(ContainerDetails {
+            name: json["Name"].as_str().map(|name| name.trim_start_matches('/').to_string()),
+            image: json["Config"]["Image"].as_str().map(str::to_string),
+        })
+    }
+
+    fn crio_inspect(id: &str) -> Result<ContainerDetails, String> {
+        let json = get_json(CRIO_SOCKETS, &format!("/containers/{}", id))?;
+        let labels = &json["labels"];
+        // Kubernetes labels name the container and its pod more usefully than the runtime name
+        let name = match (
+            labels["io.kubernetes.pod.name"].as_str(),
+            labels["io.kubernetes.container.name"].as_str(),
+        ) {
+            (Some(pod), Some(container)) => Some(format!("{}/{}", pod, container)),
+            _ => json["name"].as_str().map(str::to_string),
+        };
+        Ok(ContainerDetails {
+            name,
+            image: json["image"].as_str().map(str::to_string),
+        })
+    }
+
+    /// A plain HTTP/1.0 GET over the first runtime socket that exists.
+    fn get_json(sockets: &[&str], path: &str) -> Result<Value, String> {
+        let socket = sockets
+            .iter()
+            .map(Path::new)
+            .find(|socket| socket.exists())
+            .ok_or_else(|| format!("No runtime socket at {}", sockets.join(" or ")))?;
+
+        let mut stream =
+            UnixStream::connect(socket).map_err(|e| format!("Failed to connect to {}: {}", socket.display(), e))?;
+        stream
+            .set_read_timeout(Some(SOCKET_TIMEOUT))
+            .and_then(|_| stream.set_write_timeout(Some(SOCKET_TIMEOUT)))
+            .map_err(|e| format!("Socket error: {}", e))?;
+
+        write!(stream, "GET {} HTTP/1.0\r\nHost: localhost\r\n\r\n", path)
+            .map_err(|e| format!("Failed to send request: {}", e))?;
+        let mut response = Vec::new();
+        stream
+            .read_to_end(&mut response)
+            .map_err(|e| format!("Failed to read response: {}", e))?;
+
+        let split = response
+            .windows(4)
+            .position(|window| window == b"\r\n\r\n")
+            .ok_or("Malformed HTTP response")?;
+        let status = String::from_utf8_lossy(&response[..split]);
+        let status = status.lines().next().unwrap_or_default();
+        if status.split_whitespace().nth(1) != Some("200") {
+            return Err(format!("Runtime answered {}", status));
+        }
+        serde_json::from_slice(&response[split + 4..]).map_err(|e| format!("Invalid JSON from runtime: {}", e))
+    }
+}
+
+#[cfg(not(unix))]
+mod api {
+    use super::{ContainerDetails, ContainerInfo};
+
+    pub fn inspect(_container: &ContainerInfo) -> Result<ContainerDetails, String> {
+        Err("Container runtimes are only queried on Linux".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "3f4e5d6c7b8a99887766554433221100ffeeddccbbaa00112233445566778899";
+    const POD_UID: &str = "1a2b3c4d-1111-2222-3333-444455556666";
+
+    fn parse(path: &str) -> (String, ContainerRuntime, Option<String>) {
+        let container = parse_cgroup_path(path).expect("container cgroup");
+        (container.id, container.runtime, container.pod_uid)
+    }
+
+    #[test]
+    fn docker_cgroupfs() {
+        assert_eq!(parse(&format!("/docker/{}", ID)), (ID.to_string(), ContainerRuntime::Docker, None));
+        // cgroup v1 files carry the hierarchy ID and controllers in front
+        let container = parse_cgroup(&format!("12:pids:/docker/{}\n0::/\n", ID)).expect("container cgroup");
+        assert_eq!(container.id, ID);
+    }
+
+    #[test]
+    fn kubepods_cgroupfs_pod_uid() {
+        assert_eq!(
+            parse(&format!("/kubepods/besteffort/pod{}/{}", POD_UID, ID)),
+            (ID.to_string(), ContainerRuntime::Unknown, Some(POD_UID.to_string()))
+        );
+    }
+
+    #[test]
+    fn docker_systemd_scope() {
+        assert_eq!(
+            parse(&format!("/system.slice/docker-{}.scope", ID)),
+            (ID.to_string(), ContainerRuntime::Docker, None)
+        );
+    }
+
+    #[test]
+    fn containerd_systemd_scope_in_pod() {
+        let pod_slice = POD_UID.replace('-', "_");
+        assert_eq!(
+            parse(&format!(
+                "/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod{}.slice/cri-containerd-{}.scope",
+                pod_slice, ID
+            )),
+            (ID.to_string(), ContainerRuntime::Containerd, Some(POD_UID.to_string()))
+        );
+    }
+
+    #[test]
+    fn crio_systemd_scope_skips_conmon() {
+        let pod_slice = POD_UID.replace('-', "_");
+        assert_eq!(
+            parse(&format!("/kubepods.slice/kubepods-pod{}.slice/crio-{}.scope", pod_slice, ID)),
+            (ID.to_string(), ContainerRuntime::Crio, Some(POD_UID.to_string()))
+        );
+        assert!(parse_cgroup_path(&format!("/kubepods.slice/kubepods-pod{}.slice/crio-conmon-{}.scope", pod_slice, ID)).is_none());
+    }
+
+    #[test]
+    fn podman_libpod_scope() {
+        assert_eq!(
+            parse(&format!("/user.slice/user-1000.slice/user@1000.service/user.slice/libpod-{}.scope/container", ID)),
+            (ID.to_string(), ContainerRuntime::Podman, None)
+        );
+        assert!(parse_cgroup_path(&format!("/machine.slice/libpod-conmon-{}.scope", ID)).is_none());
+    }
+
+    #[test]
+    fn plain_sessions_are_not_containers() {
+        assert!(parse_cgroup_path("/user.slice/user-1000.slice/session-2.scope").is_none());
+        assert!(parse_cgroup_path("/").is_none());
+    }
+}
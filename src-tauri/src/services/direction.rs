@@ -0,0 +1,123 @@
+use crate::models::{parse_ip_address, AddressClass, Connection, ConnectionDirection};
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+/// Fills in `direction` for every connection of a snapshot.
+///
+/// A connection is inbound when a socket of the same protocol is listening on
+/// its local address and port in the same network namespace; otherwise the
+/// ephemeral port range decides.
+pub fn infer_directions(connections: &mut [Connection]) {
+    let ephemeral = ephemeral_port_range();
+
+    let mut listeners: HashSet<ListenerKey> = HashSet::new();
+    for conn in connections.iter().filter(|conn| !conn.is_unix_socket()) {
+        if is_listener(conn) {
+            listeners.insert((
+                namespace_id(conn),
+                conn.protocol.clone(),
+                listener_address(&conn.local_addr),
+                conn.local_port,
+            ));
+        }
+    }
+
+    for conn in connections.iter_mut() {
+        conn.direction = if is_listener(conn) {
+            ConnectionDirection::Listening
+        } else if conn.is_unix_socket() {
+            unix_direction(conn)
+        } else if accepted_by_listener(conn, &listeners) {
+            ConnectionDirection::Inbound
+        } else {
+            guess_from_ports(conn.local_port, conn.remote_port, ephemeral)
+        };
+    }
+}
+
+/// Accepted unix sockets carry their listener's path, while clients are
+/// normally unnamed and only know the path through their peer.
+fn unix_direction(conn: &Connection) -> ConnectionDirection {
+    if !conn.state.eq_ignore_ascii_case("established") {
+        ConnectionDirection::Unknown
+    } else if !conn.local_addr.is_empty() {
+        ConnectionDirection::Inbound
+    } else if !conn.remote_addr.is_empty() {
+        ConnectionDirection::Outbound
+    } else {
+        ConnectionDirection::Unknown
+    }
+}
+
+/// Namespace, protocol, bound address and port.
+type ListenerKey = (u64, String, Option<IpAddr>, u16);
+
+fn namespace_id(conn: &Connection) -> u64 {
+    conn.namespace.as_ref().map_or(0, |ns| ns.id)
+}
+
+fn is_listener(conn: &Connection) -> bool {
+    let state = conn.state.to_ascii_uppercase();
+    state == "LISTEN"
+        || state == "LISTENING"
+        // A UDP socket without a peer is simply bound and waiting for datagrams
+        || (conn.protocol == "UDP" && conn.remote_class == AddressClass::Unspecified)
+}
+
+/// `None` stands for a wildcard bind (0.0.0.0 or ::) that accepts on every address.
+fn listener_address(local_addr: &str) -> Option<IpAddr> {
+    parse_ip_address(local_addr).filter(|ip| !ip.is_unspecified())
+}
+
+fn accepted_by_listener(conn: &Connection, listeners: &HashSet<ListenerKey>) -> bool {
+    let namespace = namespace_id(conn);
+    let exact = parse_ip_address(&conn.local_addr);
+    listeners.contains(&(namespace, conn.protocol.clone(), exact, conn.local_port))
+        || listeners.contains(&(namespace, conn.protocol.clone(), None, conn.local_port))
+}
+
+fn guess_from_ports(local_port: u16, remote_port: u16, ephemeral: (u16, u16)) -> ConnectionDirection {
+    let is_ephemeral = |port: u16| port >= ephemeral.0 && port <= ephemeral.1;
+
+    match (is_ephemeral(local_port), is_ephemeral(remote_port)) {
+        (true, false) => ConnectionDirection::Outbound,
+        (false, true) => ConnectionDirection::Inbound,
+        // Both or neither ephemeral: a well-known port is the service side
+        _ if remote_port != 0 && remote_port < 1024 && local_port >= 1024 => ConnectionDirection::Outbound,
+        _ if local_port != 0 && local_port < 1024 && remote_port >= 1024 => ConnectionDirection::Inbound,
+        _ => ConnectionDirection::Unknown,
+    }
+}
+
+/// Listening sockets that offer a service: TCP listeners, and UDP sockets
+/// bound outside the ephemeral range. Resolvers and QUIC clients open
+/// unconnected UDP sockets on ephemeral ports constantly.
+pub fn is_service_listener(conn: &Connection, ephemeral: (u16, u16)) -> bool {
+    conn.direction == ConnectionDirection::Listening
+        && match conn.protocol.as_str() {
+            "TCP" => true,
+            "UDP" => conn.local_port < ephemeral.0 || conn.local_port > ephemeral.1,
+            _ => false,
+        }
+}
+
+pub fn ephemeral_port_range() -> (u16, u16) {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "linux")] {
+            // Kernel default is 32768-60999 but it is commonly tuned
+            std::fs::read_to_string("/proc/sys/net/ipv4/ip_local_port_range")
+                .ok()
+                .and_then(|content| {
+                    let mut parts = content.split_whitespace().map(|p| p.parse::<u16>());
+                    match (parts.next(), parts.next()) {
+                        (Some(Ok(low)), Some(Ok(high))) => Some((low, high)),
+                        _ => None,
+                    }
+                })
+                .unwrap_or((32768, 60999))
+        } else {
+            // IANA dynamic range, used by Windows and macOS
+            (49152, 65535)
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+pub const TYPE_A: u16 = 1;
+pub const TYPE_PTR: u16 = 12;
+pub const TYPE_AAAA: u16 = 28;
+const CLASS_IN: u16 = 1;
+
+pub const RCODE_NO_ERROR: u8 = 0;
+pub const RCODE_NX_DOMAIN: u8 = 3;
+
+/// The parts of a DNS message NetWatch cares about.
+#[derive(Debug, Clone)]
+pub struct DnsMessage {
+    pub id: u16,
+    pub is_response: bool,
+    pub rcode: u8,
+    pub questions: Vec<DnsQuestion>,
+    pub answers: Vec<DnsRecord>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DnsQuestion {
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct DnsRecord {
+    pub ttl: u32,
+    pub data: RecordData,
+}
+
+#[derive(Debug, Clone)]
+pub enum RecordData {
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+    Ptr(String),
+    Other,
+}
+
+/// `4.3.2.1.in-addr.arpa` / nibble-reversed `ip6.arpa` name for a PTR query.
+pub fn reverse_lookup_name(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, c, d] = v4.octets();
+            format!("{}.{}.{}.{}.in-addr.arpa", d, c, b, a)
+        }
+        IpAddr::V6(v6) => {
+            let mut name = String::with_capacity(72);
+            for byte in v6.octets().iter().rev() {
+                name.push_str(&format!("{:x}.{:x}.", byte & 0x0f, byte >> 4));
+            }
+            name.push_str("ip6.arpa");
+            name
+        }
+    }
+}
+
+/// Builds a recursive PTR query for `ip`.
+pub fn build_ptr_query(id: u16, ip: IpAddr) -> Vec<u8> {
+    let mut message = Vec::with_capacity(96);
+    message.extend_from_slice(&id.to_be_bytes());
+    message.extend_from_slice(&0x0100u16.to_be_bytes()); // standard query, recursion desired
+    message.extend_from_slice(&1u16.to_be_bytes()); // QDCOUNT
+    message.extend_from_slice(&[0, 0, 0, 0, 0, 0]); // ANCOUNT, NSCOUNT, ARCOUNT
+
+    for label in reverse_lookup_name(ip).split('.') {
+        message.push(label.len() as u8);
+        message.extend_from_slice(label.as_bytes());
+    }
+    message.push(0);
+    message.extend_from_slice(&TYPE_PTR.to_be_bytes());
+    message.extend_from_slice(&CLASS_IN.to_be_bytes());
+    message
+}
+
+pub fn parse_message(data: &[u8]) -> Result<DnsMessage, String> {
+    if data.len() < 12 {
+        return Err("DNS message shorter than its header".to_string());
+    }
+
+    let id = read_u16(data, 0)?;
+    let flags = read_u16(data, 2)?;
+    let question_count = read_u16(data, 4)?;
+    let answer_count = read_u16(data, 6)?;
+
+    let mut offset = 12;
+    let mut questions = Vec::new();
+    for _ in 0..question_count {
+        let (name, next) = read_name(data, offset)?;
+        read_u32(data, next)?; // QTYPE and QCLASS
+        questions.push(DnsQuestion { name });
+        offset = next + 4;
+    }
+
+    let mut answers = Vec::new();
+    for _ in 0..answer_count {
+        let (_, next) = read_name(data, offset)?;
+        let record_type = read_u16(data, next)?;
+        let ttl = read_u32(data, next + 4)?;
+        let rdata_len = read_u16(data, next + 8)? as usize;
+        let rdata_start = next + 10;
+        let rdata = data
+            .get(rdata_start..rdata_start + rdata_len)
+            .ok_or("DNS record data runs past the end of the message")?;
+
+        let record_data = match record_type {
+            TYPE_A if rdata.len() == 4 => RecordData::A(Ipv4Addr::new(rdata[0], rdata[1], rdata[2], rdata[3])),
+            TYPE_AAAA if rdata.len() == 16 => {
+                let mut octets = [0u8; 16];
+                octets.copy_from_slice(rdata);
+                RecordData::Aaaa(Ipv6Addr::from(octets))
+            }
+            TYPE_PTR => RecordData::Ptr(read_name(data, rdata_start)?.0),
+            _ => RecordData::Other,
+        };
+
+        answers.push(DnsRecord {
+            ttl,
+            data: record_data,
+        });
+        offset = rdata_start + rdata_len;
+    }
+
+    Ok(DnsMessage {
+        id,
+        is_response: flags & 0x8000 != 0,
+        rcode: (flags & 0x000f) as u8,
+        questions,
+        answers,
+    })
+}
+
+/// Reads a possibly compressed domain name, returning it (without the
+/// trailing dot) and the offset just past it in the original position.
+fn read_name(data: &[u8], start: usize) -> Result<(String, usize), String> {
+    let mut labels: Vec<String> = Vec::new();
+    let mut offset = start;
+    let mut end_of_name = None;
+    let mut jumps = 0;
+
+    loop {
+        let len = *data.get(offset).ok_or("DNS name runs past the end of the message")? as usize;
+
+        if len & 0xc0 == 0xc0 {
+            // Compression pointer; bound the number of jumps to reject loops
+            jumps += 1;
+            if jumps > 32 {
+                return Err("DNS name compression loop".to_string());
+            }
+            let pointer = (read_u16(data, offset)? & 0x3fff) as usize;
+            end_of_name.get_or_insert(offset + 2);
+            offset = pointer;
+        } else if len == 0 {
+            end_of_name.get_or_insert(offset + 1);
+            break;
+        } else {
+            let label = data
+                .get(offset + 1..offset + 1 + len)
+                .ok_or("DNS label runs past the end of the message")?;
+            labels.push(String::from_utf8_lossy(label).to_lowercase());
+            offset += 1 + len;
+        }
+    }
+
+    Ok((labels.join("."), end_of_name.unwrap_or(offset)))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, String> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+        .ok_or_else(|| "Truncated DNS message".to_string())
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, String> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| "Truncated DNS message".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(id: u16, flags: u16, questions: u16, answers: u16) -> Vec<u8> {
+        let mut message = Vec::new();
+        for field in [id, flags, questions, answers, 0, 0] {
+            message.extend_from_slice(&field.to_be_bytes());
+        }
+        message
+    }
+
+    fn push_name(message: &mut Vec<u8>, name: &str) {
+        for label in name.split('.') {
+            message.push(label.len() as u8);
+            message.extend_from_slice(label.as_bytes());
+        }
+        message.push(0);
+    }
+
+    fn push_record(message: &mut Vec<u8>, name: &[u8], record_type: u16, ttl: u32, rdata: &[u8]) {
+        message.extend_from_slice(name);
+        message.extend_from_slice(&record_type.to_be_bytes());
+        message.extend_from_slice(&CLASS_IN.to_be_bytes());
+        message.extend_from_slice(&ttl.to_be_bytes());
+        message.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        message.extend_from_slice(rdata);
+    }
+
+    #[test]
+    fn builds_ptr_queries_that_parse_back() {
+        let query = build_ptr_query(0x1234, "192.0.2.10".parse().unwrap());
+        let message = parse_message(&query).unwrap();
+        assert_eq!(message.id, 0x1234);
+        assert!(!message.is_response);
+        assert_eq!(message.questions[0].name, "10.2.0.192.in-addr.arpa");
+        assert_eq!(&query[query.len() - 4..], &[0, 12, 0, 1]);
+
+        let query = build_ptr_query(7, "2001:db8::1".parse().unwrap());
+        let name = &parse_message(&query).unwrap().questions[0].name;
+        assert!(name.starts_with("1.0.0.0.0.0.0.0."));
+        assert!(name.ends_with(".8.b.d.0.1.0.0.2.ip6.arpa"));
+        assert_eq!(name.split('.').count(), 34);
+    }
+
+    #[test]
+    fn follows_compression_pointers() {
+        let mut message = header(42, 0x8180, 1, 3);
+        push_name(&mut message, "10.2.0.192.in-addr.arpa");
+        message.extend_from_slice(&[0, 12, 0, 1]);
+
+        // PTR target "host.example.net", then an A record whose owner name
+        // is "www" followed by a pointer into the PTR target's "example.net"
+        let mut target = Vec::new();
+        push_name(&mut target, "Host.Example.net");
+        let target_offset = message.len() + 12;
+        push_record(&mut message, &[0xc0, 12], TYPE_PTR, 600, &target);
+
+        let mut owner = vec![3, b'w', b'w', b'w'];
+        owner.extend_from_slice(&(0xc000 | (target_offset as u16 + 5)).to_be_bytes());
+        let owner_offset = message.len();
+        push_record(&mut message, &owner, TYPE_A, 60, &[198, 51, 100, 7]);
+        push_record(&mut message, &[0xc0, 12], TYPE_AAAA, 60, &[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+        assert_eq!(read_name(&message, owner_offset).unwrap(), ("www.example.net".to_string(), owner_offset + 6));
+
+        let message = parse_message(&message).unwrap();
+        assert!(message.is_response);
+        assert_eq!(message.rcode, RCODE_NO_ERROR);
+        assert_eq!(message.answers.len(), 3);
+        assert!(matches!(&message.answers[0].data, RecordData::Ptr(name) if name == "host.example.net"));
+        assert_eq!(message.answers[0].ttl, 600);
+        assert!(matches!(message.answers[1].data, RecordData::A(ip) if ip == Ipv4Addr::new(198, 51, 100, 7)));
+        assert!(matches!(message.answers[2].data, RecordData::Aaaa(ip) if ip == "2001:db8::1".parse::<Ipv6Addr>().unwrap()));
+    }
+
+    #[test]
+    fn reports_nxdomain() {
+        let mut message = header(9, 0x8183, 1, 0);
+        push_name(&mut message, "20.2.0.192.in-addr.arpa");
+        message.extend_from_slice(&[0, 12, 0, 1]);
+
+        let message = parse_message(&message).unwrap();
+        assert_eq!(message.id, 9);
+        assert_eq!(message.rcode, RCODE_NX_DOMAIN);
+        assert!(message.answers.is_empty());
+    }
+
+    #[test]
+    fn rejects_truncated_messages() {
+        let mut message = header(42, 0x8180, 1, 1);
+        push_name(&mut message, "10.2.0.192.in-addr.arpa");
+        message.extend_from_slice(&[0, 12, 0, 1]);
+        let mut target = Vec::new();
+        push_name(&mut target, "host.example.net");
+        push_record(&mut message, &[0xc0, 12], TYPE_PTR, 600, &target);
+        assert!(parse_message(&message).is_ok());
+
+        for len in 0..message.len() {
+            assert!(parse_message(&message[..len]).is_err(), "accepted a message cut at {} bytes", len);
+        }
+
+        let query = build_ptr_query(1, "192.0.2.10".parse().unwrap());
+        assert!(parse_message(&query[..query.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_names_and_records() {
+        // Pointer to itself
+        let mut looping = header(1, 0x8180, 1, 0);
+        looping.extend_from_slice(&[0xc0, 12, 0, 12, 0, 1]);
+        assert!(parse_message(&looping).is_err());
+
+        // Pointer past the end of the message
+        let mut dangling = header(1, 0x8180, 1, 0);
+        dangling.extend_from_slice(&[0xc0, 200, 0, 12, 0, 1]);
+        assert!(parse_message(&dangling).is_err());
+
+        // Label longer than what follows it
+        let mut long_label = header(1, 0x8180, 1, 0);
+        long_label.extend_from_slice(&[40, b'a', b'b', 0]);
+        assert!(parse_message(&long_label).is_err());
+
+        // RDLENGTH claims more data than the message holds
+        let mut long_rdata = header(1, 0x8180, 0, 1);
+        long_rdata.extend_from_slice(&[0, 0, 1, 0, 1, 0, 0, 0, 60, 0, 16, 1, 2, 3, 4]);
+        assert!(parse_message(&long_rdata).is_err());
+
+        // A record with the wrong length is kept but not decoded
+        let mut short_a = header(1, 0x8180, 0, 1);
+        push_record(&mut short_a, &[0], TYPE_A, 60, &[1, 2, 3]);
+        assert!(matches!(parse_message(&short_a).unwrap().answers[0].data, RecordData::Other));
+    }
+}
@@ -0,0 +1,61 @@
+use crate::models::{
+    connection_exposure, exposure_risk, sensitive_service, Connection, ConnectionDirection, ExposureEntry,
+    ExposureReport, ProcessExposure,
+};
+use std::collections::BTreeMap;
+
+/// Groups every listening TCP socket and bound UDP socket of a snapshot by
+/// owning process, riskiest processes first. Binds inside containers and
+/// other network namespaces are reported as namespace-only rather than as
+/// exposed on the host's interfaces.
+pub fn build_exposure_report(connections: &[Connection]) -> ExposureReport {
+    let mut by_process: BTreeMap<(u32, String), Vec<ExposureEntry>> = BTreeMap::new();
+
+    for conn in connections
+        .iter()
+        .filter(|c| c.direction == ConnectionDirection::Listening && !c.is_unix_socket())
+    {
+        let entries = by_process.entry((conn.pid, conn.process_name.clone())).or_default();
+
+        // The same bind can be reported once per address family or collector source
+        let duplicate = entries.iter().any(|e| {
+            e.protocol == conn.protocol && e.local_addr == conn.local_addr && e.local_port == conn.local_port
+        });
+        if duplicate {
+            continue;
+        }
+
+        let exposure = connection_exposure(conn);
+        let (risk, risk_reasons) = exposure_risk(exposure, conn.local_port);
+        entries.push(ExposureEntry {
+            protocol: conn.protocol.clone(),
+            local_addr: conn.local_addr.clone(),
+            local_port: conn.local_port,
+            exposure,
+            service: sensitive_service(conn.local_port).map(|s| s.to_string()),
+            risk,
+            risk_reasons,
+        });
+    }
+
+    let mut processes: Vec<ProcessExposure> = by_process
+        .into_iter()
+        .map(|((pid, process_name), mut entries)| {
+            entries.sort_by(|a, b| b.risk.cmp(&a.risk).then(a.local_port.cmp(&b.local_port)));
+            ProcessExposure { pid, process_name, entries }
+        })
+        .collect();
+
+    processes.sort_by(|a, b| highest_risk(b).cmp(&highest_risk(a)).then(a.process_name.cmp(&b.process_name)));
+
+    let generated_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+
+    ExposureReport { generated_at, processes }
+}
+
+fn highest_risk(process: &ProcessExposure) -> Option<crate::models::RiskLevel> {
+    process.entries.iter().map(|e| e.risk.clone()).max()
+}
@@ -0,0 +1,540 @@
+use crate::models::{
+    ConnectEvent, Connection, ConnectionDirection, ConnectionInterval, HistoryFilter, HistorySettings, HistorySnapshot,
+    RiskLevel,
+};
+use rusqlite::types::ToSql;
+use rusqlite::{params, OptionalExtension};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+const PRUNE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const RETRY_OPEN_AFTER: Duration = Duration::from_secs(60);
+const DEFAULT_QUERY_LIMIT: usize = 5000;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS polls (
+        at INTEGER PRIMARY KEY,
+        connections INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS intervals (
+        id INTEGER PRIMARY KEY,
+        process_name TEXT NOT NULL,
+        pid INTEGER NOT NULL,
+        namespace TEXT,
+        container TEXT,
+        systemd_unit TEXT,
+        protocol TEXT NOT NULL,
+        local_addr TEXT NOT NULL,
+        local_port INTEGER NOT NULL,
+        remote_addr TEXT NOT NULL,
+        remote_port INTEGER NOT NULL,
+        hostname TEXT,
+        direction TEXT NOT NULL,
+        state TEXT NOT NULL,
+        risk INTEGER NOT NULL,
+        risk_reasons TEXT NOT NULL,
+        first_seen INTEGER NOT NULL,
+        last_seen INTEGER,
+        opened_at INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS intervals_first_seen ON intervals (first_seen);
+    CREATE INDEX IF NOT EXISTS intervals_last_seen ON intervals (last_seen);
+";
+
+const INTERVAL_COLUMNS: &str = "id, process_name, pid, namespace, container, systemd_unit, protocol, local_addr,
+    local_port, remote_addr, remote_port, hostname, direction, state, risk, risk_reasons, first_seen, last_seen";
+
+/// Persists live snapshots to SQLite as connection intervals. Only changes
+/// are written: a row when a socket appears or its state or risk changes,
+/// and an end time on the previous row once a poll no longer sees it as it
+/// was. Each poll adds one row to `polls`, so the connections at any polled
+/// moment are exactly the intervals spanning it.
+pub struct HistoryStore {
+    path: PathBuf,
+    settings: RwLock<HistorySettings>,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    db: Option<HistoryDb>,
+    open_failed_at: Option<Instant>,
+}
+
+struct HistoryDb {
+    conn: rusqlite::Connection,
+    // socket key -> its open interval
+    open: HashMap<String, OpenInterval>,
+    last_poll_at: Option<u64>,
+    last_pruned: Option<Instant>,
+}
+
+struct OpenInterval {
+    id: i64,
+    tracked: Tracked,
+    hostname: Option<String>,
+    opened_at: u64, // when the socket appeared, which earlier intervals may cover
+}
+
+/// The columns that stay constant over an interval.
+#[derive(PartialEq)]
+struct Tracked {
+    state: String,
+    risk: i64,
+    risk_reasons: String,
+}
+
+impl HistoryStore {
+    pub fn new(path: PathBuf, settings: HistorySettings) -> Self {
+        Self {
+            path,
+            settings: RwLock::new(settings),
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    pub fn configure(&self, settings: HistorySettings) {
+        let mut current = self.settings.write().unwrap();
+        if current.enabled && !settings.enabled {
+            // Whatever is open now would otherwise look open until history is turned back on
+            if let Some(db) = self.inner.lock().unwrap().db.as_mut() {
+                if let Err(e) = db.close_all() {
+                    tracing::warn!("Failed to close open history intervals: {}", e);
+                }
+            }
+        }
+        *current = settings;
+    }
+
+    /// Records a live snapshot. Failures are logged; history is never worth
+    /// failing a poll over.
+    pub fn record(&self, connections: &[Connection]) {
+        let settings = self.settings.read().unwrap().clone();
+        if !settings.enabled {
+            return;
+        }
+        let at = connections.first().map(|conn| conn.captured_at).unwrap_or_else(now_millis);
+
+        let mut inner = self.inner.lock().unwrap();
+        let Some(db) = self.database(&mut inner) else {
+            return;
+        };
+        if let Err(e) = db.record(connections, at) {
+            tracing::warn!("Failed to record connection history: {}", e);
+        }
+        if db.last_pruned.is_none_or(|pruned| pruned.elapsed() >= PRUNE_INTERVAL) {
+            db.last_pruned = Some(Instant::now());
+            if let Err(e) = db.prune(&settings, at) {
+                tracing::warn!("Failed to prune connection history: {}", e);
+            }
+        }
+    }
+
+    /// Intervals overlapping `from..=to` (Unix milliseconds) that match the
+    /// filter, most recent first.
+    pub fn query(&self, filter: &HistoryFilter, from: u64, to: u64) -> Result<Vec<ConnectionInterval>, String> {
+        let mut inner = self.inner.lock().unwrap();
+        let db = self
+            .database(&mut inner)
+            .ok_or_else(|| format!("History database {} is unavailable", self.path.display()))?;
+        db.query(filter, from, to)
+            .map_err(|e| format!("Failed to query connection history: {}", e))
+    }
+
+    /// The connections as the last poll at or before `at` saw them.
+    pub fn snapshot_at(&self, at: u64) -> Result<HistorySnapshot, String> {
+        let mut inner = self.inner.lock().unwrap();
+        let db = self
+            .database(&mut inner)
+            .ok_or_else(|| format!("History database {} is unavailable", self.path.display()))?;
+        db.snapshot_at(at)
+            .map_err(|e| format!("Failed to reconstruct the connections at {}: {}", at, e))
+    }
+
+    /// Outbound TCP and UDP sockets that appeared at or after `since`.
+    pub fn connects_since(&self, since: u64) -> Result<Vec<ConnectEvent>, String> {
+        let mut inner = self.inner.lock().unwrap();
+        let db = self
+            .database(&mut inner)
+            .ok_or_else(|| format!("History database {} is unavailable", self.path.display()))?;
+        db.connects_since(since)
+            .map_err(|e| format!("Failed to read connects from history: {}", e))
+    }
+
+    fn database<'a>(&self, inner: &'a mut Inner) -> Option<&'a mut HistoryDb> {
+        if inner.db.is_none() {
+            if inner.open_failed_at.is_some_and(|failed| failed.elapsed() < RETRY_OPEN_AFTER) {
+                return None;
+            }
+            match HistoryDb::open(&self.path) {
+                Ok(db) => {
+                    inner.db = Some(db);
+                    inner.open_failed_at = None;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to open history database {}: {}", self.path.display(), e);
+                    inner.open_failed_at = Some(Instant::now());
+                }
+            }
+        }
+        inner.db.as_mut()
+    }
+}
+
+impl HistoryDb {
+    fn open(path: &PathBuf) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let conn = rusqlite::Connection::open(path).map_err(|e| e.to_string())?;
+        // auto_vacuum only takes effect before the first table is created
+        conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL; PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")
+            .and_then(|_| conn.execute_batch(SCHEMA))
+            .map_err(|e| e.to_string())?;
+
+        let last_poll_at: Option<i64> = conn
+            .query_row("SELECT MAX(at) FROM polls", [], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        let mut db = Self {
+            conn,
+            open: HashMap::new(),
+            last_poll_at: last_poll_at.map(|at| at as u64),
+            last_pruned: None,
+        };
+        // Intervals left open by a previous run ended at its last poll
+        db.close_all().map_err(|e| e.to_string())?;
+        Ok(db)
+    }
+
+    fn record(&mut self, connections: &[Connection], at: u64) -> rusqlite::Result<()> {
+        // Intervals that end during this poll were last seen by the previous one
+        let ended_at = self.last_poll_at.unwrap_or(at) as i64;
+        let tx = self.conn.transaction()?;
+        let mut seen = HashSet::with_capacity(connections.len());
+        {
+            let mut insert = tx.prepare_cached(
+                "INSERT INTO intervals (process_name, pid, namespace, container, systemd_unit, protocol,
+                     local_addr, local_port, remote_addr, remote_port, hostname, direction, state, risk,
+                     risk_reasons, first_seen, opened_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+            )?;
+            let mut rename = tx.prepare_cached("UPDATE intervals SET hostname = ?2 WHERE id = ?1")?;
+            let mut close = tx.prepare_cached("UPDATE intervals SET last_seen = MAX(first_seen, ?2) WHERE id = ?1")?;
+
+            for conn in connections {
+                let key = interval_key(conn);
+                if !seen.insert(key.clone()) {
+                    continue;
+                }
+                let tracked = Tracked {
+                    state: conn.state.clone(),
+                    risk: risk_rank(&conn.risk),
+                    risk_reasons: conn.risk_reasons.join("\n"),
+                };
+                let hostname = conn.resolved_name.clone().or_else(|| conn.hostname.clone());
+
+                let opened_at = match self.open.get_mut(&key) {
+                    Some(open) if open.tracked == tracked => {
+                        // Names resolve after the socket appears; that alone is not a new interval
+                        if open.hostname != hostname {
+                            rename.execute(params![open.id, hostname])?;
+                            open.hostname = hostname;
+                        }
+                        continue;
+                    }
+                    // A new state or risk starts a new interval, so every interval is uniform
+                    Some(open) => {
+                        close.execute(params![open.id, ended_at])?;
+                        open.opened_at
+                    }
+                    None => at,
+                };
+
+                insert.execute(params![
+                    conn.process_name,
+                    conn.pid,
+                    conn.namespace.as_ref().map(|ns| ns.name.as_str()),
+                    conn.container
+                        .as_ref()
+                        .map(|c| c.name.clone().unwrap_or_else(|| c.id.clone())),
+                    conn.systemd_unit.as_ref().map(|unit| unit.name.as_str()),
+                    conn.protocol,
+                    conn.local_addr,
+                    conn.local_port,
+                    conn.remote_addr,
+                    conn.remote_port,
+                    hostname,
+                    conn.direction.as_str(),
+                    tracked.state,
+                    tracked.risk,
+                    tracked.risk_reasons,
+                    at as i64,
+                    opened_at as i64,
+                ])?;
+                let id = tx.last_insert_rowid();
+                self.open.insert(
+                    key,
+                    OpenInterval {
+                        id,
+                        tracked,
+                        hostname,
+                        opened_at,
+                    },
+                );
+            }
+
+            // Sockets this poll no longer sees were last seen by the previous one
+            let gone: Vec<String> = self.open.keys().filter(|key| !seen.contains(*key)).cloned().collect();
+            for key in gone {
+                if let Some(open) = self.open.remove(&key) {
+                    close.execute(params![open.id, ended_at])?;
+                }
+            }
+
+            tx.execute(
+                "INSERT OR REPLACE INTO polls (at, connections) VALUES (?1, ?2)",
+                params![at as i64, connections.len() as i64],
+            )?;
+        }
+        tx.commit()?;
+        self.last_poll_at = Some(at);
+        Ok(())
+    }
+
+    /// Ends every open interval at the last recorded poll.
+    fn close_all(&mut self) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE intervals SET last_seen = MAX(first_seen, COALESCE(?1, first_seen)) WHERE last_seen IS NULL",
+            params![self.last_poll_at.map(|at| at as i64)],
+        )?;
+        self.open.clear();
+        Ok(())
+    }
+
+    /// Drops history older than the retention period, then the oldest
+    /// history until the database is back under its size limit.
+    fn prune(&mut self, settings: &HistorySettings, now: u64) -> rusqlite::Result<()> {
+        let cutoff = now.saturating_sub(u64::from(settings.retention_days) * 24 * 60 * 60 * 1000) as i64;
+        let removed = self.conn.execute(
+            "DELETE FROM intervals WHERE last_seen IS NOT NULL AND last_seen < ?1",
+            params![cutoff],
+        )?;
+        self.conn.execute("DELETE FROM polls WHERE at < ?1", params![cutoff])?;
+        if removed > 0 {
+            tracing::debug!("Dropped {} history intervals older than {} days", removed, settings.retention_days);
+        }
+
+        let max_bytes = settings.max_size_mb.saturating_mul(1024 * 1024);
+        loop {
+            self.conn.execute_batch("PRAGMA incremental_vacuum;")?;
+            let size = self.size_bytes()?;
+            if size <= max_bytes {
+                break;
+            }
+
+            // Drop the oldest tenth of the polls' time span, and the intervals that ended in it
+            let span: Option<(i64, i64)> = self
+                .conn
+                .query_row("SELECT MIN(at), MAX(at) FROM polls", [], |row| {
+                    Ok(row.get::<_, Option<i64>>(0)?.zip(row.get::<_, Option<i64>>(1)?))
+                })?;
+            let Some((oldest, newest)) = span.filter(|(oldest, newest)| oldest < newest) else {
+                tracing::warn!("History database is {} bytes, over its limit, with nothing left to drop", size);
+                break;
+            };
+            let cutoff = oldest + (newest - oldest) / 10 + 1;
+            self.conn.execute(
+                "DELETE FROM intervals WHERE last_seen IS NOT NULL AND last_seen < ?1",
+                params![cutoff],
+            )?;
+            self.conn.execute("DELETE FROM polls WHERE at < ?1", params![cutoff])?;
+            tracing::debug!("History database is {} bytes; dropped history before {}", size, cutoff);
+        }
+        // Let the WAL shrink along with the database
+        self.conn
+            .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+            .optional()?;
+        Ok(())
+    }
+
+    fn size_bytes(&self) -> rusqlite::Result<u64> {
+        let pragma = |name: &str| self.conn.query_row(&format!("PRAGMA {}", name), [], |row| row.get::<_, i64>(0));
+        let used_pages = pragma("page_count")? - pragma("freelist_count")?;
+        Ok(used_pages.max(0) as u64 * pragma("page_size")? as u64)
+    }
+
+    fn query(&self, filter: &HistoryFilter, from: u64, to: u64) -> rusqlite::Result<Vec<ConnectionInterval>> {
+        let mut sql = format!(
+            "SELECT {} FROM intervals WHERE first_seen <= ?1 AND (last_seen IS NULL OR last_seen >= ?2)",
+            INTERVAL_COLUMNS
+        );
+        let mut values: Vec<Box<dyn ToSql>> = vec![Box::new(to as i64), Box::new(from as i64)];
+        let mut condition = |clause: &str, value: Box<dyn ToSql>| {
+            values.push(value);
+            sql.push_str(&clause.replace('?', &format!("?{}", values.len())));
+        };
+
+        if let Some(process) = &filter.process {
+            condition(" AND instr(lower(process_name), ?) > 0", Box::new(process.to_lowercase()));
+        }
+        if let Some(pid) = filter.pid {
+            condition(" AND pid = ?", Box::new(pid));
+        }
+        if let Some(protocol) = &filter.protocol {
+            condition(" AND protocol = ?", Box::new(protocol.to_uppercase()));
+        }
+        if let Some(remote) = &filter.remote {
+            let remote = remote.to_lowercase();
+            condition(
+                " AND (instr(lower(remote_addr), ?) > 0 OR instr(lower(coalesce(hostname, '')), ?) > 0)",
+                Box::new(remote),
+            );
+        }
+        if let Some(port) = filter.port {
+            condition(" AND (local_port = ? OR remote_port = ?)", Box::new(port));
+        }
+        if let Some(min_risk) = &filter.min_risk {
+            condition(" AND risk >= ?", Box::new(risk_rank(min_risk)));
+        }
+        let limit = filter.limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+        sql.push_str(&format!(" ORDER BY first_seen DESC, id DESC LIMIT {}", limit));
+
+        let mut statement = self.conn.prepare(&sql)?;
+        let params: Vec<&dyn ToSql> = values.iter().map(|value| value.as_ref()).collect();
+        let rows = statement.query_map(params.as_slice(), read_interval)?;
+        rows.collect()
+    }
+
+    fn connects_since(&self, since: u64) -> rusqlite::Result<Vec<ConnectEvent>> {
+        // Only an interval that starts its socket is a connect; later ones follow state changes
+        let mut statement = self.conn.prepare(
+            "SELECT process_name, protocol, remote_addr, remote_port, hostname, first_seen FROM intervals
+             WHERE first_seen >= ?1 AND direction = 'outbound' AND protocol IN ('TCP', 'UDP')
+                 AND opened_at = first_seen",
+        )?;
+        let events = statement.query_map(params![since as i64], |row| {
+            Ok(ConnectEvent {
+                process_name: row.get(0)?,
+                protocol: row.get(1)?,
+                remote_addr: row.get(2)?,
+                remote_port: row.get(3)?,
+                hostname: row.get(4)?,
+                at: row.get::<_, i64>(5)? as u64,
+            })
+        })?;
+        events.collect()
+    }
+
+    fn snapshot_at(&self, at: u64) -> rusqlite::Result<HistorySnapshot> {
+        let polled_at: Option<i64> = self.conn.query_row(
+            "SELECT MAX(at) FROM polls WHERE at <= ?1",
+            params![at as i64],
+            |row| row.get(0),
+        )?;
+        let Some(polled_at) = polled_at else {
+            return Ok(HistorySnapshot {
+                at,
+                polled_at: None,
+                connections: Vec::new(),
+            });
+        };
+
+        // Intervals end at the last poll that saw them, so this poll saw exactly these
+        let mut statement = self.conn.prepare(&format!(
+            "SELECT {} FROM intervals
+             WHERE first_seen <= ?1 AND (last_seen IS NULL OR last_seen >= ?1)
+             ORDER BY first_seen, id",
+            INTERVAL_COLUMNS
+        ))?;
+        let connections = statement
+            .query_map(params![polled_at], read_interval)?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(HistorySnapshot {
+            at,
+            polled_at: Some(polled_at as u64),
+            connections,
+        })
+    }
+}
+
+fn read_interval(row: &rusqlite::Row) -> rusqlite::Result<ConnectionInterval> {
+    Ok(ConnectionInterval {
+        id: row.get(0)?,
+        process_name: row.get(1)?,
+        pid: row.get(2)?,
+        namespace: row.get(3)?,
+        container: row.get(4)?,
+        systemd_unit: row.get(5)?,
+        protocol: row.get(6)?,
+        local_addr: row.get(7)?,
+        local_port: row.get(8)?,
+        remote_addr: row.get(9)?,
+        remote_port: row.get(10)?,
+        hostname: row.get(11)?,
+        direction: parse_direction(&row.get::<_, String>(12)?),
+        state: row.get(13)?,
+        risk: risk_from_rank(row.get(14)?),
+        risk_reasons: row
+            .get::<_, String>(15)?
+            .split('\n')
+            .filter(|reason| !reason.is_empty())
+            .map(str::to_string)
+            .collect(),
+        first_seen: row.get::<_, i64>(16)? as u64,
+        last_seen: row.get::<_, Option<i64>>(17)?.map(|at| at as u64),
+    })
+}
+
+/// Identifies a socket across polls. Unix and raw sockets often share
+/// empty addresses, so their inode or protocol is part of the key.
+fn interval_key(conn: &Connection) -> String {
+    format!(
+        "{}|{}|{}|{}:{}|{}:{}|{}|{}",
+        conn.namespace.as_ref().map_or(0, |ns| ns.id),
+        conn.protocol,
+        conn.pid,
+        conn.local_addr,
+        conn.local_port,
+        conn.remote_addr,
+        conn.remote_port,
+        conn.unix.as_ref().map_or(0, |unix| unix.inode),
+        conn.raw
+            .as_ref()
+            .map(|raw| format!("{}@{}", raw.protocol, raw.interface.as_deref().unwrap_or("")))
+            .unwrap_or_default()
+    )
+}
+
+fn risk_rank(risk: &RiskLevel) -> i64 {
+    match risk {
+        RiskLevel::Low => 0,
+        RiskLevel::Medium => 1,
+        RiskLevel::High => 2,
+    }
+}
+
+fn risk_from_rank(rank: i64) -> RiskLevel {
+    match rank {
+        2.. => RiskLevel::High,
+        1 => RiskLevel::Medium,
+        _ => RiskLevel::Low,
+    }
+}
+
+fn parse_direction(direction: &str) -> ConnectionDirection {
+    match direction {
+        "inbound" => ConnectionDirection::Inbound,
+        "outbound" => ConnectionDirection::Outbound,
+        "listening" => ConnectionDirection::Listening,
+        _ => ConnectionDirection::Unknown,
+    }
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
@@ -0,0 +1,184 @@
+use crate::models::TcpMetrics;
+use std::collections::HashMap;
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::path::Path;
+
+// linux/netlink.h, linux/sock_diag.h and linux/inet_diag.h
+const NETLINK_SOCK_DIAG: libc::c_int = 4;
+const SOCK_DIAG_BY_FAMILY: u16 = 20;
+const NLM_F_REQUEST: u16 = 0x01;
+const NLM_F_DUMP: u16 = 0x300;
+const NLMSG_ERROR: u16 = 2;
+const NLMSG_DONE: u16 = 3;
+const INET_DIAG_INFO: u16 = 2;
+
+const NLMSG_HEADER_LEN: usize = 16;
+const INET_DIAG_REQ_LEN: usize = 56;
+/// `inet_diag_msg`: family, state, timer, retrans, the 48-byte socket id,
+/// then expires, rqueue, wqueue, uid and inode.
+const INET_DIAG_MSG_LEN: usize = 72;
+const INODE_OFFSET: usize = 68;
+
+/// Dumps every TCP socket in the current network namespace over
+/// NETLINK_SOCK_DIAG and returns its `tcp_info`, keyed by socket inode.
+pub fn tcp_metrics_by_inode() -> Result<HashMap<u64, TcpMetrics>, String> {
+    let socket = open_sock_diag()?;
+
+    let mut metrics = HashMap::new();
+    for family in [libc::AF_INET, libc::AF_INET6] {
+        let mut request = Vec::with_capacity(INET_DIAG_REQ_LEN);
+        request.push(family as u8);
+        request.push(libc::IPPROTO_TCP as u8);
+        request.push(1 << (INET_DIAG_INFO - 1)); // ask for the tcp_info attribute
+        request.push(0);
+        request.extend_from_slice(&u32::MAX.to_ne_bytes()); // every TCP state
+        request.extend_from_slice(&[0; 48]); // socket id: no filter
+
+        dump(&socket, &request, |payload| {
+            if let Some((inode, tcp_metrics)) = parse_diag_msg(payload) {
+                metrics.insert(inode, tcp_metrics);
+            }
+        })?;
+    }
+    Ok(metrics)
+}
+
+/// Same as [`tcp_metrics_by_inode`], for another network namespace given by
+/// its `/proc/<pid>/ns/net` file.
+pub fn tcp_metrics_in_namespace(namespace: &Path) -> Result<HashMap<u64, TcpMetrics>, String> {
+    in_namespace(namespace, tcp_metrics_by_inode)
+}
+
+/// Runs a sock_diag dump inside another network namespace. The dump runs on
+/// a short-lived thread that joins the namespace, which needs CAP_SYS_ADMIN.
+pub(super) fn in_namespace<T: Send>(
+    namespace: &Path,
+    dump: impl FnOnce() -> Result<T, String> + Send,
+) -> Result<T, String> {
+    let file = std::fs::File::open(namespace).map_err(|e| format!("Failed to open {}: {}", namespace.display(), e))?;
+
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                // setns only moves the calling thread, which exits right after the dump
+                if unsafe { libc::setns(file.as_raw_fd(), libc::CLONE_NEWNET) } != 0 {
+                    return Err(format!("Failed to enter {}: {}", namespace.display(), io::Error::last_os_error()));
+                }
+                dump()
+            })
+            .join()
+            .map_err(|_| "Namespace dump thread panicked".to_string())?
+    })
+}
+
+pub(super) fn open_sock_diag() -> Result<OwnedFd, String> {
+    let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, NETLINK_SOCK_DIAG) };
+    if fd < 0 {
+        return Err(format!("Failed to open sock_diag socket: {}", io::Error::last_os_error()));
+    }
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// Sends a SOCK_DIAG_BY_FAMILY dump request with the given family-specific
+/// body and hands each response payload to `on_message`.
+pub(super) fn dump(socket: &OwnedFd, body: &[u8], mut on_message: impl FnMut(&[u8])) -> Result<(), String> {
+    let mut request = Vec::with_capacity(NLMSG_HEADER_LEN + body.len());
+    request.extend_from_slice(&((NLMSG_HEADER_LEN + body.len()) as u32).to_ne_bytes());
+    request.extend_from_slice(&SOCK_DIAG_BY_FAMILY.to_ne_bytes());
+    request.extend_from_slice(&(NLM_F_REQUEST | NLM_F_DUMP).to_ne_bytes());
+    request.extend_from_slice(&[0; 8]); // sequence number and port id
+    request.extend_from_slice(body);
+
+    let sent = unsafe { libc::send(socket.as_raw_fd(), request.as_ptr() as *const libc::c_void, request.len(), 0) };
+    if sent < 0 {
+        return Err(format!("Failed to send sock_diag request: {}", io::Error::last_os_error()));
+    }
+
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let len = unsafe { libc::recv(socket.as_raw_fd(), buffer.as_mut_ptr() as *mut libc::c_void, buffer.len(), 0) };
+        if len < 0 {
+            return Err(format!("Failed to read sock_diag response: {}", io::Error::last_os_error()));
+        }
+
+        let mut data = &buffer[..len as usize];
+        while data.len() >= NLMSG_HEADER_LEN {
+            let message_len = read_u32(data, 0) as usize;
+            let message_type = u16::from_ne_bytes([data[4], data[5]]);
+            if message_len < NLMSG_HEADER_LEN || message_len > data.len() {
+                return Err("Malformed sock_diag response".to_string());
+            }
+
+            match message_type {
+                NLMSG_DONE => return Ok(()),
+                NLMSG_ERROR => {
+                    if message_len < NLMSG_HEADER_LEN + 4 {
+                        return Err("Malformed sock_diag error".to_string());
+                    }
+                    let errno = -(read_u32(data, NLMSG_HEADER_LEN) as i32);
+                    return Err(format!("sock_diag request failed: {}", io::Error::from_raw_os_error(errno)));
+                }
+                SOCK_DIAG_BY_FAMILY => on_message(&data[NLMSG_HEADER_LEN..message_len]),
+                _ => {}
+            }
+            data = &data[align(message_len).min(data.len())..];
+        }
+    }
+}
+
+fn parse_diag_msg(payload: &[u8]) -> Option<(u64, TcpMetrics)> {
+    if payload.len() < INET_DIAG_MSG_LEN {
+        return None;
+    }
+    let inode = u64::from(read_u32(payload, INODE_OFFSET));
+    if inode == 0 {
+        // TIME_WAIT and other orphaned sockets share inode 0
+        return None;
+    }
+
+    // Route attributes follow the fixed header
+    let mut attributes = &payload[INET_DIAG_MSG_LEN..];
+    while attributes.len() >= 4 {
+        let attribute_len = u16::from_ne_bytes([attributes[0], attributes[1]]) as usize;
+        let attribute_type = u16::from_ne_bytes([attributes[2], attributes[3]]);
+        if attribute_len < 4 || attribute_len > attributes.len() {
+            break;
+        }
+        if attribute_type == INET_DIAG_INFO {
+            return parse_tcp_info(&attributes[4..attribute_len]).map(|metrics| (inode, metrics));
+        }
+        attributes = &attributes[align(attribute_len).min(attributes.len())..];
+    }
+    None
+}
+
+/// Reads the fields we need from `struct tcp_info` by offset. The struct has
+/// grown over kernel releases; the byte counters need Linux 4.1 or later.
+fn parse_tcp_info(info: &[u8]) -> Option<TcpMetrics> {
+    let u32_at = |offset: usize| info.get(offset..offset + 4).map(|_| read_u32(info, offset));
+    let u64_at = |offset: usize| {
+        info.get(offset..offset + 8)
+            .map(|b| u64::from_ne_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+    };
+
+    Some(TcpMetrics {
+        rtt_us: u32_at(68)?,
+        rtt_var_us: u32_at(72)?,
+        congestion_window: u32_at(80)?,
+        retransmits: u32_at(100)?,
+        bytes_acked: u64_at(120).unwrap_or(0),
+        bytes_received: u64_at(128).unwrap_or(0),
+        send_rate: None,
+        receive_rate: None,
+    })
+}
+
+pub(super) fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_ne_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+/// Netlink messages and attributes are padded to four bytes.
+pub(super) fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
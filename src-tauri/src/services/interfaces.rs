@@ -0,0 +1,269 @@
+use crate::models::{
+    parse_ip_address, Connection, InterfaceAddress, InterfaceCounters, InterfaceKind, InterfaceRates, NetworkInterface,
+};
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+
+const SYS_CLASS_NET: &str = "/sys/class/net";
+const PROC_NET_DEV: &str = "/proc/net/dev";
+
+/// Interface inventory from `/proc/net/dev` and `/sys/class/net`. Keeps the
+/// previous counters so each query can report rates.
+#[derive(Default)]
+pub struct InterfaceMonitor {
+    previous: Mutex<HashMap<String, (Instant, InterfaceCounters)>>,
+}
+
+impl InterfaceMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn list(&self) -> Result<Vec<NetworkInterface>, String> {
+        let content = std::fs::read_to_string(PROC_NET_DEV)
+            .map_err(|e| format!("Failed to read {}: {}", PROC_NET_DEV, e))?;
+        let mut addresses = sys::interface_addresses().unwrap_or_else(|e| {
+            tracing::warn!("Failed to list interface addresses: {}", e);
+            HashMap::new()
+        });
+
+        let now = Instant::now();
+        let mut previous = self.previous.lock().unwrap();
+        let mut current = HashMap::new();
+        let mut interfaces = Vec::new();
+
+        for (name, counters) in parse_proc_net_dev(&content) {
+            let dir = Path::new(SYS_CLASS_NET).join(&name);
+            let rates = previous
+                .get(&name)
+                .map(|(at, before)| counter_rates(before, &counters, now.duration_since(*at).as_secs_f64()));
+            current.insert(name.clone(), (now, counters.clone()));
+
+            interfaces.push(NetworkInterface {
+                index: read_sys(&dir, "ifindex").and_then(|v| v.parse().ok()).unwrap_or(0),
+                kind: interface_kind(&dir),
+                mac_address: read_sys(&dir, "address").filter(|mac| !mac.is_empty() && mac != "00:00:00:00:00:00"),
+                mtu: read_sys(&dir, "mtu").and_then(|v| v.parse().ok()).unwrap_or(0),
+                oper_state: read_sys(&dir, "operstate").unwrap_or_else(|| "unknown".to_string()),
+                addresses: addresses
+                    .remove(&name)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(ip, prefix_len)| InterfaceAddress {
+                        address: ip.to_string(),
+                        prefix_len,
+                    })
+                    .collect(),
+                counters,
+                rates,
+                name,
+            });
+        }
+
+        // Interfaces that disappeared are forgotten
+        *previous = current;
+        interfaces.sort_by_key(|interface| interface.index);
+        Ok(interfaces)
+    }
+
+    /// Every address assigned to one of the host's interfaces.
+    pub fn local_addresses(&self) -> HashSet<IpAddr> {
+        sys::interface_addresses()
+            .map(|addresses| addresses.into_values().flatten().map(|(ip, _)| ip).collect())
+            .unwrap_or_default()
+    }
+
+    /// Tags each connection with the interface that owns its local address.
+    /// Wildcard binds are left untagged since they span every interface, as
+    /// are sockets in other network namespaces.
+    pub fn annotate(&self, connections: &mut [Connection]) {
+        let Ok(addresses) = sys::interface_addresses() else {
+            return;
+        };
+
+        let mut owners: HashMap<IpAddr, &str> = HashMap::new();
+        let mut loopback = None;
+        for (name, ips) in &addresses {
+            for (ip, _) in ips {
+                owners.entry(*ip).or_insert(name.as_str());
+                if ip.is_loopback() {
+                    loopback.get_or_insert(name.as_str());
+                }
+            }
+        }
+
+        for conn in connections.iter_mut().filter(|conn| conn.is_host_socket()) {
+            let Some(local) = parse_ip_address(&conn.local_addr) else {
+                continue;
+            };
+            let owner = match owners.get(&local) {
+                Some(name) => Some(*name),
+                // All of 127.0.0.0/8 lives on the loopback interface
+                None if local.is_loopback() => loopback,
+                None => None,
+            };
+            conn.interface = owner.map(str::to_string);
+        }
+    }
+}
+
+/// `/proc/net/dev`: two header lines, then `name: rx(8 fields) tx(8 fields)`.
+fn parse_proc_net_dev(content: &str) -> Vec<(String, InterfaceCounters)> {
+    content
+        .lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (name, stats) = line.split_once(':')?;
+            let fields: Vec<u64> = stats.split_whitespace().filter_map(|v| v.parse().ok()).collect();
+            if fields.len() < 16 {
+                return None;
+            }
+            Some((
+                name.trim().to_string(),
+                InterfaceCounters {
+                    rx_bytes: fields[0],
+                    rx_packets: fields[1],
+                    rx_errors: fields[2],
+                    rx_dropped: fields[3],
+                    tx_bytes: fields[8],
+                    tx_packets: fields[9],
+                    tx_errors: fields[10],
+                    tx_dropped: fields[11],
+                },
+            ))
+        })
+        .collect()
+}
+
+fn counter_rates(before: &InterfaceCounters, after: &InterfaceCounters, elapsed: f64) -> InterfaceRates {
+    // Counters reset when a driver reloads; report zero rather than a huge spike
+    let rate = |before: u64, after: u64| {
+        if elapsed > 0.0 {
+            after.saturating_sub(before) as f64 / elapsed
+        } else {
+            0.0
+        }
+    };
+
+    InterfaceRates {
+        rx_bytes: rate(before.rx_bytes, after.rx_bytes),
+        rx_packets: rate(before.rx_packets, after.rx_packets),
+        rx_errors: rate(before.rx_errors, after.rx_errors),
+        rx_dropped: rate(before.rx_dropped, after.rx_dropped),
+        tx_bytes: rate(before.tx_bytes, after.tx_bytes),
+        tx_packets: rate(before.tx_packets, after.tx_packets),
+        tx_errors: rate(before.tx_errors, after.tx_errors),
+        tx_dropped: rate(before.tx_dropped, after.tx_dropped),
+    }
+}
+
+fn read_sys(dir: &Path, attribute: &str) -> Option<String> {
+    std::fs::read_to_string(dir.join(attribute))
+        .ok()
+        .map(|value| value.trim().to_string())
+}
+
+/// Works out the interface type from the sysfs attributes each driver exposes.
+fn interface_kind(dir: &Path) -> InterfaceKind {
+    const ARPHRD_ETHER: &str = "1";
+    const ARPHRD_LOOPBACK: &str = "772";
+
+    let uevent = read_sys(dir, "uevent").unwrap_or_default();
+    let devtype = uevent.lines().find_map(|line| line.strip_prefix("DEVTYPE="));
+    match devtype {
+        Some("wlan") => return InterfaceKind::Wifi,
+        Some("wireguard") => return InterfaceKind::Wireguard,
+        Some("bridge") => return InterfaceKind::Bridge,
+        Some("vlan") => return InterfaceKind::Vlan,
+        Some("bond") => return InterfaceKind::Bond,
+        _ => {}
+    }
+
+    if dir.join("wireless").exists() || dir.join("phy80211").exists() {
+        return InterfaceKind::Wifi;
+    }
+    if dir.join("tun_flags").exists() {
+        return InterfaceKind::Tun;
+    }
+
+    let arp_type = read_sys(dir, "type").unwrap_or_default();
+    if arp_type == ARPHRD_LOOPBACK {
+        return InterfaceKind::Loopback;
+    }
+    if arp_type == ARPHRD_ETHER {
+        if dir.join("device").exists() {
+            return InterfaceKind::Ethernet;
+        }
+        // A virtual Ethernet device linked to another interface is one end of a veth pair
+        if read_sys(dir, "iflink") != read_sys(dir, "ifindex") {
+            return InterfaceKind::Veth;
+        }
+    }
+    InterfaceKind::Other
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::collections::HashMap;
+    use std::ffi::CStr;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    /// Addresses and prefix lengths per interface, via getifaddrs(3).
+    pub fn interface_addresses() -> Result<HashMap<String, Vec<(IpAddr, u8)>>, String> {
+        let mut list: *mut libc::ifaddrs = std::ptr::null_mut();
+        if unsafe { libc::getifaddrs(&mut list) } != 0 {
+            return Err(format!("getifaddrs failed: {}", std::io::Error::last_os_error()));
+        }
+
+        let mut addresses: HashMap<String, Vec<(IpAddr, u8)>> = HashMap::new();
+        let mut entry = list;
+        while !entry.is_null() {
+            let ifa = unsafe { &*entry };
+            entry = ifa.ifa_next;
+
+            let (Some(ip), Some(mask)) = (unsafe { to_ip(ifa.ifa_addr) }, unsafe { to_ip(ifa.ifa_netmask) }) else {
+                continue;
+            };
+            let prefix_len = match mask {
+                IpAddr::V4(mask) => u32::from(mask).count_ones(),
+                IpAddr::V6(mask) => u128::from(mask).count_ones(),
+            } as u8;
+
+            let name = unsafe { CStr::from_ptr(ifa.ifa_name) }.to_string_lossy().into_owned();
+            addresses.entry(name).or_default().push((ip, prefix_len));
+        }
+
+        unsafe { libc::freeifaddrs(list) };
+        Ok(addresses)
+    }
+
+    unsafe fn to_ip(address: *const libc::sockaddr) -> Option<IpAddr> {
+        if address.is_null() {
+            return None;
+        }
+        match i32::from((*address).sa_family) {
+            libc::AF_INET => {
+                let v4 = &*(address as *const libc::sockaddr_in);
+                Some(IpAddr::V4(Ipv4Addr::from(u32::from_be(v4.sin_addr.s_addr))))
+            }
+            libc::AF_INET6 => {
+                let v6 = &*(address as *const libc::sockaddr_in6);
+                Some(IpAddr::V6(Ipv6Addr::from(v6.sin6_addr.s6_addr)))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use std::collections::HashMap;
+    use std::net::IpAddr;
+
+    pub fn interface_addresses() -> Result<HashMap<String, Vec<(IpAddr, u8)>>, String> {
+        Err("Interface addresses are only collected on Linux".to_string())
+    }
+}
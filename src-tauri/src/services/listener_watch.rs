@@ -0,0 +1,358 @@
+use crate::models::{
+    classify_exposure, connection_exposure, exposure_risk, sensitive_service, Alert, AlertKind, Connection, Exposure,
+    RiskLevel,
+};
+use super::direction::{ephemeral_port_range, is_service_listener};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A listener that goes away and comes back with the same owner within this
+/// long is a restart, not a new listener.
+const RESTART_GRACE: Duration = Duration::from_secs(10 * 60);
+
+/// Follows the listening sockets across polls and reports new listeners
+/// and listeners that changed owner. Owners are compared by process name,
+/// so a restarted service keeps its sockets. The first poll only records
+/// what is already listening.
+pub struct ListenerWatch {
+    ephemeral_ports: (u16, u16),
+    state: Mutex<Option<WatchState>>,
+}
+
+#[derive(Default)]
+struct WatchState {
+    // (namespace, protocol, address, port) -> owning process names
+    listeners: HashMap<ListenerKey, BTreeSet<String>>,
+    // listeners gone since an earlier poll, with their owners and when they went
+    closed: HashMap<ListenerKey, (BTreeSet<String>, Instant)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ListenerKey {
+    namespace: u64,
+    protocol: String,
+    address: String,
+    port: u16,
+}
+
+impl ListenerWatch {
+    pub fn new() -> Self {
+        Self {
+            ephemeral_ports: ephemeral_port_range(),
+            state: Mutex::new(None),
+        }
+    }
+
+    /// Compares the listeners in `connections` with the previous poll and
+    /// returns new alerts.
+    pub fn observe(&self, connections: &[Connection]) -> Vec<Alert> {
+        self.observe_at(connections, Instant::now())
+    }
+
+    fn observe_at(&self, connections: &[Connection], now: Instant) -> Vec<Alert> {
+        let mut current: HashMap<ListenerKey, BTreeSet<String>> = HashMap::new();
+        let mut owners: HashMap<(&ListenerKey, &str), &Connection> = HashMap::new();
+        let listeners: Vec<(ListenerKey, &Connection)> = connections
+            .iter()
+            .filter(|conn| self.is_listener(conn))
+            .map(|conn| (listener_key(conn), conn))
+            .collect();
+        for (key, conn) in &listeners {
+            let names = current.entry(key.clone()).or_default();
+            // A socket whose owner could not be resolved says nothing about who holds it
+            if conn.pid == 0 || conn.process_name.is_empty() {
+                continue;
+            }
+            names.insert(conn.process_name.clone());
+            owners.entry((key, conn.process_name.as_str())).or_insert(conn);
+        }
+
+        let mut guard = self.state.lock().unwrap();
+        // Listeners with only unresolved owners keep the owners last seen, if any
+        for (key, names) in current.iter_mut().filter(|(_, names)| names.is_empty()) {
+            let known = guard.as_ref().and_then(|state| {
+                state
+                    .listeners
+                    .get(key)
+                    .or_else(|| state.closed.get(key).map(|(names, _)| names))
+            });
+            if let Some(known) = known {
+                names.clone_from(known);
+            }
+        }
+        current.retain(|_, names| !names.is_empty());
+
+        let Some(state) = guard.as_mut() else {
+            *guard = Some(WatchState {
+                listeners: current,
+                closed: HashMap::new(),
+            });
+            return Vec::new();
+        };
+
+        state.closed.retain(|_, (_, closed_at)| now.duration_since(*closed_at) < RESTART_GRACE);
+
+        // New listeners of one process on one port, e.g. 0.0.0.0 and :: binds, make one alert
+        let mut started: BTreeMap<(String, u32, String, u16), Vec<&Connection>> = BTreeMap::new();
+        let mut alerts = Vec::new();
+        for (key, names) in &current {
+            let previous = state
+                .listeners
+                .get(key)
+                .or_else(|| state.closed.get(key).map(|(names, _)| names));
+            let added: Vec<&String> = match previous {
+                Some(previous) => names.difference(previous).collect(),
+                None => names.iter().collect(),
+            };
+            if added.is_empty() {
+                continue;
+            }
+
+            match previous {
+                // Someone else now holds a socket another process had
+                Some(previous) if !previous.is_subset(names) => {
+                    let gone: Vec<&str> = previous.difference(names).map(String::as_str).collect();
+                    let holders: Vec<&Connection> = added.iter().map(|name| owners[&(key, name.as_str())]).collect();
+                    alerts.push(owner_changed_alert(key, &gone, &holders));
+                }
+                _ => {
+                    for name in added {
+                        let conn = owners[&(key, name.as_str())];
+                        started
+                            .entry((name.clone(), conn.pid, key.protocol.clone(), key.port))
+                            .or_default()
+                            .push(conn);
+                    }
+                }
+            }
+        }
+        alerts.extend(started.into_values().map(|binds| new_listener_alert(&binds)));
+
+        let previous = std::mem::replace(&mut state.listeners, current);
+        for (key, names) in previous {
+            if !state.listeners.contains_key(&key) {
+                state.closed.insert(key, (names, now));
+            }
+        }
+        for key in state.listeners.keys() {
+            state.closed.remove(key);
+        }
+        alerts
+    }
+
+    fn is_listener(&self, conn: &Connection) -> bool {
+        is_service_listener(conn, self.ephemeral_ports)
+    }
+}
+
+fn listener_key(conn: &Connection) -> ListenerKey {
+    ListenerKey {
+        namespace: conn.namespace.as_ref().map_or(0, |ns| ns.id),
+        protocol: conn.protocol.clone(),
+        address: conn.local_addr.clone(),
+        port: conn.local_port,
+    }
+}
+
+fn new_listener_alert(binds: &[&Connection]) -> Alert {
+    let conn = binds[0];
+    let exposure = binds
+        .iter()
+        .map(|bind| connection_exposure(bind))
+        .max_by_key(|exposure| exposure_rank(*exposure))
+        .unwrap_or(Exposure::AllInterfaces);
+    let addresses: BTreeSet<&str> = binds.iter().map(|bind| bind.local_addr.as_str()).collect();
+
+    let mut details = vec![
+        format!("Exposure: {}", describe_exposure(exposure)),
+        format!("Addresses: {}", addresses.into_iter().collect::<Vec<_>>().join(", ")),
+    ];
+    details.extend(owner_details(conn));
+    details.extend(exposure_risk(exposure, conn.local_port).1);
+
+    Alert::new(
+        AlertKind::NewListener,
+        exposure_severity(exposure),
+        format!(
+            "{} (PID {}) started listening on {} port {} ({})",
+            conn.process_name,
+            conn.pid,
+            conn.protocol,
+            conn.local_port,
+            describe_exposure(exposure)
+        ),
+        details,
+    )
+}
+
+fn owner_changed_alert(key: &ListenerKey, gone: &[&str], holders: &[&Connection]) -> Alert {
+    let exposure = holders
+        .first()
+        .map_or_else(|| classify_exposure(&key.address), |conn| connection_exposure(conn));
+    let now_held_by: Vec<String> = holders
+        .iter()
+        .map(|conn| format!("{} (PID {})", conn.process_name, conn.pid))
+        .collect();
+
+    let mut details = vec![
+        format!("Exposure: {}", describe_exposure(exposure)),
+        format!("Previous owner: {}", gone.join(", ")),
+    ];
+    for conn in holders {
+        details.extend(owner_details(conn));
+    }
+
+    Alert::new(
+        AlertKind::ListenerOwnerChanged,
+        // A port changing hands is worth a look even where a new listener would not be
+        exposure_severity(exposure).max(RiskLevel::Medium),
+        format!(
+            "{} {}:{} is now held by {} instead of {} ({})",
+            key.protocol,
+            key.address,
+            key.port,
+            now_held_by.join(", "),
+            gone.join(", "),
+            describe_exposure(exposure)
+        ),
+        details,
+    )
+}
+
+/// Who the listener belongs to beyond its process name.
+fn owner_details(conn: &Connection) -> Vec<String> {
+    let mut details = Vec::new();
+    if let Some(service) = sensitive_service(conn.local_port) {
+        details.push(format!("Service: {}", service));
+    }
+    if let Some(unit) = &conn.systemd_unit {
+        details.push(format!("Systemd unit: {}", unit.name));
+    }
+    if let Some(container) = &conn.container {
+        details.push(format!("Container: {}", container.name.as_deref().unwrap_or(&container.id)));
+    }
+    if let Some(namespace) = conn.namespace.as_ref().filter(|ns| !ns.is_host) {
+        details.push(format!("Network namespace: {}", namespace.name));
+    }
+    details
+}
+
+fn describe_exposure(exposure: Exposure) -> &'static str {
+    match exposure {
+        Exposure::AllInterfaces => "all interfaces",
+        Exposure::SpecificAddress => "a network address",
+        Exposure::NamespaceOnly => "inside its network namespace",
+        Exposure::LoopbackOnly => "loopback only",
+    }
+}
+
+fn exposure_rank(exposure: Exposure) -> u8 {
+    match exposure {
+        Exposure::LoopbackOnly => 0,
+        Exposure::NamespaceOnly => 1,
+        Exposure::SpecificAddress => 2,
+        Exposure::AllInterfaces => 3,
+    }
+}
+
+fn exposure_severity(exposure: Exposure) -> RiskLevel {
+    match exposure {
+        Exposure::LoopbackOnly | Exposure::NamespaceOnly => RiskLevel::Low,
+        Exposure::SpecificAddress => RiskLevel::Medium,
+        Exposure::AllInterfaces => RiskLevel::High,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ConnectionDirection;
+
+    fn listener(process_name: &str, pid: u32, local_addr: &str, local_port: u16) -> Connection {
+        Connection {
+            process_name: process_name.to_string(),
+            pid,
+            protocol: "TCP".to_string(),
+            local_addr: local_addr.to_string(),
+            local_port,
+            direction: ConnectionDirection::Listening,
+            ..Default::default()
+        }
+    }
+
+    fn kinds(alerts: &[Alert]) -> Vec<AlertKind> {
+        alerts.iter().map(|alert| alert.kind).collect()
+    }
+
+    #[test]
+    fn first_poll_only_records_what_is_listening() {
+        let watch = ListenerWatch::new();
+        let start = Instant::now();
+        assert!(watch.observe_at(&[listener("sshd", 812, "0.0.0.0", 22)], start).is_empty());
+        assert!(watch.observe_at(&[listener("sshd", 812, "0.0.0.0", 22)], start + Duration::from_secs(5)).is_empty());
+    }
+
+    #[test]
+    fn dual_stack_bind_makes_one_alert() {
+        let watch = ListenerWatch::new();
+        let start = Instant::now();
+        watch.observe_at(&[], start);
+
+        let binds = [listener("nginx", 900, "0.0.0.0", 8080), listener("nginx", 900, "::", 8080)];
+        let alerts = watch.observe_at(&binds, start + Duration::from_secs(5));
+        assert_eq!(kinds(&alerts), [AlertKind::NewListener]);
+        assert_eq!(alerts[0].severity, RiskLevel::High);
+        assert!(alerts[0].details.contains(&"Addresses: 0.0.0.0, ::".to_string()));
+    }
+
+    #[test]
+    fn restart_within_the_grace_period_is_quiet() {
+        let watch = ListenerWatch::new();
+        let start = Instant::now();
+        watch.observe_at(&[listener("postgres", 700, "127.0.0.1", 5432)], start);
+        watch.observe_at(&[], start + Duration::from_secs(5));
+
+        let restarted = [listener("postgres", 4100, "127.0.0.1", 5432)];
+        assert!(watch.observe_at(&restarted, start + Duration::from_secs(65)).is_empty());
+    }
+
+    #[test]
+    fn listener_returning_after_the_grace_period_is_new() {
+        let watch = ListenerWatch::new();
+        let start = Instant::now();
+        watch.observe_at(&[listener("redis-server", 300, "127.0.0.1", 6379)], start);
+        watch.observe_at(&[], start + Duration::from_secs(5));
+
+        let back = [listener("redis-server", 5300, "127.0.0.1", 6379)];
+        let alerts = watch.observe_at(&back, start + RESTART_GRACE + Duration::from_secs(10));
+        assert_eq!(kinds(&alerts), [AlertKind::NewListener]);
+        assert_eq!(alerts[0].severity, RiskLevel::Low);
+    }
+
+    #[test]
+    fn owner_change_alerts_even_on_loopback() {
+        let watch = ListenerWatch::new();
+        let start = Instant::now();
+        watch.observe_at(&[listener("postgres", 700, "127.0.0.1", 5432)], start);
+        watch.observe_at(&[], start + Duration::from_secs(5));
+
+        let taken = [listener("nc", 6666, "127.0.0.1", 5432)];
+        let alerts = watch.observe_at(&taken, start + Duration::from_secs(10));
+        assert_eq!(kinds(&alerts), [AlertKind::ListenerOwnerChanged]);
+        assert!(alerts[0].severity >= RiskLevel::Medium);
+        assert!(alerts[0].message.contains("instead of postgres"));
+    }
+
+    #[test]
+    fn unresolved_owners_are_not_new_owners() {
+        let watch = ListenerWatch::new();
+        let start = Instant::now();
+        watch.observe_at(&[listener("sshd", 812, "0.0.0.0", 22)], start);
+
+        // The owner can't be read for a poll, then resolves again
+        let unresolved = [listener("unknown", 0, "0.0.0.0", 22), listener("", 0, "0.0.0.0", 2222)];
+        assert!(watch.observe_at(&unresolved, start + Duration::from_secs(5)).is_empty());
+        assert!(watch.observe_at(&[listener("sshd", 812, "0.0.0.0", 22)], start + Duration::from_secs(10)).is_empty());
+    }
+}
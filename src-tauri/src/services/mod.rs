@@ -1,17 +1,222 @@
 mod connections_windows;
+#[cfg(target_os = "linux")]
 mod connections_linux;
 mod connections_macos;
+mod containers;
+mod alerts;
+mod asn;
+mod bandwidth;
+mod baseline;
+mod beaconing;
+pub mod capture;
+mod direction;
+mod dns_message;
+pub mod exposure;
+mod history;
+#[cfg(target_os = "linux")]
+mod inet_diag;
+mod interfaces;
+mod listener_watch;
+mod neighbor_watch;
+mod neighbors;
+mod oui;
+mod passive_dns;
+mod processes;
+mod reverse_dns;
+mod routes;
+pub mod rules;
+mod service_names;
+pub mod settings;
+mod systemd_units;
+#[cfg(target_os = "linux")]
+mod unix_diag;
 
-use crate::models::Connection;
+use crate::models::{
+    calculate_risk, BaselineStatus, Connection, ConnectionInterval, HistoryFilter, HistorySnapshot, Neighbor, NetworkInterface, ProcessDetails, Settings,
+    TalkerWindow, TlsRules, TopTalkers,
+};
+use alerts::AlertLog;
+use bandwidth::BandwidthTracker;
+use baseline::BaselineLearner;
+use beaconing::BeaconDetector;
+use capture::LiveCapture;
+use containers::ContainerResolver;
+use history::HistoryStore;
+use interfaces::InterfaceMonitor;
+use listener_watch::ListenerWatch;
+use neighbor_watch::NeighborWatch;
+use oui::OuiRegistry;
+use reverse_dns::ReverseDnsResolver;
+use routes::RouteTable;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
-pub struct ConnectionCollector;
+const HISTORY_DATABASE_FILE: &str = "history.sqlite3";
+
+/// Collects connection snapshots and owns the enrichers that keep state
+/// between polls. Managed as Tauri state.
+pub struct ConnectionCollector {
+    reverse_dns: ReverseDnsResolver,
+    live_capture: LiveCapture,
+    bandwidth: BandwidthTracker,
+    interfaces: InterfaceMonitor,
+    containers: ContainerResolver,
+    neighbor_watch: NeighborWatch,
+    listener_watch: ListenerWatch,
+    alerts: AlertLog,
+    history: HistoryStore,
+    beacons: BeaconDetector,
+    baseline: BaselineLearner,
+    last_snapshot: Mutex<Option<Vec<Connection>>>,
+    app_data_dir: PathBuf, // rules files and the history database live under here
+}
 
 impl ConnectionCollector {
-    pub fn new() -> Self {
-        Self
+    pub fn new(settings: &Settings, app_data_dir: PathBuf) -> Self {
+        OuiRegistry::set_app_data_dir(&app_data_dir);
+        Self {
+            reverse_dns: ReverseDnsResolver::new(settings.reverse_dns.clone()),
+            live_capture: LiveCapture::new(),
+            bandwidth: BandwidthTracker::new(),
+            interfaces: InterfaceMonitor::new(),
+            containers: ContainerResolver::new(),
+            neighbor_watch: NeighborWatch::new(),
+            listener_watch: ListenerWatch::new(),
+            alerts: AlertLog::new(),
+            history: HistoryStore::new(app_data_dir.join(HISTORY_DATABASE_FILE), settings.history.clone()),
+            beacons: BeaconDetector::new(settings.beaconing.clone()),
+            baseline: BaselineLearner::new(&app_data_dir, settings.baseline.clone()),
+            last_snapshot: Mutex::new(None),
+            app_data_dir,
+        }
+    }
+
+    pub fn live_capture(&self) -> &LiveCapture {
+        &self.live_capture
+    }
+
+    pub fn alerts(&self) -> &AlertLog {
+        &self.alerts
     }
 
+    pub fn apply_settings(&self, settings: &Settings) {
+        self.reverse_dns.configure(settings.reverse_dns.clone());
+        self.history.configure(settings.history.clone());
+        self.beacons.configure(settings.beaconing.clone());
+        self.baseline.configure(settings.baseline.clone());
+    }
+
+    /// Polls: collects a fresh snapshot and advances every stateful enricher
+    /// (rates, bandwidth windows, history, alert watchers). Only the regular
+    /// refresh should call this; other readers use [`Self::latest_connections`].
     pub fn get_connections(&self) -> Result<Vec<Connection>, String> {
+        let mut connections = self.collect_platform_connections()?;
+        self.containers.annotate(&mut connections);
+        systemd_units::annotate(&mut connections);
+        self.interfaces.annotate(&mut connections);
+        let routes = RouteTable::load()
+            .inspect_err(|e| tracing::debug!("Skipping egress routes: {}", e))
+            .ok();
+        routes::annotate(&mut connections, routes.as_ref());
+        match neighbors::read_neighbors() {
+            Ok(table) => {
+                neighbors::annotate(&mut connections, &table);
+                for alert in self.neighbor_watch.observe(&table, routes.as_ref()) {
+                    self.alerts.raise(alert);
+                }
+            }
+            Err(e) => tracing::debug!("Skipping neighbor lookup: {}", e),
+        }
+        self.live_capture.annotate(&mut connections);
+        assess_snapshot(&mut connections, &rules::load_tls_rules(&self.app_data_dir));
+        for alert in self.listener_watch.observe(&connections) {
+            self.alerts.raise(alert);
+        }
+
+        let route_rules = rules::load_route_rules(&self.app_data_dir);
+        let container_rules = rules::load_container_rules(&self.app_data_dir);
+        let raw_socket_allowlist = rules::load_raw_socket_allowlist(&self.app_data_dir);
+        for connection in connections.iter_mut() {
+            route_rules.apply(connection);
+            container_rules.apply(connection);
+            raw_socket_allowlist.apply(connection);
+        }
+        self.reverse_dns.annotate(&mut connections);
+        let captured = self.live_capture.take_connects();
+        if !captured.is_empty() {
+            self.beacons
+                .record_captured(captured, &connections, &self.interfaces.local_addresses());
+        }
+        // Before recording, so history keeps the beaconing and baseline reasons
+        for alert in self.beacons.analyze(&self.history) {
+            self.alerts.raise(alert);
+        }
+        self.beacons.annotate(&mut connections);
+        for alert in self.baseline.observe(&mut connections) {
+            self.alerts.raise(alert);
+        }
+        // After enrichment, so host talkers can carry resolved names
+        self.bandwidth.annotate(&mut connections);
+        self.history.record(&connections);
+        *self.last_snapshot.lock().unwrap() = Some(connections.clone());
+        Ok(connections)
+    }
+
+    /// The snapshot from the last poll, without advancing any poll state;
+    /// polls once if nothing has been collected yet.
+    pub fn latest_connections(&self) -> Result<Vec<Connection>, String> {
+        if let Some(connections) = self.last_snapshot.lock().unwrap().clone() {
+            return Ok(connections);
+        }
+        self.get_connections()
+    }
+
+    pub fn top_talkers(&self, window: TalkerWindow) -> TopTalkers {
+        self.bandwidth.top_talkers(window)
+    }
+
+    pub fn query_history(&self, filter: &HistoryFilter, from: u64, to: u64) -> Result<Vec<ConnectionInterval>, String> {
+        self.history.query(filter, from, to)
+    }
+
+    pub fn snapshot_at(&self, at: u64) -> Result<HistorySnapshot, String> {
+        self.history.snapshot_at(at)
+    }
+
+    pub fn baseline_status(&self) -> BaselineStatus {
+        self.baseline.status()
+    }
+
+    pub fn start_baseline_learning(&self) -> Result<BaselineStatus, String> {
+        self.baseline.start_learning()
+    }
+
+    /// Writes the baseline next to the other exports and returns its path.
+    pub fn export_baseline(&self) -> Result<PathBuf, String> {
+        self.baseline.export(&self.app_data_dir)
+    }
+
+    pub fn import_baseline(&self, path: &Path) -> Result<BaselineStatus, String> {
+        self.baseline.import(path)
+    }
+
+    pub fn interfaces(&self) -> Result<Vec<NetworkInterface>, String> {
+        self.interfaces.list()
+    }
+
+    pub fn neighbors(&self) -> Result<Vec<Neighbor>, String> {
+        neighbors::read_neighbors()
+    }
+
+    /// A process's details, with the systemd unit and container it runs in.
+    pub fn process_details(&self, pid: u32) -> Result<ProcessDetails, String> {
+        let mut details = processes::read_process(pid)?;
+        details.systemd_unit = systemd_units::lookup(pid);
+        details.container = self.containers.lookup(pid);
+        Ok(details)
+    }
+
+    fn collect_platform_connections(&self) -> Result<Vec<Connection>, String> {
         cfg_if::cfg_if! {
             if #[cfg(target_os = "windows")] {
                 let collector = connections_windows::WindowsConnectionCollector::new();
@@ -27,4 +232,18 @@ impl ConnectionCollector {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Runs the snapshot-wide analysis (direction inference, service names, then
+/// risk scoring and TLS rules) over freshly collected connections.
+pub fn assess_snapshot(connections: &mut [Connection], tls_rules: &TlsRules) {
+    direction::infer_directions(connections);
+    service_names::annotate_services(connections);
+
+    for connection in connections.iter_mut() {
+        let (risk, risk_reasons) = calculate_risk(connection);
+        connection.risk = risk;
+        connection.risk_reasons = risk_reasons;
+        tls_rules.apply(connection);
+    }
+}
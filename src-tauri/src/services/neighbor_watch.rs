@@ -0,0 +1,227 @@
+use super::oui::OuiRegistry;
+use super::routes::RouteTable;
+use crate::models::{Alert, AlertKind, Neighbor, RiskLevel};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// An IP whose MAC changes this many times within the window is flapping.
+const FLAP_THRESHOLD: usize = 3;
+const FLAP_WINDOW: Duration = Duration::from_secs(10 * 60);
+/// Neighbors missing from the table for this long are forgotten, so a host
+/// coming back later is compared afresh.
+const NEIGHBOR_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+/// Follows the neighbor table across polls looking for the signs of ARP
+/// spoofing: the gateway's MAC changing, one MAC claiming several IPs, and
+/// an IP bouncing between MACs.
+#[derive(Default)]
+pub struct NeighborWatch {
+    state: Mutex<WatchState>,
+}
+
+#[derive(Default)]
+struct WatchState {
+    // (ip, interface) -> last MAC seen, and when
+    macs: HashMap<(String, String), (String, Instant)>,
+    // (ip, interface) -> when its MAC changed, within the flap window
+    changes: HashMap<(String, String), VecDeque<Instant>>,
+    // (interface, mac) -> IPs it answered for when we last alerted, and when
+    // it last answered for more than one
+    shared: HashMap<(String, String), (BTreeSet<String>, Instant)>,
+}
+
+impl NeighborWatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compares `neighbors` with the previous poll and returns new alerts.
+    pub fn observe(&self, neighbors: &[Neighbor], routes: Option<&RouteTable>) -> Vec<Alert> {
+        self.observe_at(neighbors, routes, Instant::now())
+    }
+
+    fn observe_at(&self, neighbors: &[Neighbor], routes: Option<&RouteTable>, now: Instant) -> Vec<Alert> {
+        let mut state = self.state.lock().unwrap();
+        let gateways: Vec<(IpAddr, &str)> = routes.map(RouteTable::default_gateways).unwrap_or_default();
+        let is_gateway = |neighbor: &Neighbor| {
+            gateways
+                .iter()
+                .any(|(ip, interface)| *interface == neighbor.interface && ip.to_string() == neighbor.ip_address)
+        };
+
+        let mut alerts = Vec::new();
+        for neighbor in neighbors {
+            let Some(mac) = &neighbor.mac_address else {
+                continue;
+            };
+            let key = (neighbor.ip_address.clone(), neighbor.interface.clone());
+            let Some((previous, _)) = state.macs.insert(key.clone(), (mac.clone(), now)) else {
+                continue;
+            };
+            if previous == *mac {
+                continue;
+            }
+
+            if is_gateway(neighbor) {
+                alerts.push(Alert::new(
+                    AlertKind::GatewayMacChanged,
+                    RiskLevel::High,
+                    format!(
+                        "Default gateway {} on {} changed MAC from {} to {}",
+                        neighbor.ip_address, neighbor.interface, previous, mac
+                    ),
+                    vec![
+                        format!("Previous MAC: {}", describe_mac(&previous)),
+                        format!("New MAC: {}", describe_mac(mac)),
+                    ],
+                ));
+            }
+
+            let changes = state.changes.entry(key.clone()).or_default();
+            changes.push_back(now);
+            while changes.front().is_some_and(|at| now.duration_since(*at) > FLAP_WINDOW) {
+                changes.pop_front();
+            }
+            if changes.len() >= FLAP_THRESHOLD {
+                let count = changes.len();
+                // Start counting afresh so a persistent flap alerts once per window
+                changes.clear();
+                alerts.push(Alert::new(
+                    AlertKind::MacFlapping,
+                    if is_gateway(neighbor) { RiskLevel::High } else { RiskLevel::Medium },
+                    format!(
+                        "{} on {} switched MAC {} times in {} minutes",
+                        neighbor.ip_address,
+                        neighbor.interface,
+                        count,
+                        FLAP_WINDOW.as_secs() / 60
+                    ),
+                    vec![format!("Latest MAC: {}", describe_mac(mac))],
+                ));
+            }
+        }
+
+        // IPv6 hosts legitimately answer for several addresses from one MAC
+        let mut claims: HashMap<(String, String), (BTreeSet<String>, bool)> = HashMap::new();
+        for neighbor in neighbors.iter().filter(|n| n.ip_address.parse::<Ipv4Addr>().is_ok()) {
+            let Some(mac) = &neighbor.mac_address else {
+                continue;
+            };
+            let entry = claims.entry((neighbor.interface.clone(), mac.clone())).or_default();
+            entry.0.insert(neighbor.ip_address.clone());
+            entry.1 |= is_gateway(neighbor);
+        }
+        for ((interface, mac), (ips, includes_gateway)) in claims {
+            if ips.len() < 2 {
+                continue;
+            }
+            let key = (interface.clone(), mac.clone());
+            if let Some((alerted, last_seen)) = state.shared.get_mut(&key) {
+                if *alerted == ips {
+                    *last_seen = now;
+                    continue;
+                }
+            }
+            alerts.push(Alert::new(
+                AlertKind::SharedMac,
+                if includes_gateway { RiskLevel::High } else { RiskLevel::Medium },
+                format!("{} on {} answers for {} addresses", mac, interface, ips.len()),
+                vec![
+                    format!("MAC: {}", describe_mac(&mac)),
+                    format!("Addresses: {}", ips.iter().cloned().collect::<Vec<_>>().join(", ")),
+                ],
+            ));
+            state.shared.insert(key, (ips, now));
+        }
+
+        state.macs.retain(|_, (_, last_seen)| now.duration_since(*last_seen) < NEIGHBOR_RETENTION);
+        state.shared.retain(|_, (_, last_seen)| now.duration_since(*last_seen) < NEIGHBOR_RETENTION);
+        // Changes older than the window can no longer add up to a flap
+        state
+            .changes
+            .retain(|_, changes| changes.back().is_some_and(|at| now.duration_since(*at) <= FLAP_WINDOW));
+
+        alerts
+    }
+}
+
+fn describe_mac(mac: &str) -> String {
+    match OuiRegistry::global().lookup(mac) {
+        Some(vendor) => format!("{} ({})", mac, vendor),
+        None => mac.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn neighbor(ip: &str, mac: &str) -> Neighbor {
+        Neighbor {
+            ip_address: ip.to_string(),
+            mac_address: Some(mac.to_string()),
+            interface: "eth0".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn kinds(alerts: &[Alert]) -> Vec<AlertKind> {
+        alerts.iter().map(|alert| alert.kind).collect()
+    }
+
+    #[test]
+    fn flags_flapping_and_shared_macs() {
+        let watch = NeighborWatch::new();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert!(watch.observe_at(&[neighbor("10.0.0.5", "02:00:00:00:00:01")], None, at(0)).is_empty());
+        assert!(watch.observe_at(&[neighbor("10.0.0.5", "02:00:00:00:00:02")], None, at(10)).is_empty());
+        assert!(watch.observe_at(&[neighbor("10.0.0.5", "02:00:00:00:00:01")], None, at(20)).is_empty());
+        let alerts = watch.observe_at(&[neighbor("10.0.0.5", "02:00:00:00:00:02")], None, at(30));
+        assert_eq!(kinds(&alerts), vec![AlertKind::MacFlapping]);
+        assert_eq!(alerts[0].severity, RiskLevel::Medium);
+
+        let both = [neighbor("10.0.0.5", "02:00:00:00:00:02"), neighbor("10.0.0.6", "02:00:00:00:00:02")];
+        assert_eq!(kinds(&watch.observe_at(&both, None, at(40))), vec![AlertKind::SharedMac]);
+        assert!(watch.observe_at(&both, None, at(50)).is_empty());
+    }
+
+    #[test]
+    fn forgets_neighbors_gone_past_the_retention() {
+        let watch = NeighborWatch::new();
+        let start = Instant::now();
+        let both = [neighbor("10.0.0.5", "02:00:00:00:00:01"), neighbor("10.0.0.6", "02:00:00:00:00:01")];
+
+        assert_eq!(kinds(&watch.observe_at(&both, None, start)), vec![AlertKind::SharedMac]);
+        assert!(watch
+            .observe_at(&[neighbor("10.0.0.5", "02:00:00:00:00:02")], None, start + Duration::from_secs(1))
+            .is_empty());
+
+        // Briefly gone: still remembered, so nothing new to say
+        let back = start + Duration::from_secs(60);
+        assert!(watch.observe_at(&[], None, back).is_empty());
+        assert!(watch.observe_at(&[neighbor("10.0.0.5", "02:00:00:00:00:02")], None, back).is_empty());
+        {
+            let state = watch.state.lock().unwrap();
+            assert_eq!(state.macs.len(), 2);
+            assert_eq!(state.changes.len(), 1);
+            assert_eq!(state.shared.len(), 1);
+        }
+
+        let later = back + NEIGHBOR_RETENTION;
+        assert!(watch.observe_at(&[], None, later).is_empty());
+        {
+            let state = watch.state.lock().unwrap();
+            assert!(state.macs.is_empty());
+            assert!(state.changes.is_empty());
+            assert!(state.shared.is_empty());
+        }
+
+        // A returning host starts from scratch
+        let alerts = watch.observe_at(&both, None, later + Duration::from_secs(1));
+        assert_eq!(kinds(&alerts), vec![AlertKind::SharedMac]);
+    }
+}
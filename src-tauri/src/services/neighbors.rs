@@ -0,0 +1,277 @@
+use super::oui::OuiRegistry;
+use crate::models::{parse_ip_address, Connection, Neighbor};
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+const PROC_NET_ARP: &str = "/proc/net/arp";
+
+// linux/if_arp.h
+const ATF_COM: u32 = 0x02;
+const ATF_PERM: u32 = 0x04;
+
+/// The kernel's neighbor table: IPv4 and IPv6 entries over netlink, plus
+/// anything `/proc/net/arp` lists that the dump did not. MACs are tagged
+/// with their vendor.
+pub fn read_neighbors() -> Result<Vec<Neighbor>, String> {
+    let (mut neighbors, netlink_ok) = match sys::netlink_neighbors() {
+        Ok(neighbors) => (neighbors, true),
+        Err(e) => {
+            tracing::debug!("Neighbor dump failed, falling back to {}: {}", PROC_NET_ARP, e);
+            (Vec::new(), false)
+        }
+    };
+
+    match read_proc_arp() {
+        Ok(arp) => {
+            for entry in arp {
+                let known = neighbors
+                    .iter()
+                    .any(|n| n.ip_address == entry.ip_address && n.interface == entry.interface);
+                if !known {
+                    neighbors.push(entry);
+                }
+            }
+        }
+        Err(e) if !netlink_ok => return Err(e),
+        Err(e) => tracing::debug!("{}", e),
+    }
+
+    let registry = OuiRegistry::global();
+    for neighbor in neighbors.iter_mut() {
+        neighbor.vendor = neighbor
+            .mac_address
+            .as_deref()
+            .and_then(|mac| registry.lookup(mac))
+            .map(str::to_string);
+    }
+
+    neighbors.sort_by(|a, b| a.interface.cmp(&b.interface).then_with(|| a.ip_address.cmp(&b.ip_address)));
+    Ok(neighbors)
+}
+
+/// Attaches the neighbor entry to live connections whose remote address is
+/// directly on a local link.
+pub fn annotate(connections: &mut [Connection], neighbors: &[Neighbor]) {
+    let by_ip: HashMap<IpAddr, &Neighbor> = neighbors
+        .iter()
+        .filter_map(|neighbor| Some((neighbor.ip_address.parse().ok()?, neighbor)))
+        .collect();
+
+    for conn in connections.iter_mut() {
+        if !conn.is_host_socket() {
+            continue;
+        }
+        conn.neighbor = parse_ip_address(&conn.remote_addr)
+            .and_then(|remote| by_ip.get(&remote))
+            .map(|neighbor| (*neighbor).clone());
+    }
+}
+
+/// `IP address  HW type  Flags  HW address  Mask  Device`
+fn read_proc_arp() -> Result<Vec<Neighbor>, String> {
+    let content = std::fs::read_to_string(PROC_NET_ARP).map_err(|e| format!("Failed to read {}: {}", PROC_NET_ARP, e))?;
+
+    Ok(content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 6 {
+                return None;
+            }
+            let flags = u32::from_str_radix(fields[2].trim_start_matches("0x"), 16).ok()?;
+            let state = if flags & ATF_PERM != 0 {
+                "permanent"
+            } else if flags & ATF_COM != 0 {
+                "reachable" // /proc/net/arp does not distinguish stale entries
+            } else {
+                "incomplete"
+            };
+
+            Some(Neighbor {
+                ip_address: fields[0].to_string(),
+                mac_address: normalize_mac(fields[3]),
+                interface: fields[5].to_string(),
+                state: state.to_string(),
+                ..Default::default()
+            })
+        })
+        .collect())
+}
+
+fn normalize_mac(mac: &str) -> Option<String> {
+    let mac = mac.to_lowercase();
+    (!mac.is_empty() && mac != "00:00:00:00:00:00").then_some(mac)
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use super::super::inet_diag::{align, read_u32};
+    use crate::models::Neighbor;
+    use std::collections::HashMap;
+    use std::io;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+    // linux/netlink.h, linux/rtnetlink.h and linux/neighbour.h
+    const NETLINK_ROUTE: libc::c_int = 0;
+    const RTM_NEWNEIGH: u16 = 28;
+    const RTM_GETNEIGH: u16 = 30;
+    const NLM_F_REQUEST: u16 = 0x01;
+    const NLM_F_DUMP: u16 = 0x300;
+    const NLMSG_ERROR: u16 = 2;
+    const NLMSG_DONE: u16 = 3;
+    const NDA_DST: u16 = 1;
+    const NDA_LLADDR: u16 = 2;
+    const NTF_ROUTER: u8 = 0x80;
+    const NUD_NOARP: u16 = 0x40;
+
+    const NLMSG_HEADER_LEN: usize = 16;
+    /// `ndmsg`: family, padding, ifindex, state, flags and type.
+    const NDMSG_LEN: usize = 12;
+
+    /// Dumps the IPv4 and IPv6 neighbor tables over NETLINK_ROUTE.
+    pub fn netlink_neighbors() -> Result<Vec<Neighbor>, String> {
+        let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, NETLINK_ROUTE) };
+        if fd < 0 {
+            return Err(format!("Failed to open rtnetlink socket: {}", io::Error::last_os_error()));
+        }
+        let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let mut request = Vec::with_capacity(NLMSG_HEADER_LEN + NDMSG_LEN);
+        request.extend_from_slice(&((NLMSG_HEADER_LEN + NDMSG_LEN) as u32).to_ne_bytes());
+        request.extend_from_slice(&RTM_GETNEIGH.to_ne_bytes());
+        request.extend_from_slice(&(NLM_F_REQUEST | NLM_F_DUMP).to_ne_bytes());
+        request.extend_from_slice(&[0; 8]); // sequence number and port id
+        request.extend_from_slice(&[0; NDMSG_LEN]); // AF_UNSPEC: every family
+
+        let sent = unsafe { libc::send(socket.as_raw_fd(), request.as_ptr() as *const libc::c_void, request.len(), 0) };
+        if sent < 0 {
+            return Err(format!("Failed to send neighbor dump request: {}", io::Error::last_os_error()));
+        }
+
+        let mut neighbors = Vec::new();
+        let mut names = HashMap::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            let len = unsafe { libc::recv(socket.as_raw_fd(), buffer.as_mut_ptr() as *mut libc::c_void, buffer.len(), 0) };
+            if len < 0 {
+                return Err(format!("Failed to read neighbor dump: {}", io::Error::last_os_error()));
+            }
+
+            let mut data = &buffer[..len as usize];
+            while data.len() >= NLMSG_HEADER_LEN {
+                let message_len = read_u32(data, 0) as usize;
+                let message_type = u16::from_ne_bytes([data[4], data[5]]);
+                if message_len < NLMSG_HEADER_LEN || message_len > data.len() {
+                    return Err("Malformed neighbor dump".to_string());
+                }
+
+                match message_type {
+                    NLMSG_DONE => return Ok(neighbors),
+                    NLMSG_ERROR => {
+                        if message_len < NLMSG_HEADER_LEN + 4 {
+                            return Err("Malformed neighbor dump error".to_string());
+                        }
+                        let errno = -(read_u32(data, NLMSG_HEADER_LEN) as i32);
+                        return Err(format!("Neighbor dump failed: {}", io::Error::from_raw_os_error(errno)));
+                    }
+                    RTM_NEWNEIGH => {
+                        if let Some(neighbor) = parse_ndmsg(&data[NLMSG_HEADER_LEN..message_len], &mut names) {
+                            neighbors.push(neighbor);
+                        }
+                    }
+                    _ => {}
+                }
+                data = &data[align(message_len).min(data.len())..];
+            }
+        }
+    }
+
+    fn parse_ndmsg(payload: &[u8], names: &mut HashMap<u32, String>) -> Option<Neighbor> {
+        if payload.len() < NDMSG_LEN {
+            return None;
+        }
+        let family = i32::from(payload[0]);
+        let ifindex = read_u32(payload, 4);
+        let state = u16::from_ne_bytes([payload[8], payload[9]]);
+        let flags = payload[10];
+        if state & NUD_NOARP != 0 {
+            // Multicast, broadcast and point-to-point entries are not real neighbors
+            return None;
+        }
+
+        let mut ip = None;
+        let mut mac = None;
+        let mut attributes = &payload[NDMSG_LEN..];
+        while attributes.len() >= 4 {
+            let attribute_len = u16::from_ne_bytes([attributes[0], attributes[1]]) as usize;
+            let attribute_type = u16::from_ne_bytes([attributes[2], attributes[3]]);
+            if attribute_len < 4 || attribute_len > attributes.len() {
+                break;
+            }
+            let value = &attributes[4..attribute_len];
+            match attribute_type {
+                NDA_DST => ip = parse_address(family, value),
+                NDA_LLADDR => {
+                    mac = Some(value.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":"));
+                }
+                _ => {}
+            }
+            attributes = &attributes[align(attribute_len).min(attributes.len())..];
+        }
+
+        let interface = names
+            .entry(ifindex)
+            .or_insert_with(|| interface_name(ifindex))
+            .clone();
+
+        Some(Neighbor {
+            ip_address: ip?.to_string(),
+            mac_address: mac.and_then(|mac| super::normalize_mac(&mac)),
+            interface,
+            state: state_name(state).to_string(),
+            is_router: flags & NTF_ROUTER != 0,
+            ..Default::default()
+        })
+    }
+
+    fn parse_address(family: i32, value: &[u8]) -> Option<IpAddr> {
+        match family {
+            libc::AF_INET => <[u8; 4]>::try_from(value).ok().map(|b| IpAddr::V4(Ipv4Addr::from(b))),
+            libc::AF_INET6 => <[u8; 16]>::try_from(value).ok().map(|b| IpAddr::V6(Ipv6Addr::from(b))),
+            _ => None,
+        }
+    }
+
+    /// NUD_* states from linux/neighbour.h.
+    fn state_name(state: u16) -> &'static str {
+        match state {
+            0x01 => "incomplete",
+            0x02 => "reachable",
+            0x04 => "stale",
+            0x08 => "delay",
+            0x10 => "probe",
+            0x20 => "failed",
+            0x80 => "permanent",
+            _ => "unknown",
+        }
+    }
+
+    fn interface_name(ifindex: u32) -> String {
+        let mut name = [0 as libc::c_char; libc::IF_NAMESIZE];
+        if unsafe { libc::if_indextoname(ifindex, name.as_mut_ptr()) }.is_null() {
+            return ifindex.to_string();
+        }
+        unsafe { std::ffi::CStr::from_ptr(name.as_ptr()) }.to_string_lossy().into_owned()
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use crate::models::Neighbor;
+
+    pub fn netlink_neighbors() -> Result<Vec<Neighbor>, String> {
+        Err("Neighbor table is only available on Linux".to_string())
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// The `(hex)` lines of the IEEE MA-L registry, as fetched by
+/// `scripts/update-registries.sh`.
+#[cfg(netwatch_ieee_registry)]
+const EMBEDDED_OUIS: &str = include_str!("../../resources/ieee-oui.txt");
+/// Common vendors only, for builds made without fetching the full registry.
+#[cfg(not(netwatch_ieee_registry))]
+const EMBEDDED_OUIS: &str = include_str!("../../resources/common-oui.txt");
+const EMBEDDED_IS_FULL_REGISTRY: bool = cfg!(netwatch_ieee_registry);
+const USER_OUI_FILE: &str = "manuf";
+
+/// `<app data>/manuf`, checked before the system databases.
+static USER_OUI_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// MAC prefix to vendor name lookup, loaded once per process.
+pub struct OuiRegistry {
+    vendors: HashMap<[u8; 3], String>,
+}
+
+impl OuiRegistry {
+    pub fn global() -> &'static OuiRegistry {
+        static REGISTRY: OnceLock<OuiRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(OuiRegistry::load)
+    }
+
+    /// Lets a full registry dropped into the app data directory take
+    /// precedence. Must be called before the first lookup to have an effect.
+    pub fn set_app_data_dir(dir: &Path) {
+        let _ = USER_OUI_PATH.set(dir.join(USER_OUI_FILE));
+    }
+
+    /// A registry in the app data directory wins, so vendors can be updated
+    /// without a rebuild; the host's databases are only consulted when the
+    /// build embeds the common subset.
+    fn load() -> Self {
+        let user_path = USER_OUI_PATH.get().map(|path| path.display().to_string());
+        let system_paths = if EMBEDDED_IS_FULL_REGISTRY { &[][..] } else { SYSTEM_OUI_PATHS };
+        for path in user_path.iter().map(String::as_str).chain(system_paths.iter().copied()) {
+            if let Ok(content) = std::fs::read_to_string(path) {
+                let registry = Self::parse(&content);
+                if !registry.vendors.is_empty() {
+                    tracing::debug!("Loaded {} OUI vendors from {}", registry.vendors.len(), path);
+                    return registry;
+                }
+            }
+        }
+
+        if !EMBEDDED_IS_FULL_REGISTRY {
+            tracing::info!("No OUI database found, naming only common vendors from the embedded subset");
+        }
+        Self::parse(EMBEDDED_OUIS)
+    }
+
+    /// Parses Wireshark's `manuf` format (`00:00:0C<tab>Cisco[<tab>Cisco Systems, Inc]`),
+    /// the IEEE `oui.txt` format (`00-00-0C   (hex)<tab>Cisco Systems, Inc`),
+    /// arp-scan's `ieee-oui.txt` (`00000C<tab>Cisco Systems, Inc`) and nmap's
+    /// `nmap-mac-prefixes` (`00000C Cisco Systems`).
+    /// Prefixes narrower than a full OUI (`/28`, `/36`) are skipped.
+    fn parse(content: &str) -> Self {
+        let mut vendors = HashMap::new();
+
+        for line in content.lines() {
+            if line.starts_with('#') {
+                continue;
+            }
+            if !line.contains('\t') {
+                // nmap separates the prefix from the vendor with a space
+                if let Some((prefix, vendor)) = line.split_once(' ') {
+                    if let Some(oui) = parse_oui(prefix) {
+                        vendors.entry(oui).or_insert_with(|| vendor.trim().to_string());
+                    }
+                }
+                continue;
+            }
+            let mut fields = line.split('\t').map(str::trim).filter(|f| !f.is_empty());
+            let Some(prefix) = fields.next() else {
+                continue;
+            };
+            let (prefix, ieee_format) = match prefix.strip_suffix("(hex)") {
+                Some(prefix) => (prefix.trim(), true),
+                None => (prefix, false),
+            };
+            let Some(oui) = parse_oui(prefix) else {
+                continue;
+            };
+            // manuf lines carry a short name and then the full one, which
+            // older releases wrote as a `# ` comment
+            let vendor = if ieee_format { fields.next() } else { fields.next_back() };
+            if let Some(vendor) = vendor.map(|v| v.trim_start_matches('#').trim()) {
+                vendors.entry(oui).or_insert_with(|| vendor.to_string());
+            }
+        }
+
+        Self { vendors }
+    }
+
+    /// Vendor for a MAC address in `aa:bb:cc:dd:ee:ff` form. Locally
+    /// administered addresses (randomized Wi-Fi MACs, containers, VMs) have
+    /// no vendor and are reported as such.
+    pub fn lookup(&self, mac: &str) -> Option<&str> {
+        let oui = parse_oui(mac.get(..8)?)?;
+        if oui[0] & 0x02 != 0 {
+            return Some("Locally administered");
+        }
+        self.vendors.get(&oui).map(|vendor| vendor.as_str())
+    }
+}
+
+/// `00:00:0C`, `00-00-0C` or `00000C`.
+fn parse_oui(prefix: &str) -> Option<[u8; 3]> {
+    let hex: String = prefix.chars().filter(|c| *c != ':' && *c != '-').collect();
+    if hex.len() != 6 {
+        return None;
+    }
+    let value = u32::from_str_radix(&hex, 16).ok()?;
+    let [_, a, b, c] = value.to_be_bytes();
+    Some([a, b, c])
+}
+
+const SYSTEM_OUI_PATHS: &[&str] = &[
+    "/usr/share/wireshark/manuf",
+    "/usr/share/hwdata/oui.txt",
+    "/usr/share/ieee-data/oui.txt",
+    "/usr/share/misc/oui.txt",
+    "/usr/share/arp-scan/ieee-oui.txt",
+    "/usr/share/nmap/nmap-mac-prefixes",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_supported_format() {
+        let registry = OuiRegistry::parse(
+            "# Wireshark manuf\n\
+             00:00:0C\tCisco\tCisco Systems, Inc\n\
+             00:03:93\tApple\t# Apple, Inc.\n\
+             00:1B:C5:00:00:00/36\tConvergi\tConverging Systems Inc.\n\
+             00-50-56   (hex)\t\tVMware, Inc.\n\
+             B8:27:EB\tRaspberr\n\
+             F4F5D8\tGoogle, Inc.\n\
+             DCA632 Raspberry Pi Trading\n",
+        );
+
+        assert_eq!(registry.lookup("00:00:0c:12:34:56"), Some("Cisco Systems, Inc"));
+        assert_eq!(registry.lookup("00:03:93:aa:bb:cc"), Some("Apple, Inc."));
+        assert_eq!(registry.lookup("00:50:56:01:02:03"), Some("VMware, Inc."));
+        assert_eq!(registry.lookup("b8:27:eb:01:02:03"), Some("Raspberr"));
+        assert_eq!(registry.lookup("f4:f5:d8:01:02:03"), Some("Google, Inc."));
+        assert_eq!(registry.lookup("dc:a6:32:01:02:03"), Some("Raspberry Pi Trading"));
+        // Narrower MA-S blocks aren't full OUIs
+        assert_eq!(registry.lookup("00:1b:c5:00:00:01"), None);
+        assert_eq!(registry.vendors.len(), 6);
+    }
+
+    #[test]
+    fn reports_locally_administered_addresses() {
+        let registry = OuiRegistry::parse(EMBEDDED_OUIS);
+
+        assert_eq!(registry.lookup("02:42:ac:11:00:02"), Some("Locally administered"));
+        assert_eq!(registry.lookup("00:00:0c:12:34:56").map(|vendor| vendor.starts_with("Cisco")), Some(true));
+        assert_eq!(registry.lookup("00:00"), None);
+    }
+}
@@ -0,0 +1,105 @@
+use super::dns_message::{parse_message, RecordData, RCODE_NO_ERROR};
+use crate::models::{parse_ip_address, Connection};
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+/// Answers remembered per address; older ones are dropped first.
+const MAX_NAMES_PER_ADDRESS: usize = 16;
+
+/// Allowance for the answer being timestamped just after the connection
+/// it produced, e.g. when packets from two interfaces are merged.
+const CLOCK_SKEW_MS: u64 = 1000;
+
+/// IP to hostname map learned from DNS responses seen on the wire, so a
+/// connection can be labelled with the name its client actually asked for.
+#[derive(Debug, Default)]
+pub struct PassiveDnsMap {
+    entries: HashMap<IpAddr, Vec<Resolution>>,
+}
+
+#[derive(Debug, Clone)]
+struct Resolution {
+    seen_at: u64, // Unix timestamp in milliseconds
+    name: String,
+}
+
+impl PassiveDnsMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Records the address answers of a DNS response payload. Each address is
+    /// mapped to the question name, so CNAME chains label the CDN edge with
+    /// the name the client looked up rather than the CDN's own host.
+    pub fn observe(&mut self, seen_at: u64, payload: &[u8]) {
+        let Ok(message) = parse_message(payload) else {
+            return;
+        };
+        if !message.is_response || message.rcode != RCODE_NO_ERROR {
+            return;
+        }
+        let Some(question) = message.questions.first() else {
+            return;
+        };
+
+        for record in &message.answers {
+            let ip = match record.data {
+                RecordData::A(v4) => IpAddr::V4(v4),
+                RecordData::Aaaa(v6) => IpAddr::V6(v6),
+                _ => continue,
+            };
+            self.insert(ip, seen_at, &question.name);
+        }
+    }
+
+    fn insert(&mut self, ip: IpAddr, seen_at: u64, name: &str) {
+        let resolutions = self.entries.entry(ip).or_default();
+        match resolutions.iter_mut().find(|r| r.name == name) {
+            Some(existing) => existing.seen_at = existing.seen_at.max(seen_at),
+            None => resolutions.push(Resolution {
+                seen_at,
+                name: name.to_string(),
+            }),
+        }
+
+        resolutions.sort_by_key(|r| r.seen_at);
+        if resolutions.len() > MAX_NAMES_PER_ADDRESS {
+            resolutions.remove(0);
+        }
+    }
+
+    /// The most recent name `ip` was resolved from at or before `at`.
+    pub fn lookup(&self, ip: IpAddr, at: u64) -> Option<&str> {
+        self.entries
+            .get(&ip)?
+            .iter()
+            .rev()
+            .find(|r| r.seen_at <= at.saturating_add(CLOCK_SKEW_MS))
+            .map(|r| r.name.as_str())
+    }
+
+    /// Drops answers seen before `cutoff`; bounds memory during live capture.
+    pub fn prune_before(&mut self, cutoff: u64) {
+        self.entries.retain(|_, resolutions| {
+            resolutions.retain(|r| r.seen_at >= cutoff);
+            !resolutions.is_empty()
+        });
+    }
+
+    /// Fills `resolved_name` from the answers seen before each connection started.
+    pub fn annotate(&self, connections: &mut [Connection]) {
+        for conn in connections.iter_mut() {
+            let Some(ip) = parse_ip_address(&conn.remote_addr) else {
+                continue;
+            };
+            let started_at = conn.flow.as_ref().map_or(conn.captured_at, |flow| flow.first_seen);
+            if let Some(name) = self.lookup(ip, started_at) {
+                conn.resolved_name = Some(name.to_string());
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use crate::models::ProcessDetails;
+use std::path::Path;
+
+/// Reads a process's name, executable, command line and owner from
+/// `/proc/<pid>`.
+pub fn read_process(pid: u32) -> Result<ProcessDetails, String> {
+    let dir = Path::new("/proc").join(pid.to_string());
+    let status = std::fs::read_to_string(dir.join("status"))
+        .map_err(|e| format!("Failed to read process {}: {}", pid, e))?;
+    let field = |name: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+            .map(str::trim)
+    };
+
+    Ok(ProcessDetails {
+        pid,
+        name: field("Name").unwrap_or("unknown").to_string(),
+        executable: std::fs::read_link(dir.join("exe"))
+            .ok()
+            .map(|exe| exe.to_string_lossy().into_owned()),
+        // NUL-separated, with a trailing NUL
+        command_line: std::fs::read(dir.join("cmdline"))
+            .map(|cmdline| {
+                cmdline
+                    .split(|b| *b == 0)
+                    .filter(|arg| !arg.is_empty())
+                    .map(|arg| String::from_utf8_lossy(arg).into_owned())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        parent_pid: field("PPid").and_then(|ppid| ppid.parse().ok()),
+        // Real, effective, saved and filesystem UIDs
+        user_id: field("Uid").and_then(|uids| uids.split_whitespace().next()?.parse().ok()),
+        ..Default::default()
+    })
+}
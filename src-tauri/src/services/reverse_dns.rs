@@ -0,0 +1,334 @@
+use super::dns_message::{build_ptr_query, parse_message, RecordData, RCODE_NO_ERROR, RCODE_NX_DOMAIN};
+use crate::models::{parse_ip_address, AddressClass, Connection, ReverseDnsSettings};
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::net::UdpSocket;
+use tokio::sync::Semaphore;
+
+/// PTR lookup enricher. Connections are only ever labelled from the cache;
+/// misses are resolved in the background and show up on a later poll.
+#[derive(Clone)]
+pub struct ReverseDnsResolver {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    settings: RwLock<ReverseDnsSettings>,
+    semaphore: RwLock<Arc<Semaphore>>,
+    cache: Mutex<HashMap<IpAddr, CacheEntry>>,
+    pending: Mutex<HashSet<IpAddr>>,
+}
+
+struct CacheEntry {
+    hostname: Option<String>, // None is a cached negative answer
+    inserted_at: Instant,
+    expires_at: Instant,
+}
+
+/// Outcome of a single lookup: the name and, when known, the record TTL.
+type LookupResult = Result<Option<(String, Option<u32>)>, String>;
+
+impl ReverseDnsResolver {
+    pub fn new(settings: ReverseDnsSettings) -> Self {
+        let permits = settings.max_concurrent_lookups.max(1);
+        Self {
+            inner: Arc::new(Inner {
+                settings: RwLock::new(settings),
+                semaphore: RwLock::new(Arc::new(Semaphore::new(permits))),
+                cache: Mutex::new(HashMap::new()),
+                pending: Mutex::new(HashSet::new()),
+            }),
+        }
+    }
+
+    pub fn configure(&self, settings: ReverseDnsSettings) {
+        let mut current = self.inner.settings.write().unwrap();
+        if current.server != settings.server {
+            // Answers from a different resolver may disagree; start over
+            self.inner.cache.lock().unwrap().clear();
+        }
+        if current.max_concurrent_lookups != settings.max_concurrent_lookups {
+            *self.inner.semaphore.write().unwrap() = Arc::new(Semaphore::new(settings.max_concurrent_lookups.max(1)));
+        }
+        *current = settings;
+    }
+
+    /// Fills `hostname` from the cache and queues lookups for unknown or
+    /// expired addresses. Never waits on the network.
+    pub fn annotate(&self, connections: &mut [Connection]) {
+        let settings = self.inner.settings.read().unwrap().clone();
+        if !settings.enabled {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut to_resolve = HashSet::new();
+        {
+            let cache = self.inner.cache.lock().unwrap();
+            for conn in connections.iter_mut() {
+                if !worth_resolving(conn.remote_class) {
+                    continue;
+                }
+                let Some(ip) = parse_ip_address(&conn.remote_addr) else {
+                    continue;
+                };
+
+                match cache.get(&ip) {
+                    Some(entry) => {
+                        // Stale entries keep serving until the refresh lands
+                        conn.hostname = entry.hostname.clone();
+                        if entry.expires_at <= now {
+                            to_resolve.insert(ip);
+                        }
+                    }
+                    None => {
+                        to_resolve.insert(ip);
+                    }
+                }
+            }
+        }
+
+        for ip in to_resolve {
+            self.schedule(ip, &settings);
+        }
+    }
+
+    fn schedule(&self, ip: IpAddr, settings: &ReverseDnsSettings) {
+        if !self.inner.pending.lock().unwrap().insert(ip) {
+            return;
+        }
+
+        let inner = self.inner.clone();
+        let semaphore = self.inner.semaphore.read().unwrap().clone();
+        let settings = settings.clone();
+
+        tauri::async_runtime::spawn(async move {
+            if let Ok(_permit) = semaphore.acquire_owned().await {
+                let timeout = Duration::from_millis(settings.timeout_ms.max(1));
+                let result = match tokio::time::timeout(timeout, lookup(ip, settings.server.as_deref())).await {
+                    Ok(result) => result,
+                    Err(_) => Err("timed out".to_string()),
+                };
+                inner.store(ip, result, &settings);
+            }
+            inner.pending.lock().unwrap().remove(&ip);
+        });
+    }
+}
+
+impl Inner {
+    fn store(&self, ip: IpAddr, result: LookupResult, settings: &ReverseDnsSettings) {
+        let (hostname, ttl_secs) = match result {
+            Ok(Some((name, ttl))) => {
+                // Honour short record TTLs, but not below a sane floor
+                let ttl = ttl
+                    .map(|t| u64::from(t).max(30).min(settings.positive_ttl_secs))
+                    .unwrap_or(settings.positive_ttl_secs);
+                (Some(name), ttl)
+            }
+            Ok(None) => (None, settings.negative_ttl_secs),
+            Err(e) => {
+                tracing::debug!("Reverse DNS lookup for {} failed: {}", ip, e);
+                (None, settings.negative_ttl_secs)
+            }
+        };
+
+        let now = Instant::now();
+        let mut cache = self.cache.lock().unwrap();
+        cache.insert(
+            ip,
+            CacheEntry {
+                hostname,
+                inserted_at: now,
+                expires_at: now + Duration::from_secs(ttl_secs),
+            },
+        );
+
+        if cache.len() > settings.max_cache_entries {
+            cache.retain(|_, entry| entry.expires_at > now);
+        }
+        while cache.len() > settings.max_cache_entries {
+            let oldest = cache.iter().min_by_key(|(_, entry)| entry.inserted_at).map(|(ip, _)| *ip);
+            match oldest {
+                Some(oldest) => cache.remove(&oldest),
+                None => break,
+            };
+        }
+    }
+}
+
+fn worth_resolving(class: AddressClass) -> bool {
+    matches!(
+        class,
+        AddressClass::Public | AddressClass::Private | AddressClass::Cgnat | AddressClass::Reserved
+    )
+}
+
+async fn lookup(ip: IpAddr, server: Option<&str>) -> LookupResult {
+    match server {
+        Some(server) => query_server(ip, server).await,
+        None => {
+            // getnameinfo blocks, so keep it off the async workers
+            let result = tokio::task::spawn_blocking(move || dns_lookup::lookup_addr(&ip))
+                .await
+                .map_err(|e| format!("Lookup task failed: {}", e))?;
+            Ok(result.ok().map(|name| (name, None)))
+        }
+    }
+}
+
+/// Sends a PTR query straight to `server` over UDP.
+async fn query_server(ip: IpAddr, server: &str) -> LookupResult {
+    let server: SocketAddr = match server.parse::<SocketAddr>() {
+        Ok(addr) => addr,
+        Err(_) => {
+            let ip = parse_ip_address(server).ok_or_else(|| format!("Invalid DNS server address: {}", server))?;
+            SocketAddr::new(ip, 53)
+        }
+    };
+
+    let bind_addr = if server.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+    let socket = UdpSocket::bind(bind_addr)
+        .await
+        .map_err(|e| format!("Failed to bind DNS socket: {}", e))?;
+    socket
+        .connect(server)
+        .await
+        .map_err(|e| format!("Failed to reach DNS server {}: {}", server, e))?;
+
+    let id = uuid::Uuid::new_v4().as_u128() as u16;
+    socket
+        .send(&build_ptr_query(id, ip))
+        .await
+        .map_err(|e| format!("Failed to send DNS query: {}", e))?;
+
+    let mut buffer = [0u8; 1500];
+    loop {
+        let len = socket
+            .recv(&mut buffer)
+            .await
+            .map_err(|e| format!("Failed to receive DNS response: {}", e))?;
+
+        let message = match parse_message(&buffer[..len]) {
+            Ok(message) if message.is_response && message.id == id => message,
+            // Stray or spoofed datagram; keep waiting for ours
+            _ => continue,
+        };
+
+        return match message.rcode {
+            RCODE_NO_ERROR => Ok(message.answers.into_iter().find_map(|record| match record.data {
+                RecordData::Ptr(name) => Some((name, Some(record.ttl))),
+                _ => None,
+            })),
+            RCODE_NX_DOMAIN => Ok(None),
+            rcode => Err(format!("DNS server answered with rcode {}", rcode)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const RESOLVED: &str = "192.0.2.10";
+    const UNKNOWN: &str = "192.0.2.20";
+
+    /// Answers PTR queries for `RESOLVED` with `host.example.net` (after a
+    /// reply with the wrong id) and everything else with NXDOMAIN.
+    fn spawn_server() -> (SocketAddr, Arc<AtomicUsize>) {
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+        let addr = socket.local_addr().unwrap();
+        let queries = Arc::new(AtomicUsize::new(0));
+        let counter = queries.clone();
+
+        std::thread::spawn(move || {
+            let mut buffer = [0u8; 512];
+            while let Ok((len, peer)) = socket.recv_from(&mut buffer) {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let query = &buffer[..len];
+                let Ok(message) = parse_message(query) else {
+                    continue;
+                };
+
+                let mut response = query.to_vec();
+                if message.questions[0].name == "10.2.0.192.in-addr.arpa" {
+                    let mut spoofed = response.clone();
+                    spoofed[0] ^= 0xff;
+                    spoofed[2..4].copy_from_slice(&0x8180u16.to_be_bytes());
+                    socket.send_to(&spoofed, peer).unwrap();
+
+                    response[2..4].copy_from_slice(&0x8180u16.to_be_bytes());
+                    response[6..8].copy_from_slice(&1u16.to_be_bytes());
+                    response.extend_from_slice(&[0xc0, 12, 0, 12, 0, 1]);
+                    response.extend_from_slice(&600u32.to_be_bytes());
+                    let target = b"\x04host\x07example\x03net\x00";
+                    response.extend_from_slice(&(target.len() as u16).to_be_bytes());
+                    response.extend_from_slice(target);
+                } else {
+                    response[2..4].copy_from_slice(&0x8183u16.to_be_bytes());
+                }
+                socket.send_to(&response, peer).unwrap();
+            }
+        });
+
+        (addr, queries)
+    }
+
+    fn connections() -> Vec<Connection> {
+        [RESOLVED, UNKNOWN]
+            .into_iter()
+            .map(|remote| Connection {
+                remote_addr: remote.to_string(),
+                remote_class: AddressClass::Reserved,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn resolves_through_the_configured_server_and_caches_both_answers() {
+        let (server, queries) = spawn_server();
+        let settings = ReverseDnsSettings {
+            server: Some(server.to_string()),
+            ..Default::default()
+        };
+        let resolver = ReverseDnsResolver::new(settings.clone());
+
+        // The first poll only queues the lookups
+        let mut first = connections();
+        resolver.annotate(&mut first);
+        assert!(first.iter().all(|conn| conn.hostname.is_none()));
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while resolver.inner.cache.lock().unwrap().len() < 2 || !resolver.inner.pending.lock().unwrap().is_empty() {
+            assert!(Instant::now() < deadline, "lookups did not complete");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        {
+            let cache = resolver.inner.cache.lock().unwrap();
+            let resolved = &cache[&RESOLVED.parse::<IpAddr>().unwrap()];
+            assert_eq!(resolved.hostname.as_deref(), Some("host.example.net"));
+            assert_eq!(resolved.expires_at - resolved.inserted_at, Duration::from_secs(600));
+
+            let unknown = &cache[&UNKNOWN.parse::<IpAddr>().unwrap()];
+            assert_eq!(unknown.hostname, None);
+            assert_eq!(
+                unknown.expires_at - unknown.inserted_at,
+                Duration::from_secs(settings.negative_ttl_secs)
+            );
+        }
+
+        // Both answers are served from the cache without asking again
+        let mut second = connections();
+        resolver.annotate(&mut second);
+        assert_eq!(second[0].hostname.as_deref(), Some("host.example.net"));
+        assert_eq!(second[1].hostname, None);
+        assert!(resolver.inner.pending.lock().unwrap().is_empty());
+        assert_eq!(queries.load(Ordering::SeqCst), 2);
+    }
+}
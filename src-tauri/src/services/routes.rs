@@ -0,0 +1,560 @@
+use crate::models::{parse_ip_address, Connection, EgressRoute};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+const PROC_NET_ROUTE: &str = "/proc/net/route";
+const PROC_NET_IPV6_ROUTE: &str = "/proc/net/ipv6_route";
+
+const RTF_UP: u32 = 0x0001;
+const RTF_REJECT: u32 = 0x0200;
+const RT_TABLE_MAIN: u32 = 254;
+
+/// The kernel's main routing table, for longest-prefix lookups of where
+/// traffic to a remote address leaves the host.
+pub struct RouteTable {
+    routes: Vec<Route>,
+}
+
+struct Route {
+    network: IpAddr,
+    prefix_len: u8,
+    gateway: Option<IpAddr>,
+    interface: String,
+    metric: u32,
+    reject: bool, // unreachable/prohibit routes; traffic never leaves
+    source: Option<(IpAddr, u8)>, // IPv6 source-specific routes (`ip -6 route ... from`)
+}
+
+impl RouteTable {
+    /// Reads `/proc/net/route`, plus `/proc/net/ipv6_route` when IPv6 is enabled.
+    pub fn load() -> Result<Self, String> {
+        let ipv4 = std::fs::read_to_string(PROC_NET_ROUTE)
+            .map_err(|e| format!("Failed to read {}: {}", PROC_NET_ROUTE, e))?;
+        let mut routes = parse_ipv4_routes(&ipv4);
+        if let Ok(ipv6) = std::fs::read_to_string(PROC_NET_IPV6_ROUTE) {
+            routes.extend(parse_ipv6_routes(&ipv6));
+        }
+        Ok(Self { routes })
+    }
+
+    /// Most specific route covering `ip`, lowest metric first among equals.
+    /// Source-specific routes only apply when `source` falls in their prefix.
+    fn lookup(&self, ip: IpAddr, source: Option<IpAddr>) -> Option<&Route> {
+        self.routes
+            .iter()
+            .filter(|route| route.contains(ip) && route.matches_source(source))
+            .max_by_key(|route| (route.prefix_len, std::cmp::Reverse(route.metric)))
+            .filter(|route| !route.reject)
+    }
+
+    /// Next hops of the default routes, with the interface each leaves through.
+    pub fn default_gateways(&self) -> Vec<(IpAddr, &str)> {
+        self.routes
+            .iter()
+            .filter(|route| route.prefix_len == 0 && !route.reject)
+            .filter_map(|route| Some((route.gateway?, route.interface.as_str())))
+            .collect()
+    }
+
+    fn egress(&self, remote: IpAddr, source: Option<IpAddr>) -> Option<EgressRoute> {
+        self.lookup(remote, source).map(|route| EgressRoute {
+            interface: route.interface.clone(),
+            gateway: route.gateway.map(|gateway| gateway.to_string()),
+            destination: format!("{}/{}", route.network, route.prefix_len),
+            table: Some(RT_TABLE_MAIN),
+        })
+    }
+}
+
+/// Sets the egress interface and next hop on each live connection with a
+/// routable remote address. Listeners and loopback peers have none.
+///
+/// Each remote is resolved by asking the kernel (RTM_GETROUTE), so policy
+/// routing (`ip rule`, as used by wg-quick and Tailscale) picks the same table
+/// real traffic would. When rtnetlink is unavailable, falls back to the main
+/// table read by [`RouteTable::load`].
+pub fn annotate(connections: &mut [Connection], fallback: Option<&RouteTable>) {
+    let mut socket = sys::RouteSocket::open()
+        .inspect_err(|e| tracing::debug!("Falling back to the main routing table: {}", e))
+        .ok();
+    let mut resolved: HashMap<(IpAddr, Option<IpAddr>), Option<EgressRoute>> = HashMap::new();
+
+    for conn in connections.iter_mut() {
+        if !conn.is_host_socket() {
+            continue;
+        }
+        let Some(remote) = parse_ip_address(&conn.remote_addr) else {
+            continue;
+        };
+        if remote.is_unspecified() || remote.is_loopback() {
+            continue;
+        }
+        // Source-based rules match on the bound address; unbound sockets have none yet
+        let source = parse_ip_address(&conn.local_addr).filter(|local| !local.is_unspecified());
+
+        conn.route = resolved
+            .entry((remote, source))
+            .or_insert_with(|| {
+                if let Some(route_socket) = &mut socket {
+                    match route_socket.get(remote, source) {
+                        Ok(route) => return route,
+                        Err(e) => tracing::debug!("Route lookup for {} failed: {}", remote, e),
+                    }
+                }
+                fallback.and_then(|table| table.egress(remote, source))
+            })
+            .clone();
+    }
+}
+
+impl Route {
+    fn contains(&self, ip: IpAddr) -> bool {
+        prefix_contains(self.network, self.prefix_len, ip)
+    }
+
+    fn matches_source(&self, source: Option<IpAddr>) -> bool {
+        match (self.source, source) {
+            (None, _) => true,
+            (Some((network, prefix_len)), Some(source)) => prefix_contains(network, prefix_len, source),
+            (Some(_), None) => false,
+        }
+    }
+}
+
+fn prefix_contains(network: IpAddr, prefix_len: u8, ip: IpAddr) -> bool {
+    match (network, ip) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) => {
+            let mask = u32::MAX.checked_shl(32 - u32::from(prefix_len)).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) => {
+            let mask = u128::MAX.checked_shl(128 - u32::from(prefix_len)).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
+
+/// `Iface Destination Gateway Flags RefCnt Use Metric Mask ...`, addresses as
+/// hex words in network byte order read as host integers.
+fn parse_ipv4_routes(content: &str) -> Vec<Route> {
+    let address = |field: &str| u32::from_str_radix(field, 16).ok().map(|word| Ipv4Addr::from(word.to_ne_bytes()));
+
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 8 {
+                return None;
+            }
+            let flags = u32::from_str_radix(fields[3], 16).ok()?;
+            if flags & RTF_UP == 0 {
+                return None;
+            }
+            let gateway = address(fields[2])?;
+
+            Some(Route {
+                network: IpAddr::V4(address(fields[1])?),
+                prefix_len: u32::from(address(fields[7])?).count_ones() as u8,
+                gateway: (!gateway.is_unspecified()).then_some(IpAddr::V4(gateway)),
+                interface: fields[0].to_string(),
+                metric: fields[6].parse().ok()?,
+                reject: flags & RTF_REJECT != 0,
+                source: None,
+            })
+        })
+        .collect()
+}
+
+/// `dest plen src plen nexthop metric refcnt use flags iface`, all hex.
+fn parse_ipv6_routes(content: &str) -> Vec<Route> {
+    let address = |field: &str| u128::from_str_radix(field, 16).ok().map(Ipv6Addr::from);
+
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 {
+                return None;
+            }
+            let flags = u32::from_str_radix(fields[8], 16).ok()?;
+            if flags & RTF_UP == 0 {
+                return None;
+            }
+            let gateway = address(fields[4])?;
+            let source_len = u8::from_str_radix(fields[3], 16).ok()?;
+
+            Some(Route {
+                network: IpAddr::V6(address(fields[0])?),
+                prefix_len: u8::from_str_radix(fields[1], 16).ok()?,
+                gateway: (!gateway.is_unspecified()).then_some(IpAddr::V6(gateway)),
+                interface: fields[9].to_string(),
+                metric: u32::from_str_radix(fields[5], 16).ok()?,
+                reject: flags & RTF_REJECT != 0,
+                source: match source_len {
+                    0 => None,
+                    len => Some((IpAddr::V6(address(fields[2])?), len)),
+                },
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use super::super::inet_diag::{align, read_u32};
+    use crate::models::EgressRoute;
+    use std::io;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+    // linux/netlink.h and linux/rtnetlink.h
+    const NETLINK_ROUTE: libc::c_int = 0;
+    const RTM_NEWROUTE: u16 = 24;
+    const RTM_GETROUTE: u16 = 26;
+    const NLM_F_REQUEST: u16 = 0x01;
+    const NLMSG_ERROR: u16 = 2;
+    const RTA_DST: u16 = 1;
+    const RTA_SRC: u16 = 2;
+    const RTA_OIF: u16 = 4;
+    const RTA_GATEWAY: u16 = 5;
+    const RTA_MULTIPATH: u16 = 9;
+    const RTA_TABLE: u16 = 15;
+    const RTN_UNICAST: u8 = 1;
+    /// Report the table the policy rules picked; main is assumed otherwise.
+    const RTM_F_LOOKUP_TABLE: u32 = 0x1000;
+    /// Report the matched FIB entry (its prefix and table) instead of a /32 clone.
+    const RTM_F_FIB_MATCH: u32 = 0x2000;
+
+    const NLMSG_HEADER_LEN: usize = 16;
+    /// `rtmsg`: family, dst_len, src_len, tos, table, protocol, scope, type and flags.
+    const RTMSG_LEN: usize = 12;
+    /// `rtnexthop`: len, flags, hops and ifindex, followed by attributes.
+    const RTNH_LEN: usize = 8;
+
+    /// One rtnetlink socket reused for every lookup in a poll.
+    pub struct RouteSocket {
+        socket: OwnedFd,
+        sequence: u32,
+        buffer: Vec<u8>,
+    }
+
+    impl RouteSocket {
+        pub fn open() -> Result<Self, String> {
+            let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, NETLINK_ROUTE) };
+            if fd < 0 {
+                return Err(format!("Failed to open rtnetlink socket: {}", io::Error::last_os_error()));
+            }
+            Ok(Self {
+                socket: unsafe { OwnedFd::from_raw_fd(fd) },
+                sequence: 0,
+                buffer: vec![0u8; 8 * 1024],
+            })
+        }
+
+        /// The route the kernel would pick for traffic to `destination` from
+        /// `source`, like `ip route get`. `Ok(None)` when it is unreachable.
+        pub fn get(&mut self, destination: IpAddr, source: Option<IpAddr>) -> Result<Option<EgressRoute>, String> {
+            // A source of the other family would be rejected; drop it rather than fail
+            let source = source.filter(|source| source.is_ipv4() == destination.is_ipv4());
+            self.sequence = self.sequence.wrapping_add(1);
+
+            let family = if destination.is_ipv4() { libc::AF_INET } else { libc::AF_INET6 } as u8;
+            let host_len = if destination.is_ipv4() { 32 } else { 128 };
+            let mut rtmsg = [0u8; RTMSG_LEN];
+            rtmsg[0] = family;
+            rtmsg[1] = host_len;
+            rtmsg[2] = if source.is_some() { host_len } else { 0 };
+            rtmsg[8..12].copy_from_slice(&(RTM_F_LOOKUP_TABLE | RTM_F_FIB_MATCH).to_ne_bytes());
+
+            let mut request = Vec::with_capacity(NLMSG_HEADER_LEN + RTMSG_LEN + 2 * 20);
+            request.extend_from_slice(&[0; 4]); // length, filled in below
+            request.extend_from_slice(&RTM_GETROUTE.to_ne_bytes());
+            request.extend_from_slice(&NLM_F_REQUEST.to_ne_bytes());
+            request.extend_from_slice(&self.sequence.to_ne_bytes());
+            request.extend_from_slice(&[0; 4]); // port id
+            request.extend_from_slice(&rtmsg);
+            push_address(&mut request, RTA_DST, destination);
+            if let Some(source) = source {
+                push_address(&mut request, RTA_SRC, source);
+            }
+            let len = request.len() as u32;
+            request[..4].copy_from_slice(&len.to_ne_bytes());
+
+            let fd = self.socket.as_raw_fd();
+            let sent = unsafe { libc::send(fd, request.as_ptr() as *const libc::c_void, request.len(), 0) };
+            if sent < 0 {
+                return Err(format!("Failed to send route request: {}", io::Error::last_os_error()));
+            }
+
+            loop {
+                let len = unsafe { libc::recv(fd, self.buffer.as_mut_ptr() as *mut libc::c_void, self.buffer.len(), 0) };
+                if len < 0 {
+                    return Err(format!("Failed to read route reply: {}", io::Error::last_os_error()));
+                }
+                let data = &self.buffer[..len as usize];
+                if data.len() < NLMSG_HEADER_LEN {
+                    return Err("Malformed route reply".to_string());
+                }
+                let message_len = read_u32(data, 0) as usize;
+                let message_type = u16::from_ne_bytes([data[4], data[5]]);
+                if message_len < NLMSG_HEADER_LEN || message_len > data.len() {
+                    return Err("Malformed route reply".to_string());
+                }
+                if read_u32(data, 8) != self.sequence {
+                    continue; // a late reply to an earlier request
+                }
+
+                return match message_type {
+                    NLMSG_ERROR => {
+                        if message_len < NLMSG_HEADER_LEN + 4 {
+                            return Err("Malformed route reply error".to_string());
+                        }
+                        match -(read_u32(data, NLMSG_HEADER_LEN) as i32) {
+                            libc::ENETUNREACH | libc::EHOSTUNREACH | libc::EACCES => Ok(None),
+                            errno => Err(format!("Route lookup failed: {}", io::Error::from_raw_os_error(errno))),
+                        }
+                    }
+                    RTM_NEWROUTE => Ok(parse_rtmsg(&data[NLMSG_HEADER_LEN..message_len], destination)),
+                    _ => Err(format!("Unexpected route reply type {}", message_type)),
+                };
+            }
+        }
+    }
+
+    fn push_address(request: &mut Vec<u8>, attribute_type: u16, ip: IpAddr) {
+        let octets = match ip {
+            IpAddr::V4(ip) => ip.octets().to_vec(),
+            IpAddr::V6(ip) => ip.octets().to_vec(),
+        };
+        request.extend_from_slice(&((4 + octets.len()) as u16).to_ne_bytes());
+        request.extend_from_slice(&attribute_type.to_ne_bytes());
+        request.extend_from_slice(&octets);
+        request.resize(align(request.len()), 0);
+    }
+
+    fn parse_rtmsg(payload: &[u8], destination: IpAddr) -> Option<EgressRoute> {
+        if payload.len() < RTMSG_LEN {
+            return None;
+        }
+        let family = i32::from(payload[0]);
+        let prefix_len = payload[1];
+        if payload[7] != RTN_UNICAST {
+            return None; // local, blackhole, unreachable and prohibit routes
+        }
+
+        let mut table = u32::from(payload[4]);
+        let mut network = None;
+        let mut ifindex = None;
+        let mut gateway = None;
+        for (attribute_type, value) in attributes(&payload[RTMSG_LEN..]) {
+            match attribute_type {
+                RTA_DST => network = parse_address(family, value),
+                RTA_OIF if value.len() >= 4 => ifindex = Some(read_u32(value, 0)),
+                RTA_GATEWAY => gateway = parse_address(family, value),
+                RTA_TABLE if value.len() >= 4 => table = read_u32(value, 0),
+                RTA_MULTIPATH if value.len() >= RTNH_LEN && ifindex.is_none() => {
+                    // ECMP: report the first next hop
+                    let nexthop_len = (u16::from_ne_bytes([value[0], value[1]]) as usize).min(value.len());
+                    ifindex = Some(read_u32(value, 4));
+                    gateway = attributes(&value[RTNH_LEN..nexthop_len.max(RTNH_LEN)])
+                        .find(|(attribute_type, _)| *attribute_type == RTA_GATEWAY)
+                        .and_then(|(_, value)| parse_address(family, value));
+                }
+                _ => {}
+            }
+        }
+
+        // The default route carries no RTA_DST
+        let network = network.unwrap_or(match destination {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        });
+        Some(EgressRoute {
+            interface: interface_name(ifindex?),
+            gateway: gateway.map(|gateway| gateway.to_string()),
+            destination: format!("{}/{}", network, prefix_len),
+            table: Some(table),
+        })
+    }
+
+    fn attributes(mut data: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+        std::iter::from_fn(move || {
+            if data.len() < 4 {
+                return None;
+            }
+            let attribute_len = u16::from_ne_bytes([data[0], data[1]]) as usize;
+            let attribute_type = u16::from_ne_bytes([data[2], data[3]]);
+            if attribute_len < 4 || attribute_len > data.len() {
+                return None;
+            }
+            let value = &data[4..attribute_len];
+            data = &data[align(attribute_len).min(data.len())..];
+            Some((attribute_type, value))
+        })
+    }
+
+    fn parse_address(family: i32, value: &[u8]) -> Option<IpAddr> {
+        match family {
+            libc::AF_INET => <[u8; 4]>::try_from(value).ok().map(|b| IpAddr::V4(Ipv4Addr::from(b))),
+            libc::AF_INET6 => <[u8; 16]>::try_from(value).ok().map(|b| IpAddr::V6(Ipv6Addr::from(b))),
+            _ => None,
+        }
+    }
+
+    fn interface_name(ifindex: u32) -> String {
+        let mut name = [0 as libc::c_char; libc::IF_NAMESIZE];
+        if unsafe { libc::if_indextoname(ifindex, name.as_mut_ptr()) }.is_null() {
+            return ifindex.to_string();
+        }
+        unsafe { std::ffi::CStr::from_ptr(name.as_ptr()) }.to_string_lossy().into_owned()
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use crate::models::EgressRoute;
+    use std::net::IpAddr;
+
+    pub struct RouteSocket;
+
+    impl RouteSocket {
+        pub fn open() -> Result<Self, String> {
+            Err("Route lookups are only available on Linux".to_string())
+        }
+
+        pub fn get(&mut self, _destination: IpAddr, _source: Option<IpAddr>) -> Result<Option<EgressRoute>, String> {
+            Err("Route lookups are only available on Linux".to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(address: &str) -> IpAddr {
+        address.parse().unwrap()
+    }
+
+    // Fixtures come from a little-endian host; /proc/net/route prints in host order
+    #[cfg(target_endian = "little")]
+    const IPV4_ROUTES: &str = "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n\
+        eth0\t00000000\t0101A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0\n\
+        wlan0\t00000000\t0100000A\t0003\t0\t0\t600\t00000000\t0\t0\t0\n\
+        eth0\t0001A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0\n\
+        wg0\t0002000A\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0\n\
+        wg0\t0000000A\t00000000\t0001\t0\t0\t0\t0000FFFF\t0\t0\t0\n\
+        lo\t000A000A\t00000000\t0201\t0\t0\t0\t00FFFFFF\t0\t0\t0\n\
+        eth1\t00C0A8C0\t00000000\t0000\t0\t0\t0\t00FFFFFF\t0\t0\t0\n";
+
+    const IPV6_ROUTES: &str = "\
+        20010db8000000000000000000000000 20 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000001 00000000 00000001     eth0\n\
+        fe800000000000000000000000000000 40 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000001 00000000 00000001     eth0\n\
+        00000000000000000000000000000000 00 00000000000000000000000000000000 00 fe800000000000000000000000000001 00000400 00000002 00000000 00000003     eth0\n\
+        00000000000000000000000000000000 00 20010db8000200000000000000000000 30 fe800000000000000000000000000002 00000400 00000001 00000000 00000003     wg0\n\
+        00000000000000000000000000000001 80 00000000000000000000000000000000 00 00000000000000000000000000000000 00000000 00000002 00000000 80200001       lo\n\
+        20010db8ffff00000000000000000000 30 00000000000000000000000000000000 00 00000000000000000000000000000000 00000000 00000001 00000000 00000201       lo\n";
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn parses_ipv4_routes() {
+        let routes = parse_ipv4_routes(IPV4_ROUTES);
+
+        // The route that isn't up is skipped
+        assert_eq!(routes.len(), 6);
+        assert_eq!(routes[0].network, ip("0.0.0.0"));
+        assert_eq!(routes[0].prefix_len, 0);
+        assert_eq!(routes[0].gateway, Some(ip("192.168.1.1")));
+        assert_eq!(routes[0].metric, 100);
+        assert_eq!(routes[2].network, ip("192.168.1.0"));
+        assert_eq!(routes[2].prefix_len, 24);
+        assert_eq!(routes[2].gateway, None);
+        assert!(routes[5].reject);
+    }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn more_specific_ipv4_routes_override_the_default() {
+        let table = RouteTable {
+            routes: parse_ipv4_routes(IPV4_ROUTES),
+        };
+
+        // Lowest metric default wins
+        let egress = table.egress(ip("93.184.216.34"), None).unwrap();
+        assert_eq!(egress.interface, "eth0");
+        assert_eq!(egress.gateway.as_deref(), Some("192.168.1.1"));
+        assert_eq!(egress.destination, "0.0.0.0/0");
+        assert_eq!(egress.table, Some(RT_TABLE_MAIN));
+
+        let egress = table.egress(ip("192.168.1.20"), None).unwrap();
+        assert_eq!((egress.interface.as_str(), egress.gateway), ("eth0", None));
+        assert_eq!(egress.destination, "192.168.1.0/24");
+
+        // The /24 beats the /16 it sits in
+        assert_eq!(table.egress(ip("10.0.2.7"), None).unwrap().destination, "10.0.2.0/24");
+        assert_eq!(table.egress(ip("10.0.9.7"), None).unwrap().destination, "10.0.0.0/16");
+
+        // Reject routes drop the traffic instead of falling back to the default
+        assert!(table.egress(ip("10.0.10.1"), None).is_none());
+        assert_eq!(table.egress(ip("192.168.192.1"), None).unwrap().interface, "eth0");
+        assert_eq!(
+            table.default_gateways(),
+            vec![(ip("192.168.1.1"), "eth0"), (ip("10.0.0.1"), "wlan0")]
+        );
+    }
+
+    #[test]
+    fn parses_ipv6_routes() {
+        let routes = parse_ipv6_routes(IPV6_ROUTES);
+
+        assert_eq!(routes.len(), 6);
+        assert_eq!(routes[0].network, ip("2001:db8::"));
+        assert_eq!(routes[0].prefix_len, 32);
+        assert_eq!(routes[0].metric, 256);
+        assert_eq!(routes[2].network, ip("::"));
+        assert_eq!(routes[2].prefix_len, 0);
+        assert_eq!(routes[2].gateway, Some(ip("fe80::1")));
+        assert_eq!(routes[2].source, None);
+        assert_eq!(routes[3].source, Some((ip("2001:db8:2::"), 48)));
+        assert_eq!(routes[4].interface, "lo");
+        assert!(routes[5].reject);
+    }
+
+    #[test]
+    fn picks_ipv6_routes_by_prefix_and_source() {
+        let table = RouteTable {
+            routes: parse_ipv6_routes(IPV6_ROUTES),
+        };
+
+        let egress = table.egress(ip("2606:4700::1111"), None).unwrap();
+        assert_eq!(egress.interface, "eth0");
+        assert_eq!(egress.gateway.as_deref(), Some("fe80::1"));
+        assert_eq!(egress.destination, "::/0");
+
+        // The source-specific default only carries traffic from its prefix
+        let egress = table.egress(ip("2606:4700::1111"), Some(ip("2001:db8:2::10"))).unwrap();
+        assert_eq!(egress.interface, "wg0");
+        assert_eq!(egress.gateway.as_deref(), Some("fe80::2"));
+        let egress = table.egress(ip("2606:4700::1111"), Some(ip("2001:db8:3::10"))).unwrap();
+        assert_eq!(egress.interface, "eth0");
+
+        assert_eq!(table.egress(ip("2001:db8:1::5"), None).unwrap().destination, "2001:db8::/32");
+        assert!(table.egress(ip("2001:db8:ffff::1"), None).is_none());
+        assert_eq!(table.default_gateways(), vec![(ip("fe80::1"), "eth0"), (ip("fe80::2"), "wg0")]);
+    }
+
+    #[test]
+    fn prefix_lengths_at_the_edges() {
+        assert!(prefix_contains(ip("0.0.0.0"), 0, ip("203.0.113.1")));
+        assert!(prefix_contains(ip("203.0.113.1"), 32, ip("203.0.113.1")));
+        assert!(!prefix_contains(ip("203.0.113.1"), 32, ip("203.0.113.2")));
+        assert!(prefix_contains(ip("::"), 0, ip("2001:db8::1")));
+        assert!(prefix_contains(ip("2001:db8::1"), 128, ip("2001:db8::1")));
+        assert!(!prefix_contains(ip("2001:db8::1"), 128, ip("2001:db8::2")));
+        // Families never match each other
+        assert!(!prefix_contains(ip("0.0.0.0"), 0, ip("::1")));
+        assert!(!prefix_contains(ip("::"), 0, ip("127.0.0.1")));
+    }
+}
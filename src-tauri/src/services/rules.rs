@@ -0,0 +1,68 @@
+use crate::models::{ContainerRules, RawSocketAllowlist, RouteRules, TlsRules};
+use std::path::Path;
+
+const RULES_DIR: &str = "rules";
+const TLS_FINGERPRINTS_FILE: &str = "tls-fingerprints.txt";
+const SNI_PATTERNS_FILE: &str = "sni-patterns.txt";
+const ROUTE_POLICY_FILE: &str = "route-policy.txt";
+const CONTAINER_RULES_FILE: &str = "containers.txt";
+const RAW_SOCKET_ALLOWLIST_FILE: &str = "raw-sockets.txt";
+
+/// Reads the TLS rules files from `<app data>/rules/`. They are re-read on
+/// every use so analysts can edit them without restarting; a missing file
+/// simply contributes no rules.
+pub fn load_tls_rules(app_data_dir: &Path) -> TlsRules {
+    let dir = app_data_dir.join(RULES_DIR);
+    let rules = TlsRules::parse(
+        &read_rules_file(&dir.join(TLS_FINGERPRINTS_FILE)),
+        &read_rules_file(&dir.join(SNI_PATTERNS_FILE)),
+    );
+    if !rules.is_empty() {
+        tracing::debug!("Loaded {} TLS rules from {}", rules.len(), dir.display());
+    }
+    rules
+}
+
+/// Reads `<app data>/rules/route-policy.txt`, the interfaces each process
+/// is expected to route through.
+pub fn load_route_rules(app_data_dir: &Path) -> RouteRules {
+    let path = app_data_dir.join(RULES_DIR).join(ROUTE_POLICY_FILE);
+    let rules = RouteRules::parse(&read_rules_file(&path));
+    if !rules.is_empty() {
+        tracing::debug!("Loaded {} route policies from {}", rules.len(), path.display());
+    }
+    rules
+}
+
+/// Reads `<app data>/rules/containers.txt`, the containers to flag by name,
+/// image, ID or runtime.
+pub fn load_container_rules(app_data_dir: &Path) -> ContainerRules {
+    let path = app_data_dir.join(RULES_DIR).join(CONTAINER_RULES_FILE);
+    let rules = ContainerRules::parse(&read_rules_file(&path));
+    if !rules.is_empty() {
+        tracing::debug!("Loaded {} container rules from {}", rules.len(), path.display());
+    }
+    rules
+}
+
+/// Reads `<app data>/rules/raw-sockets.txt`, the processes allowed to hold
+/// raw and packet sockets on top of the built-in ones.
+pub fn load_raw_socket_allowlist(app_data_dir: &Path) -> RawSocketAllowlist {
+    let path = app_data_dir.join(RULES_DIR).join(RAW_SOCKET_ALLOWLIST_FILE);
+    let allowlist = RawSocketAllowlist::parse(&read_rules_file(&path));
+    if !allowlist.is_empty() {
+        tracing::debug!("Loaded {} raw socket allowlist entries from {}", allowlist.len(), path.display());
+    }
+    allowlist
+}
+
+fn read_rules_file(path: &Path) -> String {
+    match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => {
+            tracing::warn!("Failed to read rules file {}: {}", path.display(), e);
+            String::new()
+        }
+    }
+}
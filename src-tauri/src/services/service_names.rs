@@ -0,0 +1,238 @@
+use crate::models::Connection;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// The IANA Service Name and Transport Protocol Port Number Registry, as
+/// fetched by `scripts/update-registries.sh`.
+#[cfg(netwatch_iana_registry)]
+const EMBEDDED_SERVICES: &str = include_str!("../../resources/service-names-port-numbers.csv");
+/// Common ports only, for builds made without fetching the full registry.
+#[cfg(not(netwatch_iana_registry))]
+const EMBEDDED_SERVICES: &str = include_str!("../../resources/common-services.txt");
+
+const IANA_CSV_HEADER: &str = "Service Name,Port Number,Transport Protocol";
+
+/// Port number to service name lookup, loaded once per process.
+pub struct ServiceRegistry {
+    names: HashMap<(u16, String), String>,
+}
+
+impl ServiceRegistry {
+    pub fn global() -> &'static ServiceRegistry {
+        static REGISTRY: OnceLock<ServiceRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(ServiceRegistry::load)
+    }
+
+    /// The host's services database wins where it names a port (it may carry
+    /// local additions); the embedded registry fills in everything else.
+    fn load() -> Self {
+        let mut registry = Self { names: HashMap::new() };
+
+        for path in system_services_paths() {
+            if let Ok(content) = std::fs::read_to_string(path) {
+                registry.extend(&content);
+                tracing::debug!("Loaded {} service names from {}", registry.names.len(), path);
+            }
+        }
+
+        let from_host = registry.names.len();
+        registry.extend(EMBEDDED_SERVICES);
+        tracing::debug!("Added {} service names from the embedded registry", registry.names.len() - from_host);
+
+        registry
+    }
+
+    /// Adds the entries of an IANA registry CSV export or an `/etc/services`
+    /// file; ports that already have a name keep it.
+    fn extend(&mut self, content: &str) {
+        if content.starts_with(IANA_CSV_HEADER) {
+            self.extend_from_iana_csv(content);
+        } else {
+            self.extend_from_services(content);
+        }
+    }
+
+    /// Parses the `/etc/services` format: `name port/proto [aliases...] [# comment]`.
+    fn extend_from_services(&mut self, content: &str) {
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let mut fields = line.split_whitespace();
+            let (Some(name), Some(port_proto)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            let Some((port, proto)) = port_proto.split_once('/') else {
+                continue;
+            };
+            let Ok(port) = port.parse::<u16>() else {
+                continue;
+            };
+
+            self.insert(port, proto, name);
+        }
+    }
+
+    /// Parses the registry's CSV export (`Service Name,Port Number,Transport
+    /// Protocol,Description,...`). Ports may be ranges (`6000-6063`); rows
+    /// without a name or a port are reserved or unassigned and skipped.
+    fn extend_from_iana_csv(&mut self, content: &str) {
+        for record in csv_records(content).into_iter().skip(1) {
+            let [name, ports, proto, ..] = record.as_slice() else {
+                continue;
+            };
+            if name.is_empty() || proto.is_empty() {
+                continue;
+            }
+            let (first, last) = ports.split_once('-').unwrap_or((ports, ports));
+            let (Ok(first), Ok(last)) = (first.trim().parse::<u16>(), last.trim().parse::<u16>()) else {
+                continue;
+            };
+
+            for port in first..=last {
+                self.insert(port, proto, name);
+            }
+        }
+    }
+
+    /// The first entry for a port is the canonical name.
+    fn insert(&mut self, port: u16, proto: &str, name: &str) {
+        self.names
+            .entry((port, proto.to_lowercase()))
+            .or_insert_with(|| name.to_string());
+    }
+
+    /// Looks up `port` for `protocol` ("TCP"/"UDP"), falling back to the
+    /// other transport since most registrations cover both.
+    pub fn lookup(&self, port: u16, protocol: &str) -> Option<&str> {
+        if port == 0 {
+            return None;
+        }
+
+        let proto = protocol.to_lowercase();
+        self.names
+            .get(&(port, proto.clone()))
+            .or_else(|| {
+                ["tcp", "udp"]
+                    .iter()
+                    .filter(|p| **p != proto)
+                    .find_map(|p| self.names.get(&(port, p.to_string())))
+            })
+            .map(|name| name.as_str())
+    }
+}
+
+fn system_services_paths() -> &'static [&'static str] {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            &["C:\\Windows\\System32\\drivers\\etc\\services"]
+        } else {
+            &["/etc/services"]
+        }
+    }
+}
+
+/// Splits RFC 4180 CSV into records: quoted fields may contain commas, line
+/// breaks and `""` escapes.
+fn csv_records(content: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => record.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    records
+}
+
+pub fn annotate_services(connections: &mut [Connection]) {
+    let registry = ServiceRegistry::global();
+
+    for conn in connections.iter_mut() {
+        conn.local_service = registry.lookup(conn.local_port, &conn.protocol).map(|s| s.to_string());
+        conn.remote_service = registry.lookup(conn.remote_port, &conn.protocol).map(|s| s.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> ServiceRegistry {
+        let mut registry = ServiceRegistry { names: HashMap::new() };
+        registry.extend(content);
+        registry
+    }
+
+    #[test]
+    fn parses_etc_services() {
+        let registry = parse(
+            "# comment\nssh\t\t22/tcp\nsmtp\t\t25/tcp\t\tmail\t# Simple Mail Transfer\n\ndomain\t\t53/udp\nbad\t\tx/tcp\n",
+        );
+
+        assert_eq!(registry.lookup(22, "TCP"), Some("ssh"));
+        assert_eq!(registry.lookup(25, "TCP"), Some("smtp"));
+        assert_eq!(registry.lookup(53, "UDP"), Some("domain"));
+        // Falls back to the other transport
+        assert_eq!(registry.lookup(53, "TCP"), Some("domain"));
+        assert_eq!(registry.lookup(0, "TCP"), None);
+        assert_eq!(registry.names.len(), 3);
+    }
+
+    #[test]
+    fn parses_iana_csv_export() {
+        let csv = "Service Name,Port Number,Transport Protocol,Description,Assignee,Contact,Registration Date,Modification Date,Reference,Service Code,Unauthorized Use Reported,Assignment Notes\r\n\
+            ,0,tcp,Reserved,[Jon_Postel],[Jon_Postel],,,,,,\r\n\
+            http,80,tcp,World Wide Web HTTP,[Tim_Berners_Lee],[Tim_Berners_Lee],,,,,,\"Defined TXT keys: u=<username> p=<password> path=<path to document>\"\r\n\
+            www,80,tcp,World Wide Web HTTP,,,,,,,,\r\n\
+            x11,6000-6002,tcp,\"X Window System, \"\"X11\"\"\nover TCP\",[Stephen_Gildea],,,,,,,\r\n\
+            domain,53,udp,Domain Name Server,,,,,,,,\r\n\
+            ,1025-65535,,Unassigned,,,,,,,,\r\n\
+            syslog-tls,6514,dccp,syslog over DTLS,,,,,,,,";
+        let registry = parse(csv);
+
+        assert_eq!(registry.lookup(80, "TCP"), Some("http"));
+        assert_eq!(registry.lookup(6000, "TCP"), Some("x11"));
+        assert_eq!(registry.lookup(6002, "TCP"), Some("x11"));
+        assert_eq!(registry.lookup(6003, "TCP"), None);
+        assert_eq!(registry.lookup(53, "UDP"), Some("domain"));
+        assert_eq!(registry.names.get(&(6514, "dccp".to_string())).map(String::as_str), Some("syslog-tls"));
+        assert_eq!(registry.lookup(0, "TCP"), None);
+        assert_eq!(registry.lookup(1025, "TCP"), None);
+    }
+
+    #[test]
+    fn host_names_take_precedence_over_the_embedded_registry() {
+        let mut registry = parse("webcache\t8080/tcp\n");
+        registry.extend(EMBEDDED_SERVICES);
+
+        assert_eq!(registry.lookup(8080, "TCP"), Some("webcache"));
+        assert_eq!(registry.lookup(22, "TCP"), Some("ssh"));
+        assert_eq!(registry.lookup(443, "TCP"), Some("https"));
+    }
+
+    #[test]
+    fn splits_quoted_csv_fields() {
+        let records = csv_records("a,\"b,c\",\"d\"\"e\"\r\n\"multi\nline\",,\n");
+
+        assert_eq!(records, vec![vec!["a", "b,c", "d\"e"], vec!["multi\nline", "", ""]]);
+    }
+}
@@ -0,0 +1,47 @@
+use crate::models::Settings;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Loads and persists [`Settings`]; managed as Tauri state.
+pub struct SettingsStore {
+    path: PathBuf,
+    settings: Mutex<Settings>,
+}
+
+impl SettingsStore {
+    pub fn load(app_data_dir: PathBuf) -> Self {
+        let path = app_data_dir.join("settings.json");
+
+        let settings = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid settings file {}: {}", path.display(), e);
+                Settings::default()
+            }),
+            Err(_) => Settings::default(),
+        };
+
+        Self {
+            path,
+            settings: Mutex::new(settings),
+        }
+    }
+
+    pub fn get(&self) -> Settings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    pub fn update(&self, settings: Settings) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create settings directory: {}", e))?;
+        }
+
+        let json_data = serde_json::to_string_pretty(&settings)
+            .map_err(|e| format!("JSON serialization error: {}", e))?;
+        std::fs::write(&self.path, json_data)
+            .map_err(|e| format!("Failed to write settings: {}", e))?;
+
+        *self.settings.lock().unwrap() = settings;
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use crate::models::{Connection, SystemdUnit};
+use std::collections::HashMap;
+
+/// Unit types whose cgroups hold processes.
+const PROCESS_UNIT_SUFFIXES: &[&str] = &[".service", ".scope", ".socket", ".mount", ".swap"];
+
+/// Tags each connection with the systemd unit of its owning process.
+pub fn annotate(connections: &mut [Connection]) {
+    let mut by_pid: HashMap<u32, Option<SystemdUnit>> = HashMap::new();
+
+    for conn in connections.iter_mut().filter(|conn| conn.pid != 0) {
+        conn.systemd_unit = by_pid.entry(conn.pid).or_insert_with(|| lookup(conn.pid)).clone();
+    }
+}
+
+pub fn lookup(pid: u32) -> Option<SystemdUnit> {
+    let cgroup = std::fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    parse_cgroup(&cgroup)
+}
+
+/// Reads the unit from systemd's own hierarchy: `name=systemd` on cgroup v1
+/// and hybrid hosts, the unified `0::` line on cgroup v2.
+fn parse_cgroup(content: &str) -> Option<SystemdUnit> {
+    let path_for = |wanted: &str| {
+        content.lines().find_map(|line| {
+            // hierarchy-ID:controller-list:cgroup-path
+            let mut fields = line.splitn(3, ':');
+            let _hierarchy = fields.next()?;
+            (fields.next()? == wanted).then(|| fields.next()).flatten()
+        })
+    };
+    parse_cgroup_path(path_for("name=systemd").or_else(|| path_for(""))?)
+}
+
+/// The innermost unit in the path. Units below `user@<uid>.service` belong
+/// to that user's manager; the manager itself is a system unit.
+fn parse_cgroup_path(path: &str) -> Option<SystemdUnit> {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let index = segments
+        .iter()
+        .rposition(|segment| PROCESS_UNIT_SUFFIXES.iter().any(|suffix| segment.ends_with(suffix)))?;
+
+    let parents = &segments[..index];
+    Some(SystemdUnit {
+        name: segments[index].to_string(),
+        slice: parents
+            .iter()
+            .rev()
+            .find(|segment| segment.ends_with(".slice"))
+            .map(|slice| slice.to_string()),
+        user_id: parents.iter().find_map(|segment| {
+            segment.strip_prefix("user@")?.strip_suffix(".service")?.parse().ok()
+        }),
+    })
+}
@@ -0,0 +1,55 @@
+use super::inet_diag::{align, dump, open_sock_diag, read_u32};
+use std::collections::HashMap;
+
+// linux/unix_diag.h
+const UDIAG_SHOW_PEER: u32 = 0x04;
+const UNIX_DIAG_PEER: u16 = 2;
+
+/// `unix_diag_req`: family, protocol, padding, states, inode, show flags and cookie.
+const UNIX_DIAG_REQ_LEN: usize = 24;
+/// `unix_diag_msg`: family, type, state, padding, inode and cookie.
+const UNIX_DIAG_MSG_LEN: usize = 16;
+
+/// Peer inodes of the connected unix sockets in the current network
+/// namespace, keyed by socket inode. `/proc/net/unix` does not expose peers.
+pub fn unix_peers_by_inode() -> Result<HashMap<u64, u64>, String> {
+    let socket = open_sock_diag()?;
+
+    let mut request = Vec::with_capacity(UNIX_DIAG_REQ_LEN);
+    request.push(libc::AF_UNIX as u8);
+    request.extend_from_slice(&[0; 3]); // protocol and padding
+    request.extend_from_slice(&u32::MAX.to_ne_bytes()); // every state
+    request.extend_from_slice(&0u32.to_ne_bytes()); // inode: no filter
+    request.extend_from_slice(&UDIAG_SHOW_PEER.to_ne_bytes());
+    request.extend_from_slice(&[0xff; 8]); // no cookie
+
+    let mut peers = HashMap::new();
+    dump(&socket, &request, |payload| {
+        if let Some((inode, peer)) = parse_unix_diag_msg(payload) {
+            peers.insert(inode, peer);
+        }
+    })?;
+    Ok(peers)
+}
+
+fn parse_unix_diag_msg(payload: &[u8]) -> Option<(u64, u64)> {
+    if payload.len() < UNIX_DIAG_MSG_LEN {
+        return None;
+    }
+    let inode = u64::from(read_u32(payload, 4));
+
+    let mut attributes = &payload[UNIX_DIAG_MSG_LEN..];
+    while attributes.len() >= 4 {
+        let attribute_len = u16::from_ne_bytes([attributes[0], attributes[1]]) as usize;
+        let attribute_type = u16::from_ne_bytes([attributes[2], attributes[3]]);
+        if attribute_len < 4 || attribute_len > attributes.len() {
+            break;
+        }
+        if attribute_type == UNIX_DIAG_PEER && attribute_len >= 8 {
+            let peer = u64::from(read_u32(attributes, 4));
+            return (peer != 0).then_some((inode, peer));
+        }
+        attributes = &attributes[align(attribute_len).min(attributes.len())..];
+    }
+    None
+}
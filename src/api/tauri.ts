@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
-import { Connection } from '../types/netwatch';
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
+import { Alert, BaselineStatus, CaptureDiagnostics, Connection, ConnectionInterval, ExposureReport, HistoryFilter, HistorySnapshot, Neighbor, NetworkInterface, ProcessDetails, Settings, TalkerWindow, TopTalkers } from '../types/netwatch';
 import { logger } from '../lib/logger';
 
 export async function getConnections(): Promise<Connection[]> {
@@ -12,7 +13,7 @@ export async function getConnections(): Promise<Connection[]> {
   }
 }
 
-export async function exportConnections(format: 'json' | 'csv', connections: Connection[]): Promise<string> {
+export async function exportConnections(format: 'json' | 'csv' | 'exposure', connections: Connection[]): Promise<string> {
   try {
     const filePath = await invoke<string>('export_connections', {
       format,
@@ -23,4 +24,173 @@ export async function exportConnections(format: 'json' | 'csv', connections: Con
     logger.error('Error exporting connections:', error);
     throw error;
   }
-}
\ No newline at end of file
+}
+
+export async function importCapture(path: string): Promise<Connection[]> {
+  try {
+    return await invoke<Connection[]>('import_capture', { path });
+  } catch (error) {
+    logger.error('Error importing capture file:', error);
+    throw error;
+  }
+}
+
+export async function getTopTalkers(window: TalkerWindow): Promise<TopTalkers> {
+  try {
+    return await invoke<TopTalkers>('get_top_talkers', { window });
+  } catch (error) {
+    logger.error('Error fetching top talkers:', error);
+    throw error;
+  }
+}
+
+export async function getInterfaces(): Promise<NetworkInterface[]> {
+  try {
+    return await invoke<NetworkInterface[]>('get_interfaces');
+  } catch (error) {
+    logger.error('Error fetching interfaces:', error);
+    throw error;
+  }
+}
+
+export async function getNeighbors(): Promise<Neighbor[]> {
+  try {
+    return await invoke<Neighbor[]>('get_neighbors');
+  } catch (error) {
+    logger.error('Error fetching neighbors:', error);
+    throw error;
+  }
+}
+
+export async function getProcessDetails(pid: number): Promise<ProcessDetails> {
+  try {
+    return await invoke<ProcessDetails>('get_process_details', { pid });
+  } catch (error) {
+    logger.error('Error fetching process details:', error);
+    throw error;
+  }
+}
+
+export async function getAlerts(): Promise<Alert[]> {
+  try {
+    return await invoke<Alert[]>('get_alerts');
+  } catch (error) {
+    logger.error('Error fetching alerts:', error);
+    throw error;
+  }
+}
+
+// Intervals overlapping from..to (Unix ms), most recent first
+export async function queryHistory(filter: HistoryFilter, from: number, to: number): Promise<ConnectionInterval[]> {
+  try {
+    return await invoke<ConnectionInterval[]>('query_history', { filter, from, to });
+  } catch (error) {
+    logger.error('Error querying connection history:', error);
+    throw error;
+  }
+}
+
+export async function getSnapshotAt(timestamp: number): Promise<HistorySnapshot> {
+  try {
+    return await invoke<HistorySnapshot>('get_snapshot_at', { timestamp });
+  } catch (error) {
+    logger.error('Error reconstructing connection snapshot:', error);
+    throw error;
+  }
+}
+
+export async function getBaselineStatus(): Promise<BaselineStatus> {
+  try {
+    return await invoke<BaselineStatus>('get_baseline_status');
+  } catch (error) {
+    logger.error('Error fetching baseline status:', error);
+    throw error;
+  }
+}
+
+// Discards any current baseline and learns a new one over the configured window
+export async function startBaselineLearning(): Promise<BaselineStatus> {
+  try {
+    return await invoke<BaselineStatus>('start_baseline_learning');
+  } catch (error) {
+    logger.error('Error starting baseline learning:', error);
+    throw error;
+  }
+}
+
+export async function exportBaseline(): Promise<string> {
+  try {
+    return await invoke<string>('export_baseline');
+  } catch (error) {
+    logger.error('Error exporting baseline:', error);
+    throw error;
+  }
+}
+
+export async function importBaseline(path: string): Promise<BaselineStatus> {
+  try {
+    return await invoke<BaselineStatus>('import_baseline', { path });
+  } catch (error) {
+    logger.error('Error importing baseline:', error);
+    throw error;
+  }
+}
+
+// Alerts are raised while connections are polled and pushed as they happen
+export async function onAlert(handler: (alert: Alert) => void): Promise<UnlistenFn> {
+  return listen<Alert>('alert-raised', (event) => handler(event.payload));
+}
+
+export async function startLiveCapture(iface: string): Promise<CaptureDiagnostics> {
+  try {
+    return await invoke<CaptureDiagnostics>('start_live_capture', { interface: iface });
+  } catch (error) {
+    logger.error('Error starting live capture:', error);
+    throw error;
+  }
+}
+
+export async function stopLiveCapture(): Promise<CaptureDiagnostics> {
+  try {
+    return await invoke<CaptureDiagnostics>('stop_live_capture');
+  } catch (error) {
+    logger.error('Error stopping live capture:', error);
+    throw error;
+  }
+}
+
+export async function getCaptureDiagnostics(): Promise<CaptureDiagnostics> {
+  try {
+    return await invoke<CaptureDiagnostics>('get_capture_diagnostics');
+  } catch (error) {
+    logger.error('Error fetching capture diagnostics:', error);
+    throw error;
+  }
+}
+
+export async function getExposureReport(): Promise<ExposureReport> {
+  try {
+    return await invoke<ExposureReport>('get_exposure_report');
+  } catch (error) {
+    logger.error('Error fetching exposure report:', error);
+    throw error;
+  }
+}
+
+export async function getSettings(): Promise<Settings> {
+  try {
+    return await invoke<Settings>('get_settings');
+  } catch (error) {
+    logger.error('Error fetching settings:', error);
+    throw error;
+  }
+}
+
+export async function updateSettings(settings: Settings): Promise<void> {
+  try {
+    await invoke('update_settings', { settings });
+  } catch (error) {
+    logger.error('Error updating settings:', error);
+    throw error;
+  }
+}
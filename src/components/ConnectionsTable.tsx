@@ -2,6 +2,7 @@ import { useNavigate } from 'react-router-dom';
 import { Connection } from '@/types/netwatch';
 import { RiskBadge } from './RiskBadge';
 import { formatDistanceToNow } from 'date-fns';
+import { formatRate } from '@/lib/format';
 
 interface ConnectionsTableProps {
   connections: Connection[];
@@ -42,25 +43,70 @@ export function ConnectionsTable({ connections }: ConnectionsTableProps) {
                 <div>
                   <p className="text-sm font-medium text-foreground">{conn.processName}</p>
                   <p className="text-xs text-muted-foreground">PID: {conn.pid}</p>
+                  {conn.systemdUnit && (
+                    <p className="text-xs text-muted-foreground">{conn.systemdUnit.name}</p>
+                  )}
+                  {conn.container && (
+                    <p className="text-xs text-muted-foreground">
+                      {conn.container.name ?? conn.container.id.slice(0, 12)}{conn.container.image && ` · ${conn.container.image}`}
+                    </p>
+                  )}
+                  {conn.namespace && !conn.namespace.isHost && (
+                    <p className="text-xs text-muted-foreground">netns: {conn.namespace.name}</p>
+                  )}
                 </div>
               </td>
               <td className="px-4 py-3">
                 <span className="text-sm text-foreground">{conn.protocol}</span>
+                {conn.unix && <p className="text-xs text-muted-foreground">{conn.unix.socketType}</p>}
+                {conn.raw && (
+                  <p className="text-xs text-muted-foreground">
+                    {conn.raw.protocol}{conn.protocol === 'PACKET' && ` on ${conn.raw.interface ?? 'all interfaces'}`}
+                  </p>
+                )}
               </td>
               <td className="px-4 py-3">
                 <span className="text-sm text-foreground font-mono">
-                  {conn.localAddr}:{conn.localPort}
+                  {conn.unix || conn.raw ? conn.localAddr || '(unnamed)' : `${conn.localAddr}:${conn.localPort}`}
                 </span>
+                {conn.localService && (
+                  <p className="text-xs text-muted-foreground">{conn.localService}</p>
+                )}
               </td>
               <td className="px-4 py-3">
                 <span className="text-sm text-foreground font-mono">
-                  {conn.remoteAddr}:{conn.remotePort}
+                  {conn.unix || conn.raw ? conn.remoteAddr || '(unnamed)' : `${conn.remoteAddr}:${conn.remotePort}`}
                 </span>
+                {conn.unix?.peerProcess && (
+                  <p className="text-xs text-muted-foreground">
+                    {conn.unix.peerProcess} ({conn.unix.peerPid})
+                  </p>
+                )}
+                {(conn.resolvedName || conn.hostname || conn.tls?.sni || conn.remoteService) && (
+                  <p className="text-xs text-muted-foreground">
+                    {[conn.resolvedName ?? conn.hostname ?? conn.tls?.sni, conn.remoteService].filter(Boolean).join(' · ')}
+                  </p>
+                )}
+                {conn.neighbor?.macAddress && (
+                  <p className="text-xs text-muted-foreground font-mono">
+                    {conn.neighbor.macAddress}{conn.neighbor.vendor && ` · ${conn.neighbor.vendor}`}
+                  </p>
+                )}
+                {conn.route && (
+                  <p className="text-xs text-muted-foreground">
+                    via {conn.route.interface}{conn.route.gateway && ` (${conn.route.gateway})`}{conn.route.table != null && conn.route.table !== 254 && ` · table ${conn.route.table}`}
+                  </p>
+                )}
               </td>
               <td className="px-4 py-3">
                 <span className="inline-flex items-center px-2 py-0.5 rounded text-xs font-medium bg-secondary text-secondary-foreground">
                   {conn.state}
                 </span>
+                {conn.tcpMetrics && ((conn.tcpMetrics.sendRate ?? 0) > 0 || (conn.tcpMetrics.receiveRate ?? 0) > 0) && (
+                  <div className="text-xs text-muted-foreground mt-1">
+                    ↑{formatRate(conn.tcpMetrics.sendRate ?? 0)} ↓{formatRate(conn.tcpMetrics.receiveRate ?? 0)}
+                  </div>
+                )}
               </td>
               <td className="px-4 py-3">
                 <span className="text-sm text-muted-foreground">
@@ -1,6 +1,6 @@
 import { useState } from 'react';
 import { Search, Download, ChevronDown } from 'lucide-react';
-import { Connection } from '@/types/netwatch';
+import { Connection, ConnectionDirection, connectionSearchText } from '@/types/netwatch';
 
 interface FiltersBarProps {
   onFiltersChange: (filters: FilterState) => void;
@@ -10,8 +10,9 @@ interface FiltersBarProps {
 
 export interface FilterState {
   search: string;
-  protocol: 'all' | 'TCP' | 'UDP';
+  protocol: 'all' | 'TCP' | 'UDP' | 'UNIX' | 'RAW' | 'ICMP' | 'PACKET';
   state: 'all' | 'ESTABLISHED' | 'LISTENING' | 'TIME_WAIT' | 'CLOSE_WAIT' | 'SYN_SENT';
+  direction: 'all' | ConnectionDirection;
   hideLocalhost: boolean;
   onlyEstablished: boolean;
   risk: 'all' | 'low' | 'medium' | 'high';
@@ -22,6 +23,7 @@ export function FiltersBar({ onFiltersChange, totalCount, filteredCount }: Filte
     search: '',
     protocol: 'all',
     state: 'all',
+    direction: 'all',
     hideLocalhost: false,
     onlyEstablished: false,
     risk: 'all',
@@ -43,7 +45,7 @@ export function FiltersBar({ onFiltersChange, totalCount, filteredCount }: Filte
             type="text"
             value={filters.search}
             onChange={(e) => updateFilter('search', e.target.value)}
-            placeholder="Search IP, port, or process..."
+            placeholder="Search IP, port, service, or process..."
             className="w-full h-9 pl-9 pr-4 bg-input border border-border rounded-lg text-sm text-foreground placeholder:text-muted-foreground focus:outline-none focus:ring-2 focus:ring-ring focus:border-transparent"
           />
         </div>
@@ -57,6 +59,10 @@ export function FiltersBar({ onFiltersChange, totalCount, filteredCount }: Filte
           <option value="all">All Protocols</option>
           <option value="TCP">TCP</option>
           <option value="UDP">UDP</option>
+          <option value="UNIX">Unix sockets</option>
+          <option value="RAW">Raw</option>
+          <option value="ICMP">ICMP</option>
+          <option value="PACKET">Packet</option>
         </select>
 
         {/* State */}
@@ -73,6 +79,18 @@ export function FiltersBar({ onFiltersChange, totalCount, filteredCount }: Filte
           <option value="SYN_SENT">Syn Sent</option>
         </select>
 
+        {/* Direction */}
+        <select
+          value={filters.direction}
+          onChange={(e) => updateFilter('direction', e.target.value as FilterState['direction'])}
+          className="h-9 px-3 bg-input border border-border rounded-lg text-sm text-foreground focus:outline-none focus:ring-2 focus:ring-ring"
+        >
+          <option value="all">All Directions</option>
+          <option value="inbound">Inbound</option>
+          <option value="outbound">Outbound</option>
+          <option value="listening">Listening</option>
+        </select>
+
         {/* Risk */}
         <select
           value={filters.risk}
@@ -130,8 +148,7 @@ export function applyFilters(connections: Connection[], filters: FilterState): C
     // Search
     if (filters.search) {
       const query = filters.search.toLowerCase();
-      const searchable = `${conn.processName} ${conn.pid} ${conn.localAddr}:${conn.localPort} ${conn.remoteAddr}:${conn.remotePort}`.toLowerCase();
-      if (!searchable.includes(query)) return false;
+      if (!connectionSearchText(conn).includes(query)) return false;
     }
 
     // Protocol
@@ -140,12 +157,15 @@ export function applyFilters(connections: Connection[], filters: FilterState): C
     // State
     if (filters.state !== 'all' && conn.state !== filters.state) return false;
 
+    // Direction
+    if (filters.direction !== 'all' && conn.direction !== filters.direction) return false;
+
     // Risk
     if (filters.risk !== 'all' && conn.risk !== filters.risk) return false;
 
     // Hide localhost
     if (filters.hideLocalhost) {
-      if (conn.localAddr === '127.0.0.1' || conn.remoteAddr === '127.0.0.1' || conn.remoteAddr === '0.0.0.0') {
+      if (conn.remoteClass === 'loopback' || conn.remoteClass === 'unspecified') {
         return false;
       }
     }
@@ -1,10 +1,12 @@
 import { useNavigate } from 'react-router-dom';
 import { RiskBadge } from './RiskBadge';
+import { formatRate } from '@/lib/format';
 
 interface ProcessRow {
   pid: number;
   name: string;
   count: number;
+  throughput?: number; // bytes per second, sent and received, over the last minute
   maxRisk: 'low' | 'medium' | 'high';
 }
 
@@ -28,6 +30,7 @@ export function ProcessesTable({ processes, maxRows = 8 }: ProcessesTableProps)
             <th className="text-left text-xs font-medium text-muted-foreground uppercase tracking-wider px-4 py-2">Process</th>
             <th className="text-left text-xs font-medium text-muted-foreground uppercase tracking-wider px-4 py-2">PID</th>
             <th className="text-left text-xs font-medium text-muted-foreground uppercase tracking-wider px-4 py-2">Connections</th>
+            <th className="text-left text-xs font-medium text-muted-foreground uppercase tracking-wider px-4 py-2">Throughput</th>
             <th className="text-left text-xs font-medium text-muted-foreground uppercase tracking-wider px-4 py-2">Risk</th>
           </tr>
         </thead>
@@ -47,6 +50,9 @@ export function ProcessesTable({ processes, maxRows = 8 }: ProcessesTableProps)
               <td className="px-4 py-2.5">
                 <span className="text-sm text-foreground">{proc.count}</span>
               </td>
+              <td className="px-4 py-2.5">
+                <span className="text-sm text-muted-foreground">{proc.throughput ? formatRate(proc.throughput) : '—'}</span>
+              </td>
               <td className="px-4 py-2.5">
                 <RiskBadge risk={proc.maxRisk} size="sm" />
               </td>
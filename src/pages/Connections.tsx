@@ -6,7 +6,7 @@ import { Pagination } from '@/components/Pagination';
 import { EmptyState } from '@/components/EmptyState';
 import { ErrorBanner } from '@/components/ErrorBanner';
 import { getConnections } from '@/api/tauri';
-import { Connection } from '@/types/netwatch';
+import { Connection, connectionSearchText } from '@/types/netwatch';
 import { logger } from '@/lib/logger';
 
 const ITEMS_PER_PAGE = 10;
@@ -17,6 +17,7 @@ export default function Connections() {
     search: '',
     protocol: 'all',
     state: 'all',
+    direction: 'all',
     hideLocalhost: false,
     onlyEstablished: false,
     risk: 'all',
@@ -54,10 +55,7 @@ export default function Connections() {
     
     if (searchQuery) {
       const query = searchQuery.toLowerCase();
-      result = result.filter(conn => {
-        const searchable = `${conn.processName} ${conn.pid} ${conn.localAddr}:${conn.localPort} ${conn.remoteAddr}:${conn.remotePort}`.toLowerCase();
-        return searchable.includes(query);
-      });
+      result = result.filter(conn => connectionSearchText(conn).includes(query));
     }
     
     return result;
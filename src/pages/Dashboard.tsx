@@ -4,13 +4,14 @@ import { SummaryCard } from '@/components/SummaryCard';
 import { ProcessesTable } from '@/components/ProcessesTable';
 import { RemotePortsList } from '@/components/RemotePortsList';
 import { RecentChangesList } from '@/components/RecentChangesList';
-import { getConnections } from '@/api/tauri';
-import { Connection } from '@/types/netwatch';
+import { getConnections, getTopTalkers } from '@/api/tauri';
+import { Connection, TopTalkers } from '@/types/netwatch';
 import { Network, Globe, Wifi, Radio } from 'lucide-react';
 
 export default function Dashboard() {
   const [, setSearchQuery] = useState('');
   const [connections, setConnections] = useState<Connection[]>([]);
+  const [talkers, setTalkers] = useState<TopTalkers | null>(null);
   const [loading, setLoading] = useState(true);
 
   useEffect(() => {
@@ -18,6 +19,7 @@ export default function Dashboard() {
       try {
         const fetchedConnections = await getConnections();
         setConnections(fetchedConnections);
+        setTalkers(await getTopTalkers('1m'));
       } catch (error) {
         console.error('Failed to fetch connections:', error);
       } finally {
@@ -30,9 +32,11 @@ export default function Dashboard() {
 
   const stats = useMemo(() => {
     const activeConnections = connections.length;
-    const uniqueRemoteIPs = new Set(connections.map(c => c.remoteAddr)).size;
+    // Unix sockets have paths rather than IPs, and raw and packet sockets have no ports
+    const inet = connections.filter(c => !c.unix && !c.raw);
+    const uniqueRemoteIPs = new Set(inet.map(c => c.remoteAddr)).size;
     const establishedTCP = connections.filter(c => c.protocol === 'TCP' && c.state === 'ESTABLISHED').length;
-    const listeningPorts = connections.filter(c => c.state === 'LISTENING').length;
+    const listeningPorts = inet.filter(c => c.state === 'LISTENING').length;
     
     return { activeConnections, uniqueRemoteIPs, establishedTCP, listeningPorts };
   }, [connections]);
@@ -52,10 +56,17 @@ export default function Dashboard() {
       }
     });
     
+    const throughputByPid = new Map<number, number>();
+    talkers?.processes.forEach(talker => {
+      const rate = talker.throughput.sendRate + talker.throughput.receiveRate;
+      throughputByPid.set(talker.pid, (throughputByPid.get(talker.pid) ?? 0) + rate);
+    });
+
+    // Busiest processes first; connection count breaks ties
     return Array.from(processMap.entries())
-      .map(([pid, data]) => ({ pid, ...data }))
-      .sort((a, b) => b.count - a.count);
-  }, [connections]);
+      .map(([pid, data]) => ({ pid, ...data, throughput: throughputByPid.get(pid) }))
+      .sort((a, b) => (b.throughput ?? 0) - (a.throughput ?? 0) || b.count - a.count);
+  }, [connections, talkers]);
 
   const portStats = useMemo(() => {
     const portMap = new Map<number, { protocol: string; count: number; maxRisk: 'low' | 'medium' | 'high' }>();
@@ -83,6 +94,7 @@ export default function Dashboard() {
       setLoading(true);
       const fetchedConnections = await getConnections();
       setConnections(fetchedConnections);
+      setTalkers(await getTopTalkers('1m'));
     } catch (error) {
       console.error('Failed to fetch connections:', error);
     } finally {
@@ -4,8 +4,8 @@ import { TopBar } from '@/components/TopBar';
 import { SummaryCard } from '@/components/SummaryCard';
 import { RiskBadge } from '@/components/RiskBadge';
 import { ConnectionsTable } from '@/components/ConnectionsTable';
-import { getConnections } from '@/api/tauri';
-import { Connection } from '@/types/netwatch';
+import { getConnections, getProcessDetails } from '@/api/tauri';
+import { Connection, ProcessDetails as ProcessInfo } from '@/types/netwatch';
 import { ArrowLeft, Copy, Download, Network, Globe, AlertTriangle } from 'lucide-react';
 
 export default function ProcessDetails() {
@@ -13,8 +13,16 @@ export default function ProcessDetails() {
   const navigate = useNavigate();
   const pidNumber = parseInt(pid || '0', 10);
   const [connections, setConnections] = useState<Connection[]>([]);
+  const [details, setDetails] = useState<ProcessInfo | null>(null);
   const [loading, setLoading] = useState(true);
 
+  useEffect(() => {
+    // Only available on Linux, and only while the process is running
+    getProcessDetails(pidNumber)
+      .then(setDetails)
+      .catch(() => setDetails(null));
+  }, [pidNumber]);
+
   useEffect(() => {
     const fetchData = async () => {
       try {
@@ -142,6 +150,11 @@ export default function ProcessDetails() {
             <span className="px-2.5 py-1 bg-secondary text-secondary-foreground rounded-md text-sm font-mono">
               PID: {pidNumber}
             </span>
+            {details?.systemdUnit && (
+              <span className="px-2.5 py-1 bg-secondary text-secondary-foreground rounded-md text-sm font-mono">
+                {details.systemdUnit.userId !== null ? `user ${details.systemdUnit.userId}: ` : ''}{details.systemdUnit.name}
+              </span>
+            )}
           </div>
           <div className="flex items-center gap-2">
             <button className="h-9 px-4 bg-secondary hover:bg-secondary/80 text-secondary-foreground rounded-lg text-sm font-medium flex items-center gap-2 transition-colors">
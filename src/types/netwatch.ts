@@ -1,20 +1,354 @@
+export type AddressClass =
+  | 'loopback'
+  | 'private'
+  | 'cgnat'
+  | 'linkLocal'
+  | 'multicast'
+  | 'broadcast'
+  | 'reserved'
+  | 'unspecified'
+  | 'public';
+
+export type ConnectionDirection = 'inbound' | 'outbound' | 'listening' | 'unknown';
+
+export type ConnectionSource = 'live' | 'capture';
+
+// Packet and byte counters for connections rebuilt from a capture file
+export interface FlowStats {
+  firstSeen: number; // Unix timestamp in milliseconds
+  lastSeen: number;
+  packetsSent: number;
+  packetsReceived: number;
+  bytesSent: number;
+  bytesReceived: number;
+}
+
+// Kernel TCP statistics (Linux tcp_info); rates need two polls
+export interface TcpMetrics {
+  bytesAcked: number;
+  bytesReceived: number;
+  rttUs: number;
+  rttVarUs: number;
+  retransmits: number;
+  congestionWindow: number;
+  sendRate: number | null; // bytes per second since the previous poll
+  receiveRate: number | null;
+}
+
+// Parsed from the TLS ClientHello of a captured flow
+export interface TlsInfo {
+  sni: string | null;
+  alpn: string[];
+  version: string;
+  ja3: string;
+  ja3Hash: string;
+  ja4: string;
+}
+
+export type ContainerRuntime = 'docker' | 'containerd' | 'podman' | 'crio' | 'unknown';
+
+export interface ContainerInfo {
+  id: string;
+  runtime: ContainerRuntime;
+  name: string | null; // null when the runtime API could not be reached
+  image: string | null;
+  podUid: string | null; // Kubernetes pod
+}
+
+export interface SystemdUnit {
+  name: string; // e.g. "nginx.service" or "session-2.scope"
+  slice: string | null;
+  userId: number | null; // owner of the per-user manager, for user units
+}
+
+export interface ProcessDetails {
+  pid: number;
+  name: string;
+  executable: string | null;
+  commandLine: string[];
+  parentPid: number | null;
+  userId: number | null;
+  systemdUnit: SystemdUnit | null;
+  container: ContainerInfo | null;
+}
+
+export interface NetworkNamespace {
+  id: number;
+  name: string; // "host", the `ip netns` name, or the first process inside
+  isHost: boolean;
+}
+
+export interface Neighbor {
+  ipAddress: string;
+  macAddress: string | null; // null while resolution is incomplete or has failed
+  vendor: string | null;
+  interface: string;
+  state: string; // "reachable", "stale", "failed", ...
+  isRouter: boolean;
+}
+
+export type AlertKind = 'gatewayMacChanged' | 'sharedMac' | 'macFlapping' | 'beaconing' | 'novelConnection' | 'newListener' | 'listenerOwnerChanged';
+
+export interface Alert {
+  id: string;
+  kind: AlertKind;
+  severity: 'low' | 'medium' | 'high';
+  raisedAt: number; // Unix timestamp in milliseconds
+  message: string;
+  details: string[];
+}
+
+export interface EgressRoute {
+  interface: string;
+  gateway: string | null; // null for directly attached networks
+  destination: string; // matched prefix, e.g. "0.0.0.0/0"
+  table: number | null; // routing table the policy rules picked; 254 is main
+}
+
 export interface Connection {
   id: string;
   processName: string;
   pid: number;
-  protocol: 'TCP' | 'UDP';
+  namespace: NetworkNamespace | null; // Linux network namespace the socket lives in
+  container: ContainerInfo | null; // container the owning process runs in
+  systemdUnit: SystemdUnit | null; // Linux only
+  protocol: 'TCP' | 'UDP' | 'UNIX' | 'RAW' | 'ICMP' | 'PACKET'; // ICMP: unprivileged ping sockets
   localAddr: string;
   localPort: number;
+  localService: string | null;
+  interface: string | null; // interface that owns the local address
+  route: EgressRoute | null; // where traffic to the remote leaves the host (Linux)
   remoteAddr: string;
   remotePort: number;
+  remoteService: string | null;
+  remoteClass: AddressClass;
+  neighbor: Neighbor | null; // neighbor table entry when the remote is on a local link
+  hostname: string | null; // reverse DNS (PTR) name of the remote address
+  resolvedName: string | null; // name the client looked up, from captured DNS answers
+  direction: ConnectionDirection;
   state: string;
   risk: 'low' | 'medium' | 'high';
   riskReasons: string[];
   capturedAt: number; // Unix timestamp in milliseconds
+  source: ConnectionSource;
+  flow: FlowStats | null;
+  tls: TlsInfo | null;
+  tcpMetrics: TcpMetrics | null;
+  unix: UnixSocketInfo | null; // only for protocol UNIX
+  raw: RawSocketInfo | null; // only for protocols RAW, ICMP and PACKET
+}
+
+// Lowercased text the search boxes match a connection against
+export function connectionSearchText(conn: Connection): string {
+  return `${conn.processName} ${conn.pid} ${conn.namespace?.name ?? ''} ${conn.container?.name ?? ''} ${conn.container?.image ?? ''} ${conn.container?.id ?? ''} ${conn.systemdUnit?.name ?? ''} ${conn.localAddr}:${conn.localPort} ${conn.remoteAddr}:${conn.remotePort} ${conn.localService ?? ''} ${conn.interface ?? ''} ${conn.route?.interface ?? ''} ${conn.neighbor?.macAddress ?? ''} ${conn.neighbor?.vendor ?? ''} ${conn.remoteService ?? ''} ${conn.hostname ?? ''} ${conn.resolvedName ?? ''} ${conn.tls?.sni ?? ''} ${conn.tls?.ja3Hash ?? ''} ${conn.tls?.ja4 ?? ''} ${conn.unix?.peerProcess ?? ''}`.toLowerCase();
+}
+
+// Raw, ICMP and packet sockets have a protocol instead of ports
+export interface RawSocketInfo {
+  protocol: string; // IP protocol, or EtherType for packet sockets ("all" for sniffers)
+  interface: string | null; // packet sockets bound to a single interface
+}
+
+// A unix socket's path is its localAddr and the peer's path its remoteAddr;
+// abstract names start with '@' and unnamed sockets have an empty address
+export interface UnixSocketInfo {
+  socketType: 'stream' | 'dgram' | 'seqpacket' | 'unknown';
+  inode: number;
+  peerInode: number | null;
+  peerPid: number | null;
+  peerProcess: string | null;
+}
+
+export type InterfaceKind = 'ethernet' | 'wifi' | 'loopback' | 'tun' | 'wireguard' | 'bridge' | 'veth' | 'vlan' | 'bond' | 'other';
+
+export interface InterfaceAddress {
+  address: string;
+  prefixLen: number;
+}
+
+export interface InterfaceCounters {
+  rxBytes: number;
+  rxPackets: number;
+  rxErrors: number;
+  rxDropped: number;
+  txBytes: number;
+  txPackets: number;
+  txErrors: number;
+  txDropped: number;
+}
+
+export interface NetworkInterface {
+  name: string;
+  index: number;
+  kind: InterfaceKind;
+  macAddress: string | null;
+  mtu: number;
+  operState: string;
+  addresses: InterfaceAddress[];
+  counters: InterfaceCounters;
+  rates: InterfaceCounters | null; // per second since the previous query; null on the first
+}
+
+export type TalkerWindow = '1m' | '5m' | '1h';
+
+export interface Throughput {
+  bytesSent: number;
+  bytesReceived: number;
+  sendRate: number; // bytes per second
+  receiveRate: number;
+}
+
+export interface ProcessTalker {
+  pid: number;
+  processName: string;
+  throughput: Throughput;
+}
+
+export interface UnitTalker {
+  unit: string;
+  throughput: Throughput;
+}
+
+export interface HostTalker {
+  remoteAddr: string;
+  hostname: string | null;
+  throughput: Throughput;
+}
+
+export interface TopTalkers {
+  window: TalkerWindow;
+  generatedAt: number; // Unix timestamp in milliseconds
+  observedSecs: number;
+  processes: ProcessTalker[];
+  units: UnitTalker[]; // Linux only
+  remoteHosts: HostTalker[];
+}
+
+export interface CaptureDiagnostics {
+  available: boolean; // built with the `capture` feature on a supported OS
+  hasPrivileges: boolean;
+  running: boolean;
+  interface: string | null;
+  startedAt: number | null; // Unix timestamp in milliseconds
+  packetsCaptured: number;
+  packetsDropped: number;
+  flowsTracked: number;
+  dnsAddresses: number;
+  lastError: string | null;
+}
+
+export type Exposure = 'allInterfaces' | 'specificAddress' | 'namespaceOnly' | 'loopbackOnly';
+
+export interface ExposureEntry {
+  protocol: string;
+  localAddr: string;
+  localPort: number;
+  exposure: Exposure;
+  service: string | null;
+  risk: 'low' | 'medium' | 'high';
+  riskReasons: string[];
+}
+
+export interface ProcessExposure {
+  pid: number;
+  processName: string;
+  entries: ExposureEntry[];
+}
+
+export interface ExposureReport {
+  generatedAt: number; // Unix timestamp in milliseconds
+  processes: ProcessExposure[];
 }
 
 export interface ExportFormat {
-  format: 'json' | 'csv';
+  format: 'json' | 'csv' | 'exposure';
   filePath?: string;
   error?: string;
-}
\ No newline at end of file
+}
+
+export interface ReverseDnsSettings {
+  enabled: boolean;
+  server: string | null; // null uses the system resolver
+  maxConcurrentLookups: number;
+  timeoutMs: number;
+  positiveTtlSecs: number;
+  negativeTtlSecs: number;
+  maxCacheEntries: number;
+}
+
+export interface HistorySettings {
+  enabled: boolean;
+  retentionDays: number;
+  maxSizeMb: number; // oldest history is dropped past this
+}
+
+// Without live capture, connects closing between polls are missed and periods are only accurate to the refresh interval
+export interface BeaconingSettings {
+  enabled: boolean;
+  windowHours: number; // history looked at by each analysis
+  minConnections: number;
+  maxVariation: number; // largest jitter / period still counted as periodic
+  minPeriodSecs: number;
+  maxPeriodSecs: number;
+}
+
+export interface BaselineSettings {
+  learningWindowHours: number;
+  ipv4PrefixLen: number; // peers without a known ASN are grouped into blocks of these sizes; a baseline keeps the sizes it was learned with
+  ipv6PrefixLen: number;
+}
+
+export interface Settings {
+  reverseDns: ReverseDnsSettings;
+  history: HistorySettings;
+  beaconing: BeaconingSettings;
+  baseline: BaselineSettings;
+}
+
+export type BaselineState = 'none' | 'learning' | 'enforcing';
+
+export interface BaselineStatus {
+  state: BaselineState;
+  learningStartedAt: number | null; // Unix ms
+  learningEndsAt: number | null;
+  tuples: number;
+}
+
+// A span of polls during which a socket existed unchanged; a new state or risk starts a new interval
+export interface ConnectionInterval {
+  id: number;
+  processName: string;
+  pid: number;
+  namespace: string | null;
+  container: string | null;
+  systemdUnit: string | null;
+  protocol: string;
+  localAddr: string;
+  localPort: number;
+  remoteAddr: string;
+  remotePort: number;
+  hostname: string | null;
+  direction: ConnectionDirection;
+  state: string;
+  risk: 'low' | 'medium' | 'high';
+  riskReasons: string[];
+  firstSeen: number; // Unix ms
+  lastSeen: number | null; // null while still open
+}
+
+// The connections the last poll at or before `at` saw
+export interface HistorySnapshot {
+  at: number;
+  polledAt: number | null; // null when nothing was recorded before `at`
+  connections: ConnectionInterval[];
+}
+
+export interface HistoryFilter {
+  process?: string;
+  pid?: number;
+  protocol?: string;
+  remote?: string; // address or hostname
+  port?: number; // local or remote
+  minRisk?: 'low' | 'medium' | 'high';
+  limit?: number;
+}